@@ -0,0 +1,95 @@
+//! Charger backends
+//!
+//! A backend encapsulates the register semantics of a wallbox model on top of
+//! a [`ModbusLike`] connection: reading measurements, status and identity and
+//! writing the charge current and phase count. [`AlfenBackend`] covers Alfen
+//! NG9xx stations; [`ProfileBackend`] takes register addresses, data types and
+//! status tables from a YAML profile so other Modbus wallboxes can run with
+//! the same control logic.
+
+use crate::config::Config;
+use crate::driver::modbus_like::ModbusLike;
+use crate::error::{PhaetonError, Result};
+
+mod alfen;
+mod profile;
+
+pub use alfen::AlfenBackend;
+pub use profile::{
+    ChargerProfile, DataType, PhasesSpec, ProfileBackend, RegisterSpec, StatusSpec, WordOrder,
+};
+
+/// Per-phase and total measurements in charger phase order; `None` when
+/// the charger does not provide a value
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChargerMeasurements {
+    pub voltages: [Option<f64>; 3],
+    pub currents: [Option<f64>; 3],
+    pub powers: [Option<f64>; 3],
+    pub total_power: Option<f64>,
+    pub energy_kwh: Option<f64>,
+}
+
+/// Charger status as reported and mapped to the base Victron status
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChargerStatus {
+    /// Raw status (e.g. Mode 3 string or numeric code)
+    pub raw: String,
+    /// 0 = Disconnected, 1 = Connected, 2 = Charging
+    pub code: u8,
+}
+
+/// Product identification; empty strings when unavailable
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChargerIdentity {
+    pub manufacturer: String,
+    pub firmware: String,
+    pub serial: String,
+    /// Hardware maximum current (A)
+    pub max_current: Option<f32>,
+}
+
+/// Register-level access to one charger model
+#[async_trait::async_trait]
+pub trait ChargerBackend: Send + Sync {
+    /// Short backend name for logs, e.g. "alfen" or the profile name
+    fn name(&self) -> &str;
+
+    /// Whether the Alfen-specific registers (meter health and telemetry,
+    /// temperature, availability, clock, setpoint validity, SCN) exist
+    fn alfen_extensions(&self) -> bool {
+        false
+    }
+
+    /// Measurements of backends without `alfen_extensions`. The driver reads
+    /// Alfen stations itself: one coalesced request also covers the meter
+    /// header and telemetry block, which this interface cannot return.
+    async fn read_measurements(&self, _modbus: &mut dyn ModbusLike) -> Result<ChargerMeasurements> {
+        Err(PhaetonError::modbus(format!(
+            "{} backend has no generic measurement read",
+            self.name()
+        )))
+    }
+
+    async fn read_status(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerStatus>;
+
+    async fn read_identity(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerIdentity>;
+
+    async fn write_current(&self, modbus: &mut dyn ModbusLike, amps: f32) -> Result<()>;
+
+    async fn write_phases(&self, modbus: &mut dyn ModbusLike, phases: u8) -> Result<()>;
+}
+
+/// Build the backend selected by `charger.backend`
+pub fn from_config(config: &Config) -> Result<Box<dyn ChargerBackend>> {
+    match config.charger.backend.to_lowercase().as_str() {
+        "alfen" => Ok(Box::new(AlfenBackend::new(config))),
+        "profile" => Ok(Box::new(ProfileBackend::from_file(
+            &config.charger.profile,
+        )?)),
+        _ => Err(PhaetonError::validation(
+            "charger.backend",
+            "Must be 'alfen' or 'profile'",
+        )),
+    }
+}
@@ -0,0 +1,102 @@
+//! Alfen NG9xx backend: FLOAT32 big-endian registers, Mode 3 status strings,
+//! socket slave 1 and station slave 200. Measurements are read by the driver
+//! (`driver::runtime_poll::io`) together with the meter header in one request.
+
+use super::{ChargerBackend, ChargerIdentity, ChargerStatus};
+use crate::config::{Config, RegistersConfig};
+use crate::driver::modbus_like::ModbusLike;
+use crate::error::Result;
+
+pub struct AlfenBackend {
+    registers: RegistersConfig,
+    socket_slave_id: u8,
+    station_slave_id: u8,
+}
+
+impl AlfenBackend {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            registers: config.registers.clone(),
+            socket_slave_id: config.modbus.socket_slave_id,
+            station_slave_id: config.modbus.station_slave_id,
+        }
+    }
+
+    async fn read_station_string(
+        &self,
+        modbus: &mut dyn ModbusLike,
+        address: u16,
+        count: u16,
+    ) -> String {
+        modbus
+            .read_holding_registers(self.station_slave_id, address, count)
+            .await
+            .ok()
+            .and_then(|regs| crate::modbus::decode_string(&regs, None).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl ChargerBackend for AlfenBackend {
+    fn name(&self) -> &str {
+        "alfen"
+    }
+
+    fn alfen_extensions(&self) -> bool {
+        true
+    }
+
+    async fn read_status(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerStatus> {
+        let regs = modbus
+            .read_holding_registers(self.socket_slave_id, self.registers.status, 5)
+            .await?;
+        let raw = crate::modbus::decode_string(&regs, None).unwrap_or_default();
+        Ok(ChargerStatus {
+            code: crate::driver::AlfenDriver::map_alfen_status_to_victron(&raw),
+            raw,
+        })
+    }
+
+    async fn read_identity(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerIdentity> {
+        let r = &self.registers;
+        let manufacturer = self
+            .read_station_string(modbus, r.manufacturer, r.manufacturer_count)
+            .await;
+        let firmware = self
+            .read_station_string(modbus, r.firmware_version, r.firmware_version_count)
+            .await;
+        let serial = self
+            .read_station_string(modbus, r.station_serial, r.station_serial_count)
+            .await;
+        let max_current = modbus
+            .read_holding_registers(self.station_slave_id, r.station_max_current, 2)
+            .await
+            .ok()
+            .and_then(|regs| crate::modbus::decode_32bit_float_opt(&regs))
+            .filter(|v| v.is_finite() && *v > 0.0);
+        Ok(ChargerIdentity {
+            manufacturer,
+            firmware,
+            serial,
+            max_current,
+        })
+    }
+
+    async fn write_current(&self, modbus: &mut dyn ModbusLike, amps: f32) -> Result<()> {
+        let regs = crate::modbus::encode_32bit_float(amps);
+        modbus
+            .write_multiple_registers(self.socket_slave_id, self.registers.amps_config, &regs)
+            .await
+    }
+
+    // 1215 belongs to the per-socket block (1200..1215) of the socket slave,
+    // like the max current; writing it to the station slave would switch
+    // both sockets of a dual-socket station
+    async fn write_phases(&self, modbus: &mut dyn ModbusLike, phases: u8) -> Result<()> {
+        let value: u16 = if phases >= 3 { 3 } else { 1 };
+        modbus
+            .write_multiple_registers(self.socket_slave_id, self.registers.phases, &[value])
+            .await
+    }
+}
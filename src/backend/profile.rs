@@ -0,0 +1,446 @@
+//! Data-driven backend for generic Modbus wallboxes
+//!
+//! A YAML profile describes where a charger keeps its measurements, status,
+//! identity and setpoint registers, how each value is encoded (integer or
+//! float width, word order, scaling) and how raw status values map to the
+//! base Victron status. See `profiles/example_wallbox.yaml`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ChargerBackend, ChargerIdentity, ChargerMeasurements, ChargerStatus};
+use crate::driver::modbus_like::ModbusLike;
+use crate::error::{PhaetonError, Result};
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_slave_id() -> u8 {
+    1
+}
+
+fn default_one_phase() -> u16 {
+    1
+}
+
+fn default_three_phase() -> u16 {
+    3
+}
+
+/// Register data type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    #[default]
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+    F64,
+    String,
+}
+
+/// Order of the 16-bit words of multi-register values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordOrder {
+    /// Most significant word first (Modbus convention)
+    #[default]
+    Big,
+    /// Least significant word first
+    Little,
+}
+
+/// Location and encoding of a single value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisterSpec {
+    pub address: u16,
+    #[serde(rename = "type", default)]
+    pub data_type: DataType,
+    /// Number of registers of a string value
+    #[serde(default)]
+    pub count: Option<u16>,
+    /// Overrides the profile word order
+    #[serde(default)]
+    pub word_order: Option<WordOrder>,
+    /// Raw value times scale gives A, V, W or kWh
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Overrides the profile slave ID
+    #[serde(default)]
+    pub slave_id: Option<u8>,
+}
+
+impl RegisterSpec {
+    /// Number of registers the value occupies
+    pub fn register_count(&self) -> u16 {
+        match self.data_type {
+            DataType::U16 | DataType::I16 => 1,
+            DataType::U32 | DataType::I32 | DataType::F32 => 2,
+            DataType::F64 => 4,
+            DataType::String => self.count.unwrap_or(1),
+        }
+    }
+
+    /// Words in most-significant-first order
+    fn words(&self, regs: &[u16], order: WordOrder) -> Option<Vec<u16>> {
+        let mut words = regs.get(..self.register_count() as usize)?.to_vec();
+        if self.word_order.unwrap_or(order) == WordOrder::Little {
+            words.reverse();
+        }
+        Some(words)
+    }
+
+    /// Unscaled numeric value; `None` for strings, short reads and NaN
+    fn decode_raw(&self, regs: &[u16], order: WordOrder) -> Option<f64> {
+        let w = self.words(regs, order)?;
+        let u32_at = || (u32::from(w[0]) << 16) | u32::from(w[1]);
+        match self.data_type {
+            DataType::U16 => Some(f64::from(w[0])),
+            DataType::I16 => Some(f64::from(w[0] as i16)),
+            DataType::U32 => Some(f64::from(u32_at())),
+            DataType::I32 => Some(f64::from(u32_at() as i32)),
+            DataType::F32 => crate::modbus::decode_32bit_float_opt(&w).map(f64::from),
+            DataType::F64 => crate::modbus::decode_64bit_float_opt(&w),
+            DataType::String => None,
+        }
+    }
+
+    /// Scaled numeric value
+    pub fn decode(&self, regs: &[u16], order: WordOrder) -> Option<f64> {
+        self.decode_raw(regs, order).map(|v| v * self.scale)
+    }
+
+    /// Registers for a scaled value; empty for strings
+    pub fn encode(&self, value: f64, order: WordOrder) -> Vec<u16> {
+        let raw = if self.scale != 0.0 {
+            value / self.scale
+        } else {
+            value
+        };
+        let mut words: Vec<u16> = match self.data_type {
+            DataType::U16 => vec![raw.round().clamp(0.0, f64::from(u16::MAX)) as u16],
+            DataType::I16 => {
+                vec![raw.round().clamp(f64::from(i16::MIN), f64::from(i16::MAX)) as i16 as u16]
+            }
+            DataType::U32 | DataType::I32 => {
+                let v = if self.data_type == DataType::U32 {
+                    raw.round().clamp(0.0, f64::from(u32::MAX)) as u32
+                } else {
+                    raw.round().clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32 as u32
+                };
+                vec![(v >> 16) as u16, v as u16]
+            }
+            DataType::F32 => crate::modbus::encode_32bit_float(raw as f32).to_vec(),
+            DataType::F64 => crate::modbus::encode_64bit_float(raw).to_vec(),
+            DataType::String => Vec::new(),
+        };
+        if self.word_order.unwrap_or(order) == WordOrder::Little {
+            words.reverse();
+        }
+        words
+    }
+}
+
+/// Status register and its value table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusSpec {
+    pub register: RegisterSpec,
+    /// Raw status (number or string) to 0 = Disconnected, 1 = Connected,
+    /// 2 = Charging; unlisted values count as Disconnected
+    pub values: HashMap<serde_yaml::Value, u8>,
+}
+
+/// Phase switching register
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhasesSpec {
+    pub register: RegisterSpec,
+    #[serde(default = "default_one_phase")]
+    pub one_phase: u16,
+    #[serde(default = "default_three_phase")]
+    pub three_phase: u16,
+}
+
+/// Measurement registers; per-phase lists are in charger phase order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeasurementsSpec {
+    pub voltages: Vec<RegisterSpec>,
+    pub currents: Vec<RegisterSpec>,
+    pub powers: Vec<RegisterSpec>,
+    pub total_power: Option<RegisterSpec>,
+    /// Total energy counter, scaled to kWh
+    pub energy: Option<RegisterSpec>,
+}
+
+/// Identification registers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdentitySpec {
+    /// Fixed manufacturer name
+    pub manufacturer: Option<String>,
+    pub firmware: Option<RegisterSpec>,
+    pub serial: Option<RegisterSpec>,
+    pub max_current: Option<RegisterSpec>,
+}
+
+/// Register map of a wallbox model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChargerProfile {
+    pub name: String,
+    #[serde(default = "default_slave_id")]
+    pub slave_id: u8,
+    /// Default word order of multi-register values
+    #[serde(default)]
+    pub word_order: WordOrder,
+    #[serde(default)]
+    pub measurements: MeasurementsSpec,
+    pub status: StatusSpec,
+    #[serde(default)]
+    pub identity: IdentitySpec,
+    /// Charge current setpoint register (A after scaling)
+    pub current: RegisterSpec,
+    /// Phase switching register; chargers without it stay on their wiring
+    #[serde(default)]
+    pub phases: Option<PhasesSpec>,
+}
+
+impl ChargerProfile {
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let profile: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| PhaetonError::config(format!("Invalid charger profile: {}", e)))?;
+        if let Some(code) = profile.status.values.values().find(|c| **c > 2) {
+            return Err(PhaetonError::config(format!(
+                "Invalid charger profile: status code {} is not 0, 1 or 2",
+                code
+            )));
+        }
+        Ok(profile)
+    }
+}
+
+pub struct ProfileBackend {
+    profile: ChargerProfile,
+}
+
+impl ProfileBackend {
+    pub fn new(profile: ChargerProfile) -> Self {
+        Self { profile }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        if path.is_empty() {
+            return Err(PhaetonError::validation(
+                "charger.profile",
+                "Profile path is required for the profile backend",
+            ));
+        }
+        let yaml = std::fs::read_to_string(path).map_err(|e| {
+            PhaetonError::config(format!("Cannot read charger profile {}: {}", path, e))
+        })?;
+        Ok(Self::new(ChargerProfile::from_yaml(&yaml)?))
+    }
+
+    async fn read_regs(
+        &self,
+        modbus: &mut dyn ModbusLike,
+        spec: &RegisterSpec,
+    ) -> Result<Vec<u16>> {
+        let slave = spec.slave_id.unwrap_or(self.profile.slave_id);
+        modbus
+            .read_holding_registers(slave, spec.address, spec.register_count())
+            .await
+    }
+
+    async fn read_value(
+        &self,
+        modbus: &mut dyn ModbusLike,
+        spec: Option<&RegisterSpec>,
+    ) -> Option<f64> {
+        let spec = spec?;
+        let regs = self.read_regs(modbus, spec).await.ok()?;
+        spec.decode(&regs, self.profile.word_order)
+    }
+
+    async fn read_phases(
+        &self,
+        modbus: &mut dyn ModbusLike,
+        specs: &[RegisterSpec],
+    ) -> [Option<f64>; 3] {
+        let mut out = [None; 3];
+        for (slot, spec) in out.iter_mut().zip(specs) {
+            *slot = self.read_value(modbus, Some(spec)).await;
+        }
+        out
+    }
+
+    async fn read_text(&self, modbus: &mut dyn ModbusLike, spec: Option<&RegisterSpec>) -> String {
+        let Some(spec) = spec else {
+            return String::new();
+        };
+        let Ok(regs) = self.read_regs(modbus, spec).await else {
+            return String::new();
+        };
+        match spec.data_type {
+            DataType::String => crate::modbus::decode_string(&regs, None).unwrap_or_default(),
+            _ => spec
+                .decode_raw(&regs, self.profile.word_order)
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn write_spec(
+        &self,
+        modbus: &mut dyn ModbusLike,
+        spec: &RegisterSpec,
+        value: f64,
+    ) -> Result<()> {
+        let slave = spec.slave_id.unwrap_or(self.profile.slave_id);
+        let regs = spec.encode(value, self.profile.word_order);
+        modbus
+            .write_multiple_registers(slave, spec.address, &regs)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl ChargerBackend for ProfileBackend {
+    fn name(&self) -> &str {
+        &self.profile.name
+    }
+
+    async fn read_measurements(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerMeasurements> {
+        let m = &self.profile.measurements;
+        Ok(ChargerMeasurements {
+            voltages: self.read_phases(modbus, &m.voltages).await,
+            currents: self.read_phases(modbus, &m.currents).await,
+            powers: self.read_phases(modbus, &m.powers).await,
+            total_power: self.read_value(modbus, m.total_power.as_ref()).await,
+            energy_kwh: self.read_value(modbus, m.energy.as_ref()).await,
+        })
+    }
+
+    async fn read_status(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerStatus> {
+        let spec = &self.profile.status.register;
+        let regs = self.read_regs(modbus, spec).await?;
+        let (raw, key) = if spec.data_type == DataType::String {
+            let s = crate::modbus::decode_string(&regs, None).unwrap_or_default();
+            (s.clone(), serde_yaml::Value::String(s))
+        } else {
+            let v = spec
+                .decode_raw(&regs, self.profile.word_order)
+                .ok_or_else(|| PhaetonError::modbus("Status register unavailable"))?
+                as i64;
+            (v.to_string(), serde_yaml::Value::Number(v.into()))
+        };
+        let code = self.profile.status.values.get(&key).copied().unwrap_or(0);
+        Ok(ChargerStatus { raw, code })
+    }
+
+    async fn read_identity(&self, modbus: &mut dyn ModbusLike) -> Result<ChargerIdentity> {
+        let id = &self.profile.identity;
+        Ok(ChargerIdentity {
+            manufacturer: id.manufacturer.clone().unwrap_or_default(),
+            firmware: self.read_text(modbus, id.firmware.as_ref()).await,
+            serial: self.read_text(modbus, id.serial.as_ref()).await,
+            max_current: self
+                .read_value(modbus, id.max_current.as_ref())
+                .await
+                .map(|a| a as f32)
+                .filter(|a| *a > 0.0),
+        })
+    }
+
+    async fn write_current(&self, modbus: &mut dyn ModbusLike, amps: f32) -> Result<()> {
+        self.write_spec(modbus, &self.profile.current, f64::from(amps))
+            .await
+    }
+
+    async fn write_phases(&self, modbus: &mut dyn ModbusLike, phases: u8) -> Result<()> {
+        let Some(p) = self.profile.phases.as_ref() else {
+            return Err(PhaetonError::modbus(format!(
+                "Charger profile '{}' does not support phase switching",
+                self.profile.name
+            )));
+        };
+        let value = if phases >= 3 {
+            p.three_phase
+        } else {
+            p.one_phase
+        };
+        // The phase value is written unscaled
+        let spec = RegisterSpec {
+            scale: 1.0,
+            ..p.register.clone()
+        };
+        self.write_spec(modbus, &spec, f64::from(value)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(data_type: DataType, scale: f64) -> RegisterSpec {
+        RegisterSpec {
+            address: 0,
+            data_type,
+            count: None,
+            word_order: None,
+            scale,
+            slave_id: None,
+        }
+    }
+
+    #[test]
+    fn decodes_and_encodes_types_with_scaling_and_word_order() {
+        let u32_spec = spec(DataType::U32, 0.001);
+        assert_eq!(
+            u32_spec.decode(&[0x0001, 0x86A0], WordOrder::Big),
+            Some(100.0)
+        );
+        assert_eq!(
+            u32_spec.decode(&[0x86A0, 0x0001], WordOrder::Little),
+            Some(100.0)
+        );
+        assert_eq!(
+            spec(DataType::I16, 0.1).decode(&[0xFF9C], WordOrder::Big),
+            Some(-10.0)
+        );
+        assert_eq!(
+            spec(DataType::F32, 1.0).decode(&[0xFFFF, 0xFFFF], WordOrder::Big),
+            None
+        );
+        assert_eq!(spec(DataType::U16, 1.0).decode(&[], WordOrder::Big), None);
+
+        assert_eq!(
+            spec(DataType::U16, 0.1).encode(16.0, WordOrder::Big),
+            vec![160]
+        );
+        assert_eq!(
+            spec(DataType::I32, 1.0).encode(-2.0, WordOrder::Little),
+            vec![0xFFFE, 0xFFFF]
+        );
+        let f = spec(DataType::F32, 1.0);
+        assert_eq!(
+            f.decode(&f.encode(13.5, WordOrder::Big), WordOrder::Big),
+            Some(13.5)
+        );
+    }
+
+    #[test]
+    fn rejects_status_codes_outside_base_range() {
+        let yaml = r#"
+name: bad
+status:
+  register: {address: 1}
+  values: {0: 0, 1: 7}
+current: {address: 2}
+"#;
+        assert!(ChargerProfile::from_yaml(yaml).is_err());
+    }
+}
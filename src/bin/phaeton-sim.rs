@@ -0,0 +1,105 @@
+use anyhow::Result;
+use phaeton::simulator::{self, SimCommand, Simulator, SimulatorConfig};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpListener;
+
+const USAGE: &str = "Usage: phaeton-sim [options]
+
+Simulates an Alfen NG9xx charging station over Modbus TCP.
+
+  --bind <addr>           Listen address (default 127.0.0.1:5020)
+  --sockets <1|2>         Number of sockets (default 1)
+  --station-max <A>       Station active max current (default 32)
+  --safe-current <A>      Safe current after validity expiry (default 6)
+  --validity <s>          Max current validity time (default 60)
+  --car-max <A>           Max current accepted by cars (default 16)
+  --lag <s>               Car response time constant (default 2)
+  --scn                   Enable SCN max currents and disable socket max currents
+  --script <file>         Run scripted events from a file
+  --help, -h              Show this help
+
+Commands (from --script or stdin): plug [socket] [amps], unplug [socket],
+full [socket], resume [socket], fault [socket], temp <celsius>, wait <s>";
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(v)) => v,
+        _ => {
+            eprintln!(
+                "Error: {} requires a valid value\nTry --help for usage.",
+                flag
+            );
+            std::process::exit(2);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut cfg = SimulatorConfig::default();
+    let mut bind = "127.0.0.1:5020".to_string();
+    let mut script: Option<String> = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            "--bind" => bind = parse_value(&arg, args.next()),
+            "--sockets" => cfg.sockets = parse_value::<u8>(&arg, args.next()).clamp(1, 2),
+            "--station-max" => cfg.station_max_current = parse_value(&arg, args.next()),
+            "--safe-current" => cfg.safe_current = parse_value(&arg, args.next()),
+            "--validity" => cfg.validity_seconds = parse_value(&arg, args.next()),
+            "--car-max" => cfg.car_max_current = parse_value(&arg, args.next()),
+            "--lag" => cfg.car_lag_seconds = parse_value(&arg, args.next()),
+            "--scn" => {
+                cfg.scn_control = true;
+                cfg.socket_control = false;
+            }
+            "--script" => script = Some(parse_value(&arg, args.next())),
+            other => eprintln!("Unknown argument ignored: {}", other),
+        }
+    }
+
+    let _ = phaeton::logging::init_logging(&phaeton::config::LoggingConfig::default());
+    let sim = Simulator::new(cfg);
+    let listener = TcpListener::bind(&bind).await?;
+    println!("phaeton-sim listening on {}", listener.local_addr()?);
+
+    if let Some(path) = script {
+        let text = std::fs::read_to_string(&path)?;
+        let mut commands: Vec<SimCommand> = Vec::new();
+        for line in text.lines() {
+            if let Some(cmd) = simulator::parse_command(line)? {
+                commands.push(cmd);
+            }
+        }
+        let sim = sim.clone();
+        tokio::spawn(async move {
+            if let Err(e) = simulator::run_script(&sim, &commands).await {
+                eprintln!("Script failed: {}", e);
+            }
+        });
+    }
+
+    // Interactive commands on stdin
+    let stdin_sim = sim.clone();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            match simulator::parse_command(&line) {
+                Ok(Some(SimCommand::Wait(_))) | Ok(None) => {}
+                Ok(Some(cmd)) => match stdin_sim.apply(&cmd) {
+                    Ok(()) => println!("ok: {:?}", cmd),
+                    Err(e) => eprintln!("error: {}", e),
+                },
+                Err(e) => eprintln!("error: {}", e),
+            }
+        }
+    });
+
+    simulator::serve(listener, sim).await?;
+    Ok(())
+}
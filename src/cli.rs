@@ -0,0 +1,223 @@
+//! `phaeton modbus read|write` subcommands
+//!
+//! The commands call the register inspector API of the running instance
+//! (`/api/modbus/*` on `web.host`/`web.port`), so they use its Modbus
+//! connection instead of taking one of the station's two master slots. The
+//! token comes from `web.api_token` or the `PHAETON_API_TOKEN` environment
+//! variable.
+
+use crate::config::Config;
+use crate::error::{PhaetonError, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+pub const MODBUS_USAGE: &str = "Usage:\n  phaeton modbus read <address> [count] [--type <t>] [--slave <id>] [--socket <n>]\n  phaeton modbus write <address> <value>[,<value>...] [--type <t>] [--slave <id>] [--socket <n>]\n\nTypes: u16 (default), i16, u32, u64, f32, f64, string. Writes are limited to\nthe max current (1210), phases (1215) and SCN max current (1417-1422) registers.";
+
+/// A parsed `phaeton modbus` command
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModbusCommand {
+    Read {
+        address: u16,
+        count: Option<u16>,
+        options: Options,
+    },
+    Write {
+        address: u16,
+        value: serde_json::Value,
+        options: Options,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options {
+    pub ty: Option<String>,
+    pub slave: Option<u8>,
+    pub socket: Option<usize>,
+}
+
+fn invalid(what: &str, value: &str) -> PhaetonError {
+    PhaetonError::validation(what, &format!("Invalid {} '{}'", what, value))
+}
+
+fn parse_num<T: std::str::FromStr>(what: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| invalid(what, value))
+}
+
+/// Parse the arguments following `modbus`
+pub fn parse_modbus_args(args: &[String]) -> Result<ModbusCommand> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        let mut value = |name: &str| it.next().cloned().ok_or_else(|| invalid(name, "<missing>"));
+        match arg.as_str() {
+            "--type" => options.ty = Some(value("type")?),
+            "--slave" => options.slave = Some(parse_num("slave", &value("slave")?)?),
+            "--socket" => options.socket = Some(parse_num("socket", &value("socket")?)?),
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    match positional.as_slice() {
+        ["read", address, rest @ ..] if rest.len() <= 1 => Ok(ModbusCommand::Read {
+            address: parse_num("address", address)?,
+            count: rest.first().map(|c| parse_num("count", c)).transpose()?,
+            options,
+        }),
+        ["write", address, value] => {
+            let values = value
+                .split(',')
+                .map(|v| serde_json::from_str(v.trim()).map_err(|_| invalid("value", v)))
+                .collect::<Result<Vec<serde_json::Value>>>()?;
+            Ok(ModbusCommand::Write {
+                address: parse_num("address", address)?,
+                value: match <[serde_json::Value; 1]>::try_from(values) {
+                    Ok([single]) => single,
+                    Err(values) => serde_json::Value::Array(values),
+                },
+                options,
+            })
+        }
+        _ => Err(PhaetonError::validation("arguments", MODBUS_USAGE)),
+    }
+}
+
+impl ModbusCommand {
+    /// HTTP method, path and body of the inspector request
+    pub fn request(&self) -> (&'static str, String, Option<String>) {
+        match self {
+            Self::Read {
+                address,
+                count,
+                options,
+            } => {
+                let mut query = format!("address={}", address);
+                let params = [
+                    ("count", count.map(|c| c.to_string())),
+                    ("type", options.ty.clone()),
+                    ("slave", options.slave.map(|s| s.to_string())),
+                    ("socket", options.socket.map(|s| s.to_string())),
+                ];
+                for (k, v) in params {
+                    if let Some(v) = v {
+                        query.push_str(&format!("&{}={}", k, v));
+                    }
+                }
+                ("GET", format!("/api/modbus/read?{}", query), None)
+            }
+            Self::Write {
+                address,
+                value,
+                options,
+            } => {
+                let body = serde_json::json!({
+                    "address": address,
+                    "value": value,
+                    "type": options.ty.as_deref().unwrap_or("u16"),
+                    "slave": options.slave,
+                    "socket": options.socket,
+                });
+                (
+                    "POST",
+                    "/api/modbus/write".to_string(),
+                    Some(body.to_string()),
+                )
+            }
+        }
+    }
+}
+
+/// Send one HTTP/1.1 request and return the status code and body
+async fn http_request(
+    host: &str,
+    port: u16,
+    method: &str,
+    path: &str,
+    token: &str,
+    body: Option<&str>,
+) -> Result<(u16, String)> {
+    let mut stream = tokio::net::TcpStream::connect((host, port))
+        .await
+        .map_err(|e| {
+            PhaetonError::network(format!(
+                "Cannot reach phaeton at {}:{} ({}); is it running?",
+                host, port, e
+            ))
+        })?;
+    let body = body.unwrap_or_default();
+    let request = format!(
+        "{method} {path} HTTP/1.1\r\nHost: {host}:{port}\r\nAuthorization: Bearer {token}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| PhaetonError::network("Malformed HTTP response"))?;
+    Ok((status, body.to_string()))
+}
+
+/// Run a `phaeton modbus` command against the running instance; returns the
+/// JSON response
+pub async fn run_modbus(args: &[String], config: &Config) -> Result<String> {
+    let command = parse_modbus_args(args)?;
+    let host = match config.web.host.as_str() {
+        "0.0.0.0" | "::" | "" => "127.0.0.1",
+        h => h,
+    };
+    let token = std::env::var("PHAETON_API_TOKEN").unwrap_or_else(|_| config.web.api_token.clone());
+    let (method, path, body) = command.request();
+    let (status, body) = http_request(
+        host,
+        config.web.port,
+        method,
+        &path,
+        &token,
+        body.as_deref(),
+    )
+    .await?;
+    if status >= 400 {
+        return Err(PhaetonError::api(format!(
+            "HTTP {}: {}",
+            status,
+            body.trim()
+        )));
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_read_and_write() {
+        let read = parse_modbus_args(&args("read 1100 2 --type f32 --slave 200")).unwrap();
+        assert_eq!(
+            read.request(),
+            (
+                "GET",
+                "/api/modbus/read?address=1100&count=2&type=f32&slave=200".to_string(),
+                None
+            )
+        );
+        let write = parse_modbus_args(&args("write 1417 16,16,16 --type f32")).unwrap();
+        let ModbusCommand::Write { value, .. } = &write else {
+            panic!("expected write");
+        };
+        assert_eq!(value, &serde_json::json!([16, 16, 16]));
+        let single = parse_modbus_args(&args("write 1215 1")).unwrap();
+        let (_, path, body) = single.request();
+        assert_eq!(path, "/api/modbus/write");
+        assert!(body.unwrap().contains("\"value\":1"));
+        assert!(parse_modbus_args(&args("read")).is_err());
+        assert!(parse_modbus_args(&args("read x")).is_err());
+        assert!(parse_modbus_args(&args("erase 1210")).is_err());
+    }
+}
@@ -8,7 +8,14 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+mod controls;
 mod defaults;
+mod registers;
+mod scheduler;
+
+pub use controls::ControlsConfig;
+pub use registers::RegistersConfig;
+pub use scheduler::SchedulerConfig;
 
 fn default_true() -> bool {
     true
@@ -21,6 +28,10 @@ pub struct Config {
     /// Modbus TCP connection configuration
     pub modbus: ModbusConfig,
 
+    /// Charger backend selection (Alfen or a register-map profile)
+    #[serde(default)]
+    pub charger: ChargerConfig,
+
     /// Device instance for D-Bus service naming
     pub device_instance: u32,
 
@@ -46,6 +57,10 @@ pub struct Config {
     /// Control and safety limit configuration
     pub controls: ControlsConfig,
 
+    /// Modbus poll scheduling per register group
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+
     /// Web server binding configuration
     pub web: WebConfig,
 
@@ -72,7 +87,8 @@ pub struct Config {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct ModbusConfig {
-    /// IP address of the EV charger
+    /// IP address or hostname of the EV charger, or `auto:<serial>` to
+    /// locate it via mDNS; resolved again on every reconnect
     pub ip: String,
 
     /// TCP port (typically 502)
@@ -83,54 +99,65 @@ pub struct ModbusConfig {
 
     /// Slave ID for station configuration
     pub station_slave_id: u8,
-}
-
-/// Modbus register address mappings
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
-pub struct RegistersConfig {
-    /// Voltage register addresses (L1, L2, L3)
-    pub voltages: u16,
-
-    /// Current register addresses (L1, L2, L3)
-    pub currents: u16,
-
-    /// Power register addresses
-    pub power: u16,
 
-    /// Energy counter register address
-    pub energy: u16,
-
-    /// Status string register address
-    pub status: u16,
-
-    /// Current setting register address
-    pub amps_config: u16,
+    /// Number of sockets to control (0 = detect from the station).
+    /// Socket N uses slave ID `socket_slave_id + N - 1` and D-Bus device
+    /// instance `device_instance + N - 1`.
+    #[serde(default)]
+    pub sockets: u8,
 
-    /// Phase configuration register address
-    pub phases: u16,
+    /// Append every Modbus request and response, and the D-Bus reads of the
+    /// control loop, to this JSONL file for offline replay (empty = off)
+    #[serde(default)]
+    pub capture_file: String,
+
+    /// Send a keepalive read after this many idle seconds; the station
+    /// drops connections idle for 60 s (0 = off)
+    #[serde(default = "default_keepalive_s")]
+    pub keepalive_s: u64,
+
+    /// Longest delay between reconnect attempts (s); attempts start
+    /// `controls.retry_delay` apart and double after each failure
+    #[serde(default = "default_backoff_max_s")]
+    pub backoff_max_s: f64,
+
+    /// Failed connect attempts (including connections dropped before the
+    /// first response) after which the circuit opens (0 = never)
+    #[serde(default = "default_open_circuit_after")]
+    pub open_circuit_after: u32,
+
+    /// After `open_circuit_after` failed reconnects, wait this long before
+    /// the next attempt (s)
+    #[serde(default = "default_open_circuit_s")]
+    pub open_circuit_s: f64,
+}
 
-    /// Firmware version register addresses
-    pub firmware_version: u16,
-    pub firmware_version_count: u16,
+fn default_keepalive_s() -> u64 {
+    30
+}
 
-    /// Serial number register addresses
-    pub station_serial: u16,
-    pub station_serial_count: u16,
+fn default_backoff_max_s() -> f64 {
+    30.0
+}
 
-    /// Manufacturer register addresses
-    pub manufacturer: u16,
-    pub manufacturer_count: u16,
+fn default_open_circuit_after() -> u32 {
+    10
+}
 
-    /// Platform type register addresses
-    pub platform_type: u16,
-    pub platform_type_count: u16,
+fn default_open_circuit_s() -> f64 {
+    300.0
+}
 
-    /// Station max current register address
-    pub station_max_current: u16,
+/// Charger backend selection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ChargerConfig {
+    /// Backend: "alfen" (built-in) or "profile" (YAML register map)
+    pub backend: String,
 
-    /// Station status register address
-    pub station_status: u16,
+    /// Path to the register-map profile used by the "profile" backend
+    pub profile: String,
 }
 
 /// Default operational values
@@ -256,70 +283,6 @@ pub struct TibberConfig {
     pub cheap_percentile: f64,
 }
 
-/// Control and safety limits
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
-#[serde(default)]
-pub struct ControlsConfig {
-    /// Tolerance for current verification
-    pub current_tolerance: f32,
-
-    /// Min difference to trigger update
-    pub update_difference_threshold: f32,
-
-    /// Delay before verifying settings
-    pub verification_delay: f64,
-
-    /// Delay between retries
-    pub retry_delay: f64,
-
-    /// Max retry attempts
-    pub max_retries: u32,
-
-    /// Watchdog interval in seconds
-    pub watchdog_interval_seconds: u32,
-
-    /// Max settable current
-    pub max_set_current: f32,
-
-    /// Minimum non-zero current to apply in automatic mode. If the computed
-    /// current is below this threshold, we set 0 A to avoid oscillating with
-    /// sub-minimum setpoints. Typical EVSE minimum is 6 A.
-    pub min_set_current: f32,
-
-    /// Min charge duration in seconds
-    pub min_charge_duration_seconds: u32,
-
-    /// Interval for refreshing current settings
-    pub current_update_interval: u32,
-
-    /// Verification delay in milliseconds
-    pub verify_delay: u32,
-
-    /// Time window to compensate measurement lag between Victron house loads
-    /// and charger Modbus readings (milliseconds). During this window after a
-    /// set-current change we subtract the expected EV power (derived from the
-    /// last sent current) from house consumption instead of the charger-reported
-    /// power to avoid double-counting.
-    pub ev_reporting_lag_ms: u32,
-
-    /// Exponential moving average smoothing factor for PV excess (0..1)
-    /// Lower values increase smoothing; 0 disables and uses raw values.
-    pub pv_excess_ema_alpha: f32,
-
-    /// Minimum time between phase switches (seconds) to avoid oscillations
-    pub phase_switch_grace_seconds: u32,
-
-    /// Settling time to keep charging stopped after switching phases (seconds)
-    pub phase_switch_settle_seconds: u32,
-
-    /// Enable automatic 1P/3P switching in Auto mode
-    pub auto_phase_switch: bool,
-
-    /// Hysteresis margin in watts for auto phase switching decisions
-    pub auto_phase_hysteresis_watts: f32,
-}
-
 /// Web server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
@@ -329,6 +292,11 @@ pub struct WebConfig {
 
     /// TCP port
     pub port: u16,
+
+    /// Bearer token for diagnostic endpoints such as the raw Modbus register
+    /// inspector (`/api/modbus/*`); empty disables them
+    #[serde(default)]
+    pub api_token: String,
 }
 
 /// Pricing configuration
@@ -427,6 +395,32 @@ impl Config {
             ));
         }
 
+        self.registers.validate()?;
+
+        match self.charger.backend.to_ascii_lowercase().as_str() {
+            "alfen" => {}
+            "profile" if !self.charger.profile.trim().is_empty() => {}
+            "profile" => {
+                return Err(PhaetonError::validation(
+                    "charger.profile",
+                    "A profile path is required for the 'profile' backend",
+                ));
+            }
+            _ => {
+                return Err(PhaetonError::validation(
+                    "charger.backend",
+                    "Must be 'alfen' or 'profile'",
+                ));
+            }
+        }
+
+        if self.modbus.sockets > 2 {
+            return Err(PhaetonError::validation(
+                "modbus.sockets",
+                "Must be 0 (detect), 1 or 2",
+            ));
+        }
+
         // Validate current limits
         if self.defaults.intended_set_current <= 0.0 {
             return Err(PhaetonError::validation(
@@ -442,6 +436,8 @@ impl Config {
             ));
         }
 
+        self.controls.validate()?;
+
         // Validate polling interval
         if self.poll_interval_ms == 0 {
             return Err(PhaetonError::validation(
@@ -0,0 +1,256 @@
+//! Control loop and safety limit configuration
+
+use crate::error::{PhaetonError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Control and safety limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ControlsConfig {
+    /// Tolerance for current verification
+    pub current_tolerance: f32,
+
+    /// Min difference to trigger update
+    pub update_difference_threshold: f32,
+
+    /// Delay before the setpoint readback is checked (s)
+    pub verification_delay: f64,
+
+    /// Delay between retries; also the first Modbus reconnect delay
+    pub retry_delay: f64,
+
+    /// Max retry attempts
+    pub max_retries: u32,
+
+    /// Watchdog interval in seconds (used when the station's remaining
+    /// setpoint validity cannot be read)
+    pub watchdog_interval_seconds: u32,
+
+    /// Reassert the setpoint once the station's remaining max current
+    /// validity time (register 1208) drops to this many seconds
+    pub validity_reassert_margin_seconds: u32,
+
+    /// Max settable current
+    pub max_set_current: f32,
+
+    /// Minimum non-zero current to apply in automatic mode. If the computed
+    /// current is below this threshold, we set 0 A to avoid oscillating with
+    /// sub-minimum setpoints. Typical EVSE minimum is 6 A.
+    pub min_set_current: f32,
+
+    /// Min charge duration in seconds
+    pub min_charge_duration_seconds: u32,
+
+    /// Interval for refreshing current settings
+    pub current_update_interval: u32,
+
+    /// Time window to compensate measurement lag between Victron house loads
+    /// and charger Modbus readings (milliseconds). During this window after a
+    /// set-current change we subtract the expected EV power (derived from the
+    /// last sent current) from house consumption instead of the charger-reported
+    /// power to avoid double-counting.
+    pub ev_reporting_lag_ms: u32,
+
+    /// Treat socket meter values as stale once the meter's last value
+    /// timestamp (registers 301..304) is older than this (ms). Stale values
+    /// are not used for lag compensation or session accounting.
+    pub meter_stale_threshold_ms: u64,
+
+    /// Exponential moving average smoothing factor for PV excess (0..1)
+    /// Lower values increase smoothing; 0 disables and uses raw values.
+    pub pv_excess_ema_alpha: f32,
+
+    /// Minimum time between phase switches (seconds) to avoid oscillations
+    pub phase_switch_grace_seconds: u32,
+
+    /// Settling time to keep charging stopped after switching phases (seconds)
+    pub phase_switch_settle_seconds: u32,
+
+    /// Enable automatic 1P/3P switching in Auto mode
+    pub auto_phase_switch: bool,
+
+    /// Hysteresis margin in watts for auto phase switching decisions
+    pub auto_phase_hysteresis_watts: f32,
+
+    /// Station board temperature (register 1102) above which a warning is logged (°C)
+    pub temperature_warning_c: f32,
+
+    /// Board temperature above which the current is scaled down linearly
+    /// towards zero at `temperature_stop_c`, but not below `min_set_current`
+    /// (°C)
+    pub temperature_derate_c: f32,
+
+    /// Board temperature at which charging is paused; it resumes once the
+    /// temperature drops below `temperature_derate_c` (°C)
+    pub temperature_stop_c: f32,
+
+    /// Interval for re-reading the station clock and uptime (168..178)
+    /// after the read on connect (seconds)
+    pub clock_check_interval_seconds: u32,
+
+    /// Warn when the station clock differs from the host clock by more
+    /// than this (seconds)
+    pub clock_drift_warning_seconds: u32,
+
+    /// Installation phases the charger's L1, L2 and L3 are wired to, e.g.
+    /// "L2L3L1" when charger L1 is on installation L2. Per-phase charger
+    /// measurements are remapped to installation phases with it.
+    pub phase_rotation: String,
+
+    /// Nominal voltage of one charging phase, used for watts/amps
+    /// conversion while no plausible measurement is available (V).
+    /// Line-to-neutral (230 on 230/400 V, 120 on 120/208 V), or
+    /// line-to-line with `line_to_line` (e.g. 208 or 240).
+    pub nominal_voltage: f32,
+
+    /// Single-phase charging runs line-to-line (208 V between two legs of
+    /// 120/208 V, or 240 V split-phase): the measured L1-L2 voltage of the
+    /// full meter telemetry is used instead of L1-N
+    pub line_to_line: bool,
+
+    /// Exponential moving average smoothing factor for the measured phase
+    /// voltages (0..1); 0 disables smoothing
+    pub voltage_ema_alpha: f32,
+
+    /// Auto mode controller: "pv_excess" converts the smoothed PV excess to
+    /// amps (open loop); "grid_zero" runs a PI loop on the grid meter
+    /// (`/Ac/Grid/Lx/Power`) towards `grid_setpoint_w`
+    pub auto_controller: String,
+
+    /// Grid power the grid-zero controller aims for (W, positive = import);
+    /// a small negative value keeps an export margin
+    pub grid_setpoint_w: f32,
+
+    /// Proportional gain of the grid-zero controller (A per A of error)
+    pub grid_kp: f32,
+
+    /// Integral gain of the grid-zero controller (1/s)
+    pub grid_ki: f32,
+
+    /// Grid power errors within this band are ignored (W)
+    pub grid_deadband_w: f32,
+
+    /// Maximum change of the grid-zero current (A/s); 0 disables the limit
+    pub grid_ramp_a_per_s: f32,
+
+    /// Current above `min_set_current` the grid-zero controller needs before
+    /// it starts charging (A); stopping happens below the minimum, after the
+    /// `min_charge_duration_seconds` grace period
+    pub grid_start_margin_a: f32,
+
+    /// How PV is shared with the home battery: "ev_first" (battery charging
+    /// power is available to the EV), "battery_first" (below
+    /// `battery_priority_soc` it is not) or "battery_buffer" (above
+    /// `battery_buffer_soc` the EV may also draw `battery_max_discharge_w`
+    /// from the battery)
+    pub battery_policy: String,
+
+    /// battery_first: battery SoC below which the battery keeps its PV (%)
+    pub battery_priority_soc: f32,
+
+    /// battery_buffer: battery SoC above which the EV may discharge it (%)
+    pub battery_buffer_soc: f32,
+
+    /// battery_buffer: battery power the EV may use on top of the PV excess (W)
+    pub battery_max_discharge_w: f32,
+
+    /// Main fuse rating per phase (A); the setpoint is capped so no phase
+    /// the EV charges on exceeds it. 0 disables main fuse protection.
+    pub main_fuse_a: f32,
+
+    /// Safety margin kept below `main_fuse_a` (A)
+    pub main_fuse_margin_a: f32,
+
+    /// After the fuse cap stopped charging, headroom above `min_set_current`
+    /// required to resume (A)
+    pub main_fuse_resume_a: f32,
+
+    /// How long the headroom must stay sufficient before charging resumes
+    /// after a fuse stop (s)
+    pub main_fuse_hold_s: f32,
+
+    /// D-Bus service of the grid meter read for main fuse protection
+    /// (`/Ac/Lx/Current`, `/Ac/Lx/Power`); empty uses
+    /// `com.victronenergy.system` (`/Ac/Grid/Lx/...`)
+    pub main_fuse_service: String,
+}
+
+impl ControlsConfig {
+    /// Installation phase index (0..=2) of the charger's L1, L2 and L3, or
+    /// `None` when `phase_rotation` is not a permutation of L1, L2 and L3
+    pub fn phase_map(&self) -> Option<[usize; 3]> {
+        let s: Vec<char> = self
+            .phase_rotation
+            .chars()
+            .filter(|c| !matches!(c, '-' | ' ' | ','))
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if s.len() != 6 {
+            return None;
+        }
+        let mut map = [0usize; 3];
+        let mut seen = [false; 3];
+        for (i, pair) in s.chunks(2).enumerate() {
+            let line = match pair {
+                ['L', d] => d.to_digit(10)? as usize,
+                _ => return None,
+            };
+            if !(1..=3).contains(&line) || seen[line - 1] {
+                return None;
+            }
+            seen[line - 1] = true;
+            map[i] = line - 1;
+        }
+        Some(map)
+    }
+
+    /// Validate the controls section (called from `Config::validate`)
+    pub(super) fn validate(&self) -> Result<()> {
+        if self.temperature_derate_c >= self.temperature_stop_c {
+            return Err(PhaetonError::validation(
+                "controls.temperature_derate_c",
+                "Must be below controls.temperature_stop_c",
+            ));
+        }
+        if self.temperature_warning_c > self.temperature_derate_c {
+            return Err(PhaetonError::validation(
+                "controls.temperature_warning_c",
+                "Must not be above controls.temperature_derate_c",
+            ));
+        }
+
+        if self.phase_map().is_none() {
+            return Err(PhaetonError::validation(
+                "controls.phase_rotation",
+                "Must be a permutation of L1, L2 and L3 (e.g. L2L3L1)",
+            ));
+        }
+
+        if !(50.0..=500.0).contains(&self.nominal_voltage) {
+            return Err(PhaetonError::validation(
+                "controls.nominal_voltage",
+                "Must be between 50 and 500 V",
+            ));
+        }
+
+        if !matches!(self.auto_controller.as_str(), "pv_excess" | "grid_zero") {
+            return Err(PhaetonError::validation(
+                "controls.auto_controller",
+                "Must be pv_excess or grid_zero",
+            ));
+        }
+
+        if !matches!(
+            self.battery_policy.as_str(),
+            "ev_first" | "battery_first" | "battery_buffer"
+        ) {
+            return Err(PhaetonError::validation(
+                "controls.battery_policy",
+                "Must be ev_first, battery_first or battery_buffer",
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -7,6 +7,21 @@ impl Default for ModbusConfig {
             port: 502,
             socket_slave_id: 1,
             station_slave_id: 200,
+            sockets: 0,
+            capture_file: String::new(),
+            keepalive_s: 30,
+            backoff_max_s: 30.0,
+            open_circuit_after: 10,
+            open_circuit_s: 300.0,
+        }
+    }
+}
+
+impl Default for ChargerConfig {
+    fn default() -> Self {
+        Self {
+            backend: "alfen".to_string(),
+            profile: String::new(),
         }
     }
 }
@@ -31,6 +46,22 @@ impl Default for RegistersConfig {
             platform_type_count: 17,
             station_max_current: 1100,
             station_status: 1201,
+            temperature: 1102,
+            station_clock: 168,
+            ocpp_state: 1104,
+            availability: 1200,
+            nr_of_sockets: 1105,
+            actual_applied_current: 1206,
+            setpoint_accounted_for: 1214,
+            max_current_valid_time: 1208,
+            safe_current: 1212,
+            current_control: "socket".to_string(),
+            scn_consumption: 1405,
+            scn_actual_max_current: 1411,
+            scn_max_current: 1417,
+            scn_max_current_enable: 1431,
+            meter_block: 300,
+            full_meter_telemetry: false,
         }
     }
 }
@@ -84,17 +115,56 @@ impl Default for ControlsConfig {
             retry_delay: 1.0,
             max_retries: 10,
             watchdog_interval_seconds: 30,
+            validity_reassert_margin_seconds: 20,
             max_set_current: 64.0,
             min_set_current: 6.0,
             min_charge_duration_seconds: 300,
             current_update_interval: 30000,
-            verify_delay: 100,
             ev_reporting_lag_ms: 2000,
+            meter_stale_threshold_ms: 10000,
             pv_excess_ema_alpha: 0.4,
             phase_switch_grace_seconds: 60,
             phase_switch_settle_seconds: 5,
             auto_phase_switch: true,
             auto_phase_hysteresis_watts: 300.0,
+            temperature_warning_c: 70.0,
+            temperature_derate_c: 75.0,
+            temperature_stop_c: 85.0,
+            clock_check_interval_seconds: 300,
+            clock_drift_warning_seconds: 60,
+            phase_rotation: "L1L2L3".to_string(),
+            nominal_voltage: 230.0,
+            line_to_line: false,
+            voltage_ema_alpha: 0.2,
+            auto_controller: "pv_excess".to_string(),
+            grid_setpoint_w: -100.0,
+            grid_kp: 0.5,
+            grid_ki: 0.1,
+            grid_deadband_w: 50.0,
+            grid_ramp_a_per_s: 2.0,
+            grid_start_margin_a: 1.0,
+            battery_policy: "ev_first".to_string(),
+            battery_priority_soc: 80.0,
+            battery_buffer_soc: 50.0,
+            battery_max_discharge_w: 3000.0,
+            main_fuse_a: 0.0,
+            main_fuse_margin_a: 2.0,
+            main_fuse_resume_a: 2.0,
+            main_fuse_hold_s: 60.0,
+            main_fuse_service: String::new(),
+        }
+    }
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            status_interval_ms: 0,
+            validity_interval_ms: 0,
+            station_max_interval_ms: 60_000,
+            temperature_interval_ms: 10_000,
+            identity_interval_ms: 3_600_000,
+            coalesce_max_gap: 32,
         }
     }
 }
@@ -104,6 +174,7 @@ impl Default for WebConfig {
         Self {
             host: "127.0.0.1".to_string(),
             port: 8088,
+            api_token: String::new(),
         }
     }
 }
@@ -135,6 +206,7 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             modbus: ModbusConfig::default(),
+            charger: ChargerConfig::default(),
             device_instance: 0,
             require_dbus: true,
             registers: RegistersConfig::default(),
@@ -143,6 +215,7 @@ impl Default for Config {
             schedule: ScheduleConfig::default(),
             tibber: TibberConfig::default(),
             controls: ControlsConfig::default(),
+            scheduler: SchedulerConfig::default(),
             poll_interval_ms: 1000,
             timezone: "UTC".to_string(),
             web: WebConfig::default(),
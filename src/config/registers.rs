@@ -0,0 +1,153 @@
+//! Modbus register address configuration
+
+use crate::error::{PhaetonError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Modbus register address mappings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct RegistersConfig {
+    /// Voltage register addresses (L1, L2, L3)
+    pub voltages: u16,
+
+    /// Current register addresses (L1, L2, L3)
+    pub currents: u16,
+
+    /// Power register addresses
+    pub power: u16,
+
+    /// Energy counter register address
+    pub energy: u16,
+
+    /// Status string register address
+    pub status: u16,
+
+    /// Current setting register address
+    pub amps_config: u16,
+
+    /// Phase configuration register address
+    pub phases: u16,
+
+    /// Firmware version register addresses
+    pub firmware_version: u16,
+    pub firmware_version_count: u16,
+
+    /// Serial number register addresses
+    pub station_serial: u16,
+    pub station_serial_count: u16,
+
+    /// Manufacturer register addresses
+    pub manufacturer: u16,
+    pub manufacturer_count: u16,
+
+    /// Platform type register addresses
+    pub platform_type: u16,
+    pub platform_type_count: u16,
+
+    /// Station max current register address
+    pub station_max_current: u16,
+
+    /// Station status register address
+    pub station_status: u16,
+
+    /// Board temperature register address (station slave)
+    pub temperature: u16,
+
+    /// Start of the station clock block (station slave): date/time
+    /// (168..173), uptime in ms (174..177) and timezone offset (178)
+    pub station_clock: u16,
+
+    /// OCPP back office connection state register address (station slave)
+    pub ocpp_state: u16,
+
+    /// Availability register address (socket slave; 1 operative, 0 inoperative)
+    pub availability: u16,
+
+    /// Number of sockets register address (station slave)
+    pub nr_of_sockets: u16,
+
+    /// Actual Applied Max Current register address (socket slave)
+    pub actual_applied_current: u16,
+
+    /// Modbus Slave received setpoint accounted for register address (socket slave)
+    pub setpoint_accounted_for: u16,
+
+    /// Modbus Slave Max Current valid time register address (socket slave, seconds)
+    pub max_current_valid_time: u16,
+
+    /// Active Load Balancing Safe Current register address (socket slave)
+    pub safe_current: u16,
+
+    /// Current control backend: "socket" writes the per-socket max current
+    /// (`amps_config`); "scn" writes the setpoint of socket 1 as the per-phase
+    /// SCN max currents instead, treating the whole Smart Charging Network as
+    /// one entity
+    pub current_control: String,
+
+    /// SCN total consumption L1..L3 register address (station slave)
+    pub scn_consumption: u16,
+
+    /// SCN actual max current L1..L3 register address (station slave)
+    pub scn_actual_max_current: u16,
+
+    /// SCN max current per phase L1..L3 register address (station slave, R/W)
+    pub scn_max_current: u16,
+
+    /// SCN Modbus slave max current enable register address (station slave)
+    pub scn_max_current_enable: u16,
+
+    /// Start of the socket meter register block (meter state, 300..425)
+    pub meter_block: u16,
+
+    /// Read the whole meter block (power factor, frequency, apparent/reactive
+    /// power, neutral current, per-phase energies) instead of only the
+    /// registers needed for control
+    pub full_meter_telemetry: bool,
+}
+
+impl RegistersConfig {
+    /// Whether current is controlled through the SCN per-phase registers
+    pub fn uses_scn(&self) -> bool {
+        self.current_control.eq_ignore_ascii_case("scn")
+    }
+
+    /// Start and end (exclusive) of the socket's validity block covering
+    /// 1206..1214; `None` when a range runs past the register space
+    pub fn validity_block(&self) -> Option<(u16, u16)> {
+        let ranges = [
+            (self.actual_applied_current, 2u16),
+            (self.max_current_valid_time, 2),
+            (self.safe_current, 2),
+            (self.setpoint_accounted_for, 1),
+        ];
+        let start = ranges.iter().map(|(a, _)| *a).min()?;
+        let mut end = start;
+        for (addr, count) in ranges {
+            end = end.max(addr.checked_add(count)?);
+        }
+        Some((start, end))
+    }
+
+    /// Validate the registers section (called from `Config::validate`)
+    pub(super) fn validate(&self) -> Result<()> {
+        if !matches!(
+            self.current_control.to_ascii_lowercase().as_str(),
+            "socket" | "scn"
+        ) {
+            return Err(PhaetonError::validation(
+                "registers.current_control",
+                "Must be 'socket' or 'scn'",
+            ));
+        }
+
+        if self.validity_block().is_none() {
+            return Err(PhaetonError::validation(
+                "registers.max_current_valid_time",
+                "Validity registers exceed the register space",
+            ));
+        }
+
+        Ok(())
+    }
+}
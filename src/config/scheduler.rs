@@ -0,0 +1,31 @@
+//! Modbus poll scheduling configuration
+
+use serde::{Deserialize, Serialize};
+
+/// Per-group poll intervals. Fast measurements (voltages, currents, power,
+/// energy) are read every `poll_interval_ms`; the other register groups are
+/// read once their interval has elapsed (0 = every poll).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Mode 3 status (1201) and socket availability interval (ms)
+    pub status_interval_ms: u64,
+
+    /// Setpoint validity timers (1206..) or SCN status interval (ms)
+    pub validity_interval_ms: u64,
+
+    /// Station max current (1100) interval (ms)
+    pub station_max_interval_ms: u64,
+
+    /// Board temperature interval (ms)
+    pub temperature_interval_ms: u64,
+
+    /// Identity (manufacturer, firmware, serial) interval (ms); identity is
+    /// also read on every (re)connect
+    pub identity_interval_ms: u64,
+
+    /// Largest gap in registers bridged when coalescing reads of one slave
+    /// into a single request
+    pub coalesce_max_gap: u16,
+}
@@ -24,6 +24,38 @@ fn test_config_validation() {
     config = Config::default();
     config.modbus.port = 0;
     assert!(config.validate().is_err());
+
+    // Derating must start below the stop temperature
+    config = Config::default();
+    config.controls.temperature_derate_c = config.controls.temperature_stop_c;
+    assert!(config.validate().is_err());
+
+    // The warning cannot come after derating has started
+    config = Config::default();
+    config.controls.temperature_warning_c = config.controls.temperature_derate_c + 1.0;
+    assert!(config.validate().is_err());
+
+    // Phase rotation must name each phase once
+    config = Config::default();
+    config.controls.phase_rotation = "L2-L3-L1".to_string();
+    assert_eq!(config.controls.phase_map(), Some([1, 2, 0]));
+    config.controls.phase_rotation = "L1L1L3".to_string();
+    assert!(config.validate().is_err());
+
+    // The profile backend needs a profile path
+    config = Config::default();
+    config.charger.backend = "profile".to_string();
+    assert!(config.validate().is_err());
+    config.charger.profile = "profiles/example_wallbox.yaml".to_string();
+    assert!(config.validate().is_ok());
+    config.charger.backend = "modbus".to_string();
+    assert!(config.validate().is_err());
+
+    // Validity registers must fit the register space
+    config = Config::default();
+    assert_eq!(config.registers.validity_block(), Some((1206, 1214)));
+    config.registers.safe_current = u16::MAX;
+    assert!(config.validate().is_err());
 }
 
 #[test]
@@ -3,9 +3,12 @@
 //! This module contains the business logic for different charging modes
 //! including manual, automatic, and scheduled charging strategies.
 
+mod grid_zero;
+pub use grid_zero::{GridZeroController, GridZeroTuning};
+
 use crate::error::Result;
 use crate::logging::get_logger;
-use chrono::{Datelike, Timelike, Utc};
+use chrono::{DateTime, Datelike, Timelike, Utc};
 use chrono_tz::Tz;
 
 /// Charging mode enumeration
@@ -19,6 +22,36 @@ pub enum ChargingMode {
 
     /// Scheduled control - time-based charging
     Scheduled = 2,
+
+    /// Minimum + PV - always at least `min_set_current`, more when the PV
+    /// excess allows; never waits for sun
+    MinPv = 3,
+}
+
+impl ChargingMode {
+    /// Mode for a numeric code; unknown codes are Manual
+    pub const fn from_code(code: u32) -> Self {
+        match code {
+            1 => Self::Auto,
+            2 => Self::Scheduled,
+            3 => Self::MinPv,
+            _ => Self::Manual,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Manual => "Manual",
+            Self::Auto => "Auto",
+            Self::Scheduled => "Scheduled",
+            Self::MinPv => "Minimum + PV",
+        }
+    }
+
+    /// Whether the current follows the PV excess (Auto, Minimum + PV)
+    pub const fn follows_pv(self) -> bool {
+        matches!(self, Self::Auto | Self::MinPv)
+    }
 }
 
 /// Start/stop state enumeration
@@ -31,9 +64,29 @@ pub enum StartStopState {
     Enabled = 1,
 }
 
+/// Station readback after writing a current setpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SetpointReadback {
+    /// Actual Applied Max Current (register 1206), if readable
+    pub applied: Option<f32>,
+    /// Modbus Slave received setpoint accounted for (register 1214), if readable
+    pub accounted_for: Option<bool>,
+}
+
+impl SetpointReadback {
+    /// Whether the station took the setpoint into account. `None` when the
+    /// readback failed. The applied current may be lower than the target
+    /// because of other limits (installation, cable, load balancing), but
+    /// must not exceed it by more than `tolerance`.
+    pub fn accepted(&self, target: f32, tolerance: f32) -> Option<bool> {
+        let applied = self.applied?;
+        let accounted_for = self.accounted_for?;
+        Some(accounted_for && applied <= target + tolerance.max(0.0))
+    }
+}
+
 /// Charging control system
 pub struct ChargingControls {
-    #[allow(dead_code)]
     logger: crate::logging::StructuredLogger,
 }
 
@@ -52,6 +105,30 @@ impl Default for ChargingControls {
 }
 
 impl ChargingControls {
+    /// Sum of the voltages of the phases in use (at least one phase at
+    /// `controls.nominal_voltage`)
+    fn watts_per_amp(phase_voltages: &[f32], config: &crate::config::Config) -> f32 {
+        let sum: f32 = phase_voltages.iter().take(3).sum();
+        if sum > 0.0 {
+            sum
+        } else {
+            config.controls.nominal_voltage.max(1.0)
+        }
+    }
+
+    /// Minimum + PV: `min_set_current`, raised to what the PV excess covers.
+    /// The excess already includes the EV's own draw, so it replaces the
+    /// minimum rather than adding to it.
+    fn min_plus_pv(
+        solar_power: Option<f32>,
+        config: &crate::config::Config,
+        phase_voltages: &[f32],
+    ) -> f32 {
+        let excess_watts = solar_power.unwrap_or(0.0).max(0.0);
+        let pv_amps = excess_watts / Self::watts_per_amp(phase_voltages, config);
+        pv_amps.max(config.controls.min_set_current.max(0.0))
+    }
+
     /// Compute effective current based on mode and conditions
     #[allow(clippy::too_many_arguments)]
     pub async fn compute_effective_current(
@@ -60,10 +137,10 @@ impl ChargingControls {
         start_stop: StartStopState,
         requested_current: f32,
         station_max_current: f32,
-        _current_time: f64,
+        current_time: f64,
         solar_power: Option<f32>,
         config: &crate::config::Config,
-        assumed_phases: u8,
+        phase_voltages: &[f32],
     ) -> Result<f32> {
         if matches!(start_stop, StartStopState::Stopped) {
             return Ok(0.0);
@@ -73,11 +150,9 @@ impl ChargingControls {
             ChargingMode::Manual => requested_current.min(station_max_current),
             ChargingMode::Auto => {
                 // Interpret solar_power as (smoothed) excess Watts available for charging.
-                // Convert Watts to Amps using nominal 230V per phase and assume 3 phases.
+                // Convert Watts to Amps with the voltages of the phases in use.
                 let excess_watts = solar_power.unwrap_or(0.0).max(0.0);
-                let nominal_voltage = 230.0f32;
-                let phases = assumed_phases.clamp(1, 3) as f32;
-                let amps_raw = excess_watts / (phases * nominal_voltage);
+                let amps_raw = excess_watts / Self::watts_per_amp(phase_voltages, config);
                 // Below EVSE minimum current we should not oscillate with tiny setpoints.
                 // If below min_set_current, clamp to exactly 0.0 unless already above threshold.
                 let min_current = config.controls.min_set_current.max(0.0);
@@ -88,9 +163,12 @@ impl ChargingControls {
                 };
                 amps.min(station_max_current)
             }
+            ChargingMode::MinPv => {
+                Self::min_plus_pv(solar_power, config, phase_voltages).min(station_max_current)
+            }
             ChargingMode::Scheduled => match config.schedule.mode.as_str() {
                 "time" => {
-                    if Self::is_within_any_schedule(config) {
+                    if Self::is_within_any_schedule(config, Self::utc_at(current_time)) {
                         station_max_current
                     } else {
                         0.0
@@ -117,7 +195,7 @@ impl ChargingControls {
                         "Unknown schedule.mode='{}' — defaulting to time-based schedule",
                         other
                     ));
-                    if Self::is_within_any_schedule(config) {
+                    if Self::is_within_any_schedule(config, Self::utc_at(current_time)) {
                         station_max_current
                     } else {
                         0.0
@@ -137,10 +215,10 @@ impl ChargingControls {
         start_stop: StartStopState,
         requested_current: f32,
         station_max_current: f32,
-        _current_time: f64,
+        current_time: f64,
         solar_power: Option<f32>,
         config: &crate::config::Config,
-        assumed_phases: u8,
+        phase_voltages: &[f32],
     ) -> Result<f32> {
         if matches!(start_stop, StartStopState::Stopped) {
             return Ok(0.0);
@@ -149,9 +227,7 @@ impl ChargingControls {
             ChargingMode::Manual => requested_current.min(station_max_current),
             ChargingMode::Auto => {
                 let excess_watts = solar_power.unwrap_or(0.0).max(0.0);
-                let nominal_voltage = 230.0f32;
-                let phases = assumed_phases.clamp(1, 3) as f32;
-                let amps_raw = excess_watts / (phases * nominal_voltage);
+                let amps_raw = excess_watts / Self::watts_per_amp(phase_voltages, config);
                 let min_current = config.controls.min_set_current.max(0.0);
                 let amps = if amps_raw < min_current {
                     0.0
@@ -160,9 +236,12 @@ impl ChargingControls {
                 };
                 amps.min(station_max_current)
             }
+            ChargingMode::MinPv => {
+                Self::min_plus_pv(solar_power, config, phase_voltages).min(station_max_current)
+            }
             ChargingMode::Scheduled => match config.schedule.mode.as_str() {
                 "time" => {
-                    if Self::is_within_any_schedule(config) {
+                    if Self::is_within_any_schedule(config, Self::utc_at(current_time)) {
                         station_max_current
                     } else {
                         0.0
@@ -189,7 +268,7 @@ impl ChargingControls {
                         "Unknown schedule.mode='{}' — defaulting to time-based schedule",
                         other
                     ));
-                    if Self::is_within_any_schedule(config) {
+                    if Self::is_within_any_schedule(config, Self::utc_at(current_time)) {
                         station_max_current
                     } else {
                         0.0
@@ -200,18 +279,16 @@ impl ChargingControls {
         Ok(effective)
     }
 
-    /// Apply current setting to charger
-    pub async fn apply_current(&self, _current: f32, _explanation: &str) -> Result<bool> {
-        // TODO: Implement actual current setting via Modbus
-        Ok(true)
+    /// Unix time in seconds as UTC
+    fn utc_at(unix_secs: f64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis((unix_secs * 1000.0) as i64).unwrap_or_else(Utc::now)
     }
 
-    fn is_within_any_schedule(config: &crate::config::Config) -> bool {
+    fn is_within_any_schedule(config: &crate::config::Config, now_utc: DateTime<Utc>) -> bool {
         let tz: Tz = config
             .timezone
             .parse()
             .unwrap_or_else(|_| "UTC".parse().unwrap());
-        let now_utc = Utc::now();
         let now_local = now_utc.with_timezone(&tz);
         let weekday = now_local.weekday().num_days_from_monday() as u8; // 0..6
         let minutes_now = now_local.hour() * 60 + now_local.minute();
@@ -253,7 +330,7 @@ impl ChargingControls {
 
     /// Public helper to check if any schedule window is currently active
     pub fn is_schedule_active(config: &crate::config::Config) -> bool {
-        Self::is_within_any_schedule(config)
+        Self::is_within_any_schedule(config, Utc::now())
     }
 }
 
@@ -318,7 +395,7 @@ mod tests {
                 0.0,
                 None,
                 &cfg,
-                3,
+                &[230.0; 3],
             )
             .unwrap();
         assert!((manual - 32.0).abs() < f32::EPSILON);
@@ -333,7 +410,7 @@ mod tests {
                 0.0,
                 Some(3000.0),
                 &cfg,
-                3,
+                &[230.0; 3],
             )
             .unwrap();
         assert_eq!(auto_low, 0.0);
@@ -349,11 +426,54 @@ mod tests {
                 0.0,
                 Some(watts),
                 &cfg,
-                3,
+                &[230.0; 3],
             )
             .unwrap();
         let expected = watts / (3.0 * 230.0);
         assert!((auto_high - expected).abs() < 0.01);
+
+        // Measured 215 V per phase: fewer watts per ampere, more amps
+        let weak_grid = controls
+            .blocking_compute_effective_current(
+                ChargingMode::Auto,
+                StartStopState::Enabled,
+                0.0,
+                32.0,
+                0.0,
+                Some(watts),
+                &cfg,
+                &[215.0; 3],
+            )
+            .unwrap();
+        assert!((weak_grid - watts / (3.0 * 215.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn blocking_min_pv_never_drops_below_minimum() {
+        let controls = ChargingControls::new();
+        let mut cfg = crate::config::Config::default();
+        cfg.controls.min_set_current = 6.0;
+        let compute = |excess: Option<f32>, voltages: &[f32]| {
+            controls
+                .blocking_compute_effective_current(
+                    ChargingMode::MinPv,
+                    StartStopState::Enabled,
+                    0.0,
+                    32.0,
+                    0.0,
+                    excess,
+                    &cfg,
+                    voltages,
+                )
+                .unwrap()
+        };
+        // No sun: minimum current, on 1P or 3P
+        assert_eq!(compute(None, &[230.0]), 6.0);
+        assert_eq!(compute(Some(500.0), &[230.0; 3]), 6.0);
+        // Enough PV for more than the minimum
+        assert!((compute(Some(6900.0), &[230.0; 3]) - 10.0).abs() < 0.01);
+        assert_eq!(compute(Some(50000.0), &[230.0; 3]), 32.0);
+        assert_eq!(ChargingMode::from_code(3).name(), "Minimum + PV");
     }
 
     #[test]
@@ -366,10 +486,10 @@ mod tests {
                 StartStopState::Enabled,
                 0.0,
                 20.0,
-                0.0,
+                Utc::now().timestamp() as f64,
                 None,
                 &cfg,
-                3,
+                &[230.0; 3],
             )
             .unwrap();
         assert_eq!(amps, 20.0);
@@ -0,0 +1,202 @@
+//! Closed-loop grid-zero PI controller for Auto mode
+
+/// Tuning of the grid-zero controller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridZeroTuning {
+    /// Target grid power (W); negative keeps a small export margin
+    pub setpoint_w: f32,
+    /// Proportional gain (A per A of error)
+    pub kp: f32,
+    /// Integral gain (1/s)
+    pub ki: f32,
+    /// Grid power errors within this band are ignored (W)
+    pub deadband_w: f32,
+    /// Maximum change of the output (A/s); 0 disables rate limiting
+    pub ramp_a_per_s: f32,
+}
+
+impl GridZeroTuning {
+    pub fn from_config(c: &crate::config::ControlsConfig) -> Self {
+        Self {
+            setpoint_w: c.grid_setpoint_w,
+            kp: c.grid_kp.max(0.0),
+            ki: c.grid_ki.max(0.0),
+            deadband_w: c.grid_deadband_w.max(0.0),
+            ramp_a_per_s: c.grid_ramp_a_per_s.max(0.0),
+        }
+    }
+}
+
+/// PI controller state
+#[derive(Debug, Clone, Default)]
+pub struct GridZeroController {
+    integral: f32,
+    output: f32,
+    /// Proportional term of the last update
+    p: f32,
+    active: bool,
+}
+
+impl GridZeroController {
+    /// Start from `current` (bumpless), e.g. the setpoint the station has
+    pub fn reset_to(&mut self, current: f32) {
+        self.integral = current.max(0.0);
+        self.output = current.max(0.0);
+        self.p = 0.0;
+        self.active = true;
+    }
+
+    /// Anti-windup tracking: adopt the setpoint actually applied after the
+    /// last update, so the next step and its ramp start from there
+    pub fn track(&mut self, applied: f32) {
+        if !self.active {
+            return;
+        }
+        let applied = applied.max(0.0);
+        self.integral = (applied - self.p).max(0.0);
+        self.output = applied;
+    }
+
+    /// Forget the state; the next update starts from scratch
+    pub fn deactivate(&mut self) {
+        self.active = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Last output (A)
+    pub fn output(&self) -> f32 {
+        self.output
+    }
+
+    /// One control step. `watts_per_amp` converts the grid error to amps,
+    /// `max_a` bounds the output and `dt_s` is the time since the last step.
+    /// Returns the new charging current (A), in `0..=max_a`.
+    pub fn update(
+        &mut self,
+        tuning: &GridZeroTuning,
+        grid_w: f32,
+        watts_per_amp: f32,
+        max_a: f32,
+        dt_s: f32,
+    ) -> f32 {
+        if !self.active {
+            self.reset_to(0.0);
+        }
+        let max_a = max_a.max(0.0);
+        let mut error_w = tuning.setpoint_w - grid_w;
+        if error_w.abs() <= tuning.deadband_w {
+            error_w = 0.0;
+        }
+        let error_a = error_w / watts_per_amp.max(1.0);
+        let p = tuning.kp * error_a;
+        self.p = p;
+
+        // Anti-windup: integrate only while the output is not saturated in
+        // the direction of the error, and keep the integral within range
+        let unclamped = p + self.integral;
+        let saturated =
+            (unclamped >= max_a && error_a > 0.0) || (unclamped <= 0.0 && error_a < 0.0);
+        if !saturated {
+            self.integral = (self.integral + tuning.ki * error_a * dt_s).clamp(0.0, max_a);
+        }
+        let mut out = (p + self.integral).clamp(0.0, max_a);
+
+        if tuning.ramp_a_per_s > 0.0 && dt_s > 0.0 {
+            let step = tuning.ramp_a_per_s * dt_s;
+            out = out.clamp(self.output - step, self.output + step);
+        }
+        self.output = out;
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuning() -> GridZeroTuning {
+        GridZeroTuning {
+            setpoint_w: -100.0,
+            kp: 0.5,
+            ki: 0.2,
+            deadband_w: 30.0,
+            ramp_a_per_s: 2.0,
+        }
+    }
+
+    /// Grid power of a house exporting `export_w` while the EV draws `amps`
+    fn grid(export_w: f32, amps: f32) -> f32 {
+        -export_w + amps * 690.0
+    }
+
+    #[test]
+    fn settles_at_grid_setpoint_and_tracks_clouds() {
+        let t = tuning();
+        let mut c = GridZeroController::default();
+        let mut amps = 0.0;
+        for _ in 0..120 {
+            amps = c.update(&t, grid(7000.0, amps), 690.0, 32.0, 1.0);
+        }
+        assert!((grid(7000.0, amps) - t.setpoint_w).abs() <= t.deadband_w + 1.0);
+
+        // A cloud halves the PV; the current is ramped down by at most 2 A/s
+        let before = amps;
+        amps = c.update(&t, grid(3500.0, amps), 690.0, 32.0, 1.0);
+        assert!((before - amps - 2.0).abs() < 1e-3);
+        for _ in 0..120 {
+            amps = c.update(&t, grid(3500.0, amps), 690.0, 32.0, 1.0);
+        }
+        assert!((grid(3500.0, amps) - t.setpoint_w).abs() <= t.deadband_w + 1.0);
+    }
+
+    #[test]
+    fn integral_does_not_wind_up_while_saturated() {
+        let t = tuning();
+        let mut c = GridZeroController::default();
+        // Far more PV than the station can take
+        for _ in 0..600 {
+            c.update(&t, grid(30000.0, c.output()), 690.0, 16.0, 1.0);
+        }
+        assert_eq!(c.output(), 16.0);
+        // Once the excess is gone the output leaves saturation right away
+        let next = c.update(&t, grid(0.0, 16.0), 690.0, 16.0, 1.0);
+        assert!(next < 16.0);
+    }
+
+    #[test]
+    fn tracking_the_applied_setpoint_prevents_windup() {
+        let t = tuning();
+        let mut c = GridZeroController::default();
+        c.reset_to(10.0);
+        // The EV is held at 6 A (e.g. thermal derating) although 14 A of PV
+        // excess is available
+        for _ in 0..300 {
+            c.update(&t, grid(10000.0, 6.0), 690.0, 32.0, 1.0);
+            c.track(6.0);
+        }
+        assert_eq!(c.output(), 6.0);
+        // Once the limit is gone the ramp starts from the applied 6 A
+        let next = c.update(&t, grid(10000.0, 6.0), 690.0, 32.0, 1.0);
+        assert!(next > 6.0 && next <= 8.0 + 1e-3);
+        // And the PV dropping away brings it down without unwinding first
+        c.track(next);
+        let mut amps = next;
+        for _ in 0..30 {
+            amps = c.update(&t, grid(3000.0, amps), 690.0, 32.0, 1.0);
+            c.track(amps);
+        }
+        assert!((grid(3000.0, amps) - t.setpoint_w).abs() <= t.deadband_w + 1.0);
+    }
+
+    #[test]
+    fn ignores_errors_within_deadband() {
+        let t = tuning();
+        let mut c = GridZeroController::default();
+        c.reset_to(10.0);
+        assert_eq!(c.update(&t, -80.0, 690.0, 32.0, 1.0), 10.0);
+        assert_eq!(c.update(&t, -125.0, 690.0, 32.0, 1.0), 10.0);
+    }
+}
@@ -1,4 +1,5 @@
 mod ev_charger;
+mod export;
 mod items;
 mod root;
 mod service;
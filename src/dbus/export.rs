@@ -0,0 +1,188 @@
+//! Mapping of driver snapshots to D-Bus paths
+
+use super::DbusService;
+use crate::driver::{DriverSnapshot, MeterTelemetry};
+use crate::error::Result;
+
+/// Phaeton-specific paths (not part of the Victron evcharger schema)
+fn phaeton_paths(snap: &DriverSnapshot) -> Vec<(String, serde_json::Value)> {
+    vec![
+        (
+            "/Phaeton/SetpointAccepted".to_string(),
+            serde_json::json!(snap.setpoint_accepted.map(u8::from)),
+        ),
+        (
+            "/Phaeton/ActualAppliedCurrent".to_string(),
+            serde_json::json!(snap.actual_applied_current),
+        ),
+        (
+            "/Phaeton/Temperature".to_string(),
+            serde_json::json!(snap.thermal.as_ref().and_then(|t| t.temperature_c)),
+        ),
+        (
+            "/Phaeton/StationOperative".to_string(),
+            serde_json::json!(snap.station_operative.map(u8::from)),
+        ),
+        (
+            "/Phaeton/BackofficeConnected".to_string(),
+            serde_json::json!(snap.backoffice_connected.map(u8::from)),
+        ),
+        (
+            "/Phaeton/Mode3State".to_string(),
+            serde_json::json!(snap.mode3.as_ref().map(|m| m.state.clone())),
+        ),
+        // Transition history as JSON text; VeDbus items carry no lists of objects
+        (
+            "/Phaeton/Mode3History".to_string(),
+            serde_json::json!(
+                snap.mode3
+                    .as_ref()
+                    .and_then(|m| serde_json::to_string(&m.transitions).ok())
+            ),
+        ),
+    ]
+}
+
+/// Full meter telemetry paths, published only when the meter block is read
+fn meter_paths(m: &MeterTelemetry) -> Vec<(String, serde_json::Value)> {
+    let mut out = vec![
+        ("/Ac/Frequency".to_string(), serde_json::json!(m.frequency)),
+        (
+            "/Ac/PowerFactor".to_string(),
+            serde_json::json!(m.power_factor_total),
+        ),
+        (
+            "/Ac/ApparentPower".to_string(),
+            serde_json::json!(m.apparent_power_total),
+        ),
+        (
+            "/Ac/ReactivePower".to_string(),
+            serde_json::json!(m.reactive_power_total),
+        ),
+        ("/Ac/N/Current".to_string(), serde_json::json!(m.current_n)),
+        (
+            "/Ac/Energy/Reverse".to_string(),
+            serde_json::json!(m.energy_consumed_total_kwh),
+        ),
+    ];
+    for (i, line) in ["L1", "L2", "L3"].iter().enumerate() {
+        let ll = ["L1L2", "L2L3", "L3L1"][i];
+        out.extend([
+            (
+                format!("/Ac/{}/PowerFactor", line),
+                serde_json::json!(m.power_factor[i]),
+            ),
+            (
+                format!("/Ac/{}/ApparentPower", line),
+                serde_json::json!(m.apparent_power[i]),
+            ),
+            (
+                format!("/Ac/{}/ReactivePower", line),
+                serde_json::json!(m.reactive_power[i]),
+            ),
+            (
+                format!("/Ac/{}/Energy/Forward", line),
+                serde_json::json!(m.energy_delivered_kwh[i]),
+            ),
+            (
+                format!("/Ac/{}/Energy/Reverse", line),
+                serde_json::json!(m.energy_consumed_kwh[i]),
+            ),
+            (
+                format!("/Ac/{}/Voltage", ll),
+                serde_json::json!(m.voltage_ll[i]),
+            ),
+        ]);
+    }
+    out
+}
+
+impl DbusService {
+    /// Export a typed driver snapshot to D-Bus paths
+    pub async fn export_typed_snapshot(&mut self, snap: &DriverSnapshot) -> Result<()> {
+        // Derive forward/session energy and charging time if available
+        let (energy_forward, charging_time): (f64, i64) =
+            if let Some(obj) = snap.session.as_object() {
+                let fwd = obj
+                    .get("energy_delivered_kwh")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                let t = obj
+                    .get("charging_time_sec")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0);
+                (fwd, t)
+            } else {
+                (0.0, 0)
+            };
+
+        // Map snapshot fields to Victron D-Bus paths
+        let updates = [
+            ("/Ac/Power".to_string(), serde_json::json!(snap.ac_power)),
+            (
+                "/Ac/Current".to_string(),
+                serde_json::json!(snap.ac_current),
+            ),
+            ("/Current".to_string(), serde_json::json!(snap.ac_current)),
+            (
+                "/Ac/Energy/Total".to_string(),
+                serde_json::json!(snap.total_energy_kwh),
+            ),
+            (
+                "/Ac/Energy/Forward".to_string(),
+                serde_json::json!(energy_forward),
+            ),
+            (
+                "/Ac/PhaseCount".to_string(),
+                serde_json::json!(snap.active_phases),
+            ),
+            (
+                "/Ac/L1/Voltage".to_string(),
+                serde_json::json!(snap.l1_voltage),
+            ),
+            (
+                "/Ac/L2/Voltage".to_string(),
+                serde_json::json!(snap.l2_voltage),
+            ),
+            (
+                "/Ac/L3/Voltage".to_string(),
+                serde_json::json!(snap.l3_voltage),
+            ),
+            (
+                "/Ac/L1/Current".to_string(),
+                serde_json::json!(snap.l1_current),
+            ),
+            (
+                "/Ac/L2/Current".to_string(),
+                serde_json::json!(snap.l2_current),
+            ),
+            (
+                "/Ac/L3/Current".to_string(),
+                serde_json::json!(snap.l3_current),
+            ),
+            ("/Ac/L1/Power".to_string(), serde_json::json!(snap.l1_power)),
+            ("/Ac/L2/Power".to_string(), serde_json::json!(snap.l2_power)),
+            ("/Ac/L3/Power".to_string(), serde_json::json!(snap.l3_power)),
+            ("/Status".to_string(), serde_json::json!(snap.status)),
+            (
+                "/MaxCurrent".to_string(),
+                serde_json::json!(snap.station_max_current),
+            ),
+            (
+                "/ChargingTime".to_string(),
+                serde_json::json!(charging_time),
+            ),
+            ("/Mode".to_string(), serde_json::json!(snap.mode)),
+            ("/StartStop".to_string(), serde_json::json!(snap.start_stop)),
+            (
+                "/SetCurrent".to_string(),
+                serde_json::json!(snap.set_current),
+            ),
+        ];
+        self.update_paths(updates).await?;
+        if let Some(m) = &snap.meter {
+            self.update_paths(meter_paths(m)).await?;
+        }
+        self.update_paths(phaeton_paths(snap)).await
+    }
+}
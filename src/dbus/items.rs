@@ -54,19 +54,9 @@ impl BusItem {
 
     fn normalize_start_stop(value: &serde_json::Value) -> serde_json::Value {
         let v = match value {
-            serde_json::Value::Bool(b) => {
-                if *b {
-                    1
-                } else {
-                    0
-                }
-            }
+            serde_json::Value::Bool(b) => u8::from(*b),
             serde_json::Value::Number(n) => {
-                if n.as_u64().unwrap_or(0) > 0 || n.as_i64().unwrap_or(0) > 0 {
-                    1
-                } else {
-                    0
-                }
+                u8::from(n.as_u64().unwrap_or(0) > 0 || n.as_i64().unwrap_or(0) > 0)
             }
             serde_json::Value::String(s) => {
                 let t = s.trim().to_ascii_lowercase();
@@ -92,16 +82,11 @@ impl BusItem {
                     0 => 0,
                     1 => 1,
                     2 => 2,
+                    3 => 3,
                     _ => 0,
                 }
             }
-            serde_json::Value::Bool(b) => {
-                if *b {
-                    1
-                } else {
-                    0
-                }
-            }
+            serde_json::Value::Bool(b) => u8::from(*b),
             serde_json::Value::String(s) => {
                 let t = s.trim().to_ascii_lowercase();
                 if t == "manual" || t == "0" {
@@ -110,6 +95,11 @@ impl BusItem {
                     1
                 } else if t == "scheduled" || t == "schedule" || t == "2" {
                     2
+                } else if matches!(
+                    t.as_str(),
+                    "min_pv" | "minpv" | "min+pv" | "minimum+pv" | "3"
+                ) {
+                    3
                 } else {
                     0
                 }
@@ -175,7 +165,9 @@ impl BusItem {
 
     pub(crate) fn serde_to_owned_value(v: &serde_json::Value) -> OwnedValue {
         match v {
-            serde_json::Value::Null => OwnedValue::from(0i64),
+            // Unavailable values are published as an empty array, like VeDbus does
+            serde_json::Value::Null => OwnedValue::try_from(Value::from(Vec::<i32>::new()))
+                .unwrap_or_else(|_| OwnedValue::from(0i64)),
             serde_json::Value::Bool(b) => OwnedValue::from(*b),
             serde_json::Value::Number(n) => {
                 if let Some(i) = n.as_i64() {
@@ -208,6 +200,11 @@ impl BusItem {
         if let Ok(s) = <&str as TryFrom<&OwnedValue>>::try_from(v) {
             return serde_json::json!(s.to_string());
         }
+        if let Value::Array(a) = &**v
+            && a.is_empty()
+        {
+            return serde_json::Value::Null;
+        }
         serde_json::json!(v.to_string())
     }
 }
@@ -275,6 +272,18 @@ mod tests {
             item.normalize_value_for_path(&serde_json::json!("schedule")),
             serde_json::json!(2)
         );
+        assert_eq!(
+            BusItem::normalize_mode(&serde_json::json!(3)),
+            serde_json::json!(3)
+        );
+        assert_eq!(
+            BusItem::normalize_mode(&serde_json::json!("Min+PV")),
+            serde_json::json!(3)
+        );
+        assert_eq!(
+            BusItem::normalize_mode(&serde_json::json!(4)),
+            serde_json::json!(0)
+        );
     }
 
     #[test]
@@ -301,6 +310,13 @@ mod tests {
             serde_json::json!(5u64)
         );
 
+        // Null (unavailable) becomes the VeDbus invalid value and back
+        let ov_n = BusItem::serde_to_owned_value(&serde_json::Value::Null);
+        assert_eq!(
+            BusItem::owned_value_to_serde(&ov_n),
+            serde_json::Value::Null
+        );
+
         let ov_f = BusItem::serde_to_owned_value(&serde_json::json!(std::f64::consts::PI));
         assert_eq!(
             BusItem::owned_value_to_serde(&ov_f),
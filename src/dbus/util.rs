@@ -9,6 +9,7 @@ pub(crate) fn format_text_value(val: &serde_json::Value) -> String {
         }
         serde_json::Value::String(s) => s.clone(),
         serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Null => "---".to_string(),
         _ => val.to_string(),
     }
 }
@@ -24,6 +25,7 @@ mod tests {
         assert_eq!(format_text_value(&serde_json::json!(2)), "2.00");
         assert_eq!(format_text_value(&serde_json::json!("abc")), "abc");
         assert_eq!(format_text_value(&serde_json::json!(true)), "true");
+        assert_eq!(format_text_value(&serde_json::Value::Null), "---");
         // Objects/arrays fall back to to_string
         assert!(format_text_value(&serde_json::json!({"k":"v"})).contains("k"));
     }
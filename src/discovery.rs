@@ -0,0 +1,193 @@
+//! mDNS/DNS-SD discovery of Alfen stations on the LAN
+//!
+//! Stations advertise `_alfen._tcp.local.`. Browsing lists them with their
+//! address, hostname and serial (when advertised) for `/api/discovery` and
+//! `phaeton --discover`. `modbus.ip` may also be a hostname or
+//! `auto:<serial>`; both are resolved again on every (re)connect so a DHCP
+//! address change does not need a config edit.
+
+use crate::error::{PhaetonError, Result};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// DNS-SD service type advertised by Alfen stations
+pub const SERVICE_TYPE: &str = "_alfen._tcp.local.";
+
+/// `modbus.ip` prefix selecting a station by serial via mDNS
+pub const AUTO_PREFIX: &str = "auto:";
+
+/// Default browse duration
+pub const DEFAULT_BROWSE: Duration = Duration::from_secs(3);
+
+/// A station found on the LAN
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DiscoveredStation {
+    /// Service instance name
+    pub name: String,
+    pub ip: String,
+    pub hostname: String,
+    pub port: u16,
+    /// Station serial from the TXT record or the instance name
+    pub serial: Option<String>,
+}
+
+/// Serial requested by an `auto:<serial>` address
+pub fn auto_serial(ip: &str) -> Option<&str> {
+    ip.strip_prefix(AUTO_PREFIX)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Serial of a station: the `serial`/`sn` TXT property, else an instance
+/// name that looks like a serial (e.g. `ACE0123456`)
+pub fn station_serial(instance: &str, txt_serial: Option<&str>) -> Option<String> {
+    if let Some(s) = txt_serial.map(str::trim).filter(|s| !s.is_empty()) {
+        return Some(s.to_string());
+    }
+    let label = instance.split('.').next().unwrap_or_default();
+    let looks_like_serial = label.len() >= 6
+        && label.chars().all(|c| c.is_ascii_alphanumeric())
+        && label.chars().any(|c| c.is_ascii_digit());
+    looks_like_serial.then(|| label.to_string())
+}
+
+fn valid_hostname(host: &str) -> bool {
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Resolve `modbus.ip` (IP address, hostname or `auto:<serial>`) to a socket
+/// address
+pub async fn resolve_modbus_address(ip: &str, port: u16) -> Result<SocketAddr> {
+    if let Ok(addr) = ip.parse::<std::net::IpAddr>() {
+        return Ok(SocketAddr::new(addr, port));
+    }
+    if let Some(serial) = auto_serial(ip) {
+        let station = find_station(serial, DEFAULT_BROWSE).await?;
+        let addr = station
+            .ip
+            .parse::<std::net::IpAddr>()
+            .map_err(|e| PhaetonError::modbus(format!("Invalid socket address: {}", e)))?;
+        return Ok(SocketAddr::new(addr, port));
+    }
+    let host = ip.trim_end_matches('.');
+    if !valid_hostname(host) {
+        return Err(PhaetonError::modbus(format!(
+            "Invalid socket address: '{}' is not an IP address or hostname",
+            ip
+        )));
+    }
+    tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| PhaetonError::network(format!("Failed to resolve {}: {}", host, e)))?
+        .min_by_key(SocketAddr::is_ipv6)
+        .ok_or_else(|| PhaetonError::network(format!("No address found for {}", host)))
+}
+
+/// Browse for the station with the given serial (case-insensitive)
+pub async fn find_station(serial: &str, duration: Duration) -> Result<DiscoveredStation> {
+    discover(duration)
+        .await?
+        .into_iter()
+        .find(|s| {
+            s.serial
+                .as_deref()
+                .is_some_and(|v| v.eq_ignore_ascii_case(serial))
+        })
+        .ok_or_else(|| {
+            PhaetonError::network(format!("No station with serial {} found via mDNS", serial))
+        })
+}
+
+/// Browse the LAN for `duration` and return the stations found
+#[cfg(feature = "discovery")]
+pub async fn discover(duration: Duration) -> Result<Vec<DiscoveredStation>> {
+    tokio::task::spawn_blocking(move || browse_blocking(duration))
+        .await
+        .map_err(|e| PhaetonError::generic(format!("Discovery task failed: {}", e)))?
+}
+
+#[cfg(feature = "discovery")]
+fn browse_blocking(duration: Duration) -> Result<Vec<DiscoveredStation>> {
+    use mdns_sd::{ServiceDaemon, ServiceEvent};
+
+    let mdns_err = |e: mdns_sd::Error| PhaetonError::network(format!("mDNS: {}", e));
+    let daemon = ServiceDaemon::new().map_err(mdns_err)?;
+    let events = daemon.browse(SERVICE_TYPE).map_err(mdns_err)?;
+    let deadline = std::time::Instant::now() + duration;
+    let mut found = std::collections::BTreeMap::new();
+    while let Some(left) = deadline.checked_duration_since(std::time::Instant::now()) {
+        match events.recv_timeout(left) {
+            Ok(ServiceEvent::ServiceResolved(info)) => {
+                let Some(ip) = info.get_addresses_v4().into_iter().min() else {
+                    continue;
+                };
+                let name = info
+                    .get_fullname()
+                    .trim_end_matches(SERVICE_TYPE)
+                    .trim_end_matches('.')
+                    .to_string();
+                let txt = info
+                    .get_property_val_str("serial")
+                    .or_else(|| info.get_property_val_str("sn"));
+                let station = DiscoveredStation {
+                    serial: station_serial(&name, txt),
+                    ip: ip.to_string(),
+                    hostname: info.get_hostname().trim_end_matches('.').to_string(),
+                    port: info.get_port(),
+                    name,
+                };
+                found.insert(station.name.clone(), station);
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    let _ = daemon.shutdown();
+    Ok(found.into_values().collect())
+}
+
+#[cfg(not(feature = "discovery"))]
+pub async fn discover(_duration: Duration) -> Result<Vec<DiscoveredStation>> {
+    Err(PhaetonError::config("mDNS discovery disabled"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_auto_serial_and_station_serial() {
+        assert_eq!(auto_serial("auto:ACE0123456"), Some("ACE0123456"));
+        assert_eq!(auto_serial("auto: "), None);
+        assert_eq!(auto_serial("192.168.1.10"), None);
+        assert_eq!(
+            station_serial("ACE0123456", None).as_deref(),
+            Some("ACE0123456")
+        );
+        assert_eq!(
+            station_serial("Alfen NG910", Some("ACE42")).as_deref(),
+            Some("ACE42")
+        );
+        assert_eq!(station_serial("Alfen NG910", None), None);
+    }
+
+    #[tokio::test]
+    async fn resolves_ip_and_hostname() {
+        let addr = resolve_modbus_address("10.0.0.5", 502).await.unwrap();
+        assert_eq!(addr.to_string(), "10.0.0.5:502");
+        let addr = resolve_modbus_address("localhost", 502).await.unwrap();
+        assert!(addr.ip().is_loopback());
+        let err = resolve_modbus_address("bad host", 502).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid socket address"));
+    }
+}
@@ -17,8 +17,12 @@ use tokio::sync::{broadcast, mpsc, watch};
 // tokio::time only used in runtime modules
 
 mod types;
-pub use types::{DriverCommand, DriverSnapshot, DriverState};
+pub use types::{
+    DriverCommand, DriverSnapshot, DriverState, MeterHealth, MeterTelemetry, Mode3Status,
+    Mode3Transition, ScnStatus,
+};
 // internal worker types moved out; keep type module private
+pub mod capture;
 mod commands;
 mod dbus_helpers;
 pub mod modbus_like;
@@ -26,7 +30,10 @@ mod pv;
 mod runtime;
 mod runtime_arc;
 mod runtime_poll;
+pub mod scheduler;
 mod snapshot;
+pub mod sockets;
+pub(crate) mod wall_clock;
 
 // Measurements and ModbusCommand moved to types.rs
 
@@ -35,6 +42,9 @@ pub struct AlfenDriver {
     /// Configuration
     config: Config,
 
+    /// Socket number (1-based) on the station controlled by this driver
+    socket: u8,
+
     /// Current driver state
     state: watch::Sender<DriverState>,
     /// Keep one receiver alive so state updates always succeed
@@ -44,6 +54,12 @@ pub struct AlfenDriver {
     /// Modbus connection manager (trait for testability)
     modbus_manager: Option<Box<dyn modbus_like::ModbusLike>>,
 
+    /// Register semantics of the charger model (`charger.backend`)
+    backend: Box<dyn crate::backend::ChargerBackend>,
+
+    /// When each register group was last polled
+    poll_scheduler: scheduler::PollScheduler,
+
     /// Logger with context
     logger: crate::logging::StructuredLogger,
 
@@ -61,6 +77,8 @@ pub struct AlfenDriver {
 
     /// D-Bus service shared across tasks; guard with a mutex to avoid take/restore races
     dbus: Option<Arc<tokio::sync::Mutex<DbusService>>>,
+    /// Traffic capture of the D-Bus reads (`modbus.capture_file`) or replay
+    dbus_capture: Option<capture::DbusCapture>,
 
     /// Controls logic
     controls: ChargingControls,
@@ -71,15 +89,22 @@ pub struct AlfenDriver {
     intended_set_current: f32,
     station_max_current: f32,
     last_sent_current: f32,
-    last_current_set_time: std::time::Instant,
+    last_current_set_time: tokio::time::Instant,
     /// When we last changed the current setpoint (monotonic clock)
-    last_set_current_monotonic: std::time::Instant,
+    last_set_current_monotonic: tokio::time::Instant,
     /// Deadline for minimum-charge grace timer when excess < 6A
-    min_charge_timer_deadline: Option<std::time::Instant>,
+    min_charge_timer_deadline: Option<tokio::time::Instant>,
     /// Marker when entering Auto mode; used to suppress grace timer until first Auto charging
-    auto_mode_entered_at: Option<std::time::Instant>,
+    auto_mode_entered_at: Option<tokio::time::Instant>,
     /// Last observed Victron-esque status (0=Disc,1=Conn,2=Charging)
     last_status: u8,
+    /// Status the session logic last acted on; held while energy is
+    /// unavailable so a plug-in during a meter outage still opens a session
+    session_status: u8,
+
+    /// Last base status read from the charger, reused while the status
+    /// group is not due
+    last_base_status: i32,
 
     /// Command receiver for external control
     commands_rx: mpsc::UnboundedReceiver<DriverCommand>,
@@ -94,18 +119,23 @@ pub struct AlfenDriver {
     status_snapshot_tx: watch::Sender<Arc<DriverSnapshot>>,
     status_snapshot_rx: watch::Receiver<Arc<DriverSnapshot>>,
 
-    // Last measured values (from Modbus) for snapshot building
-    last_l1_voltage: f64,
-    last_l2_voltage: f64,
-    last_l3_voltage: f64,
-    last_l1_current: f64,
-    last_l2_current: f64,
-    last_l3_current: f64,
-    last_l1_power: f64,
-    last_l2_power: f64,
-    last_l3_power: f64,
-    last_total_power: f64,
-    last_energy_kwh: f64,
+    // Last measured values (from Modbus) for snapshot building; None when unavailable
+    last_l1_voltage: Option<f64>,
+    last_l2_voltage: Option<f64>,
+    last_l3_voltage: Option<f64>,
+    last_l1_current: Option<f64>,
+    last_l2_current: Option<f64>,
+    last_l3_current: Option<f64>,
+    last_l1_power: Option<f64>,
+    last_l2_power: Option<f64>,
+    last_l3_power: Option<f64>,
+    last_total_power: Option<f64>,
+    last_energy_kwh: Option<f64>,
+    /// Smoothed phase voltages for watts/amps conversion
+    voltage_ema: runtime_poll::VoltageEma,
+    /// Closed-loop Auto controller (`controls.auto_controller: grid_zero`)
+    grid_zero: crate::controls::GridZeroController,
+    grid_zero_at: Option<tokio::time::Instant>,
 
     // Identity cache (to avoid depending on DBus for UI identity fields)
     product_name: Option<String>,
@@ -129,13 +159,51 @@ pub struct AlfenDriver {
     /// Last applied number of phases as known by the driver
     applied_phases: u8,
     /// Time when phases last changed (for grace)
-    last_phase_switch: Option<std::time::Instant>,
+    last_phase_switch: Option<tokio::time::Instant>,
     /// If currently settling after a phase switch, this is the deadline
-    phase_settle_deadline: Option<std::time::Instant>,
+    phase_settle_deadline: Option<tokio::time::Instant>,
 
     /// If set during a phase switch settle period, indicates the target phase count (1 or 3)
     /// Used to expose Victron D-Bus status 22/23 (switching to 3P/1P)
     phase_switch_to: Option<u8>,
+
+    /// Last SCN state read from the station (SCN current control only)
+    scn_status: Option<types::ScnStatus>,
+    /// SCN per-phase max currents written last (A)
+    scn_limit_written: Option<[f32; 3]>,
+
+    /// Whether the station accepted the last setpoint (None until verified)
+    setpoint_accepted: Option<bool>,
+    /// Actual Applied Max Current read back after the last write
+    actual_applied_current: Option<f32>,
+    /// Scheduled retry for a setpoint the station did not accept
+    setpoint_retry: Option<types::SetpointRetry>,
+    /// Setpoint written last and when its readback is due
+    setpoint_verify: Option<(f32, tokio::time::Instant)>,
+    /// Setpoint validity / safe-current state read each cycle
+    validity: Option<types::ValidityStatus>,
+    /// Full meter telemetry from the last cycle (when enabled)
+    meter_telemetry: Option<types::MeterTelemetry>,
+    /// Socket meter health and age of its last value (registers 300..305)
+    meter_health: Option<types::MeterHealth>,
+    meter_age_ms: Option<u64>,
+    /// Board temperature and derating state from the last cycle
+    thermal: Option<types::ThermalStatus>,
+    /// Main fuse protection state; None when disabled
+    main_fuse: Option<types::MainFuseStatus>,
+    main_fuse_stop: runtime_poll::FuseStop,
+    /// Socket availability (1200) and OCPP back office state (1104)
+    station_operative: Option<bool>,
+    backoffice_connected: Option<bool>,
+    /// Mode 3 state tracking (register 1201)
+    mode3: Option<types::Mode3Status>,
+    /// Station clock/uptime tracking (168..178)
+    clock: Option<types::StationClock>,
+    last_clock_check: Option<tokio::time::Instant>,
+    /// Time of day for schedules and the clock check (pinned on replay)
+    wall_clock: wall_clock::WallClock,
+    /// Reassert the setpoint on the next cycle (e.g. after a station reboot)
+    reassert_pending: bool,
 }
 
 impl AlfenDriver {
@@ -160,13 +228,27 @@ impl AlfenDriver {
         &self.config
     }
 
-    /// Update configuration safely (no hot-restart of subsystems yet)
+    /// Update configuration safely (no hot-restart of subsystems yet). This
+    /// loads the charger profile from disk; callers holding the driver lock
+    /// build the backend first and use [`Self::apply_config`].
     pub fn update_config(&mut self, new_config: Config) -> Result<()> {
         // Basic validation already expected by caller
-        self.config = new_config;
+        let backend = crate::backend::from_config(&new_config)?;
+        self.apply_config(new_config, backend);
         Ok(())
     }
 
+    /// Update configuration with a backend built from it beforehand
+    pub fn apply_config(
+        &mut self,
+        new_config: Config,
+        backend: Box<dyn crate::backend::ChargerBackend>,
+    ) {
+        self.backend = backend;
+        self.poll_scheduler.set_config(&new_config.scheduler);
+        self.config = new_config;
+    }
+
     /// Accessors for web/UI
     pub fn current_mode_code(&self) -> u8 {
         self.current_mode as u8
@@ -236,31 +318,21 @@ impl AlfenDriver {
 // Control callbacks for Mode/StartStop/SetCurrent updates (stub: call these from web API later)
 impl AlfenDriver {
     pub async fn set_mode(&mut self, mode: u8) {
-        let new_mode = match mode {
-            1 => ChargingMode::Auto,
-            2 => ChargingMode::Scheduled,
-            _ => ChargingMode::Manual,
-        };
+        let new_mode = ChargingMode::from_code(u32::from(mode));
         if new_mode as u8 != self.current_mode as u8 {
-            let name = |v: u8| match v {
-                0 => "Manual",
-                1 => "Auto",
-                2 => "Scheduled",
-                _ => "Unknown",
-            };
             self.logger.info(&format!(
                 "Mode changed: {} ({}) -> {} ({})",
                 self.current_mode as u8,
-                name(self.current_mode as u8),
+                self.current_mode.name(),
                 new_mode as u8,
-                name(new_mode as u8)
+                new_mode.name()
             ));
         }
         self.current_mode = new_mode;
         // If entering Auto, clear any existing grace timer and mark entry time.
         if matches!(self.current_mode, ChargingMode::Auto) {
             self.min_charge_timer_deadline = None;
-            self.auto_mode_entered_at = Some(std::time::Instant::now());
+            self.auto_mode_entered_at = Some(tokio::time::Instant::now());
         }
         if let Some(dbus) = &self.dbus {
             let _ = dbus
@@ -310,10 +382,10 @@ impl AlfenDriver {
         self.persistence.set_set_current(self.intended_set_current);
         let _ = self.persistence.save();
         // Record the moment we changed the intended current to enable lag compensation
-        self.last_set_current_monotonic = std::time::Instant::now();
+        self.last_set_current_monotonic = tokio::time::Instant::now();
     }
 
-    /// Set desired number of phases (1 or 3). Applies immediately in Manual/Scheduled; in Auto it may be overridden.
+    /// Set desired number of phases (1 or 3). Applies immediately in Manual/Scheduled; in Auto and Minimum + PV it may be overridden.
     pub async fn set_phases(&mut self, phases: u8) {
         let p = if phases >= 3 { 3 } else { 1 };
         if p != self.desired_phases {
@@ -324,7 +396,7 @@ impl AlfenDriver {
         }
         self.desired_phases = p;
         // In Manual or Scheduled, apply immediately
-        if !matches!(self.current_mode, ChargingMode::Auto) {
+        if !self.current_mode.follows_pv() {
             let _ = self.apply_phases_now(p).await;
         }
         if let Some(dbus) = &self.dbus {
@@ -343,41 +415,37 @@ impl AlfenDriver {
         }
         // Stop charging during switch
         let prev_current = self.last_sent_current;
-        // Write 0.0 A to amps register directly (avoid cross-module private call)
         if let Some(mgr) = self.modbus_manager.as_mut() {
-            let socket_id = self.config.modbus.socket_slave_id;
-            let addr_amps = self.config.registers.amps_config;
-            let regs = crate::modbus::encode_32bit_float(0.0);
-            let _ = mgr
-                .write_multiple_registers(socket_id, addr_amps, &regs)
-                .await
-                .ok();
+            let _ = self.backend.write_current(mgr.as_mut(), 0.0).await;
         }
         self.last_sent_current = 0.0;
-        self.last_current_set_time = std::time::Instant::now();
-
-        // Write the phases register
-        let station_id = self.config.modbus.station_slave_id;
-        let addr_phases = self.config.registers.phases;
-        let value: u16 = if target == 3 { 3 } else { 1 };
-        let write_ok = if let Some(mgr) = self.modbus_manager.as_mut() {
-            mgr.write_multiple_registers(station_id, addr_phases, &[value])
+        self.last_current_set_time = tokio::time::Instant::now();
+
+        // Write the phase count (per-socket register on the socket slave)
+        let write_ok = match self.modbus_manager.as_mut() {
+            Some(mgr) => self
+                .backend
+                .write_phases(mgr.as_mut(), target)
                 .await
-                .is_ok()
-        } else {
-            false
+                .is_ok(),
+            None => false,
         };
 
         if write_ok {
             self.applied_phases = target;
-            self.last_phase_switch = Some(std::time::Instant::now());
+            self.last_phase_switch = Some(tokio::time::Instant::now());
             let settle = self.config.controls.phase_switch_settle_seconds as u64;
             self.phase_settle_deadline =
-                Some(std::time::Instant::now() + std::time::Duration::from_secs(settle));
+                Some(tokio::time::Instant::now() + std::time::Duration::from_secs(settle));
             self.phase_switch_to = Some(target);
+            let line = if target == 1 {
+                format!(" on installation L{}", self.phase_map()[0] + 1)
+            } else {
+                String::new()
+            };
             self.logger.info(&format!(
-                "Switched phases to {}P; settling for {}s (prev current {:.1} A)",
-                target, settle, prev_current
+                "Switched phases to {}P{}; settling for {}s (prev current {:.1} A)",
+                target, line, settle, prev_current
             ));
             // Update D-Bus to reflect switching status immediately (22/23)
             if let Some(dbus) = &self.dbus {
@@ -0,0 +1,466 @@
+//! Modbus and D-Bus traffic capture and deterministic replay
+//!
+//! [`RecordingModbus`] wraps a connection and appends every request and its
+//! response to a JSONL capture file (`modbus.capture_file`), one record per
+//! line; the driver adds its D-Bus reads (PV, grid, battery) to the same
+//! file. Records go through a [`CaptureSink`] whose writer thread does the
+//! file I/O, so the control loop never blocks on the disk.
+//!
+//! [`ReplayModbus`] feeds a capture back into a driver
+//! (`AlfenDriver::attach_replay`): reads return the recorded values or
+//! errors in order and writes are compared with the recorded ones, so a
+//! capture attached to a bug report reproduces the control decisions of the
+//! original run. Each request waits until its recorded offset on the Tokio
+//! clock, which also drives the control timers and the driver's wall clock;
+//! with the clock paused (`tokio::time::pause`) a replay runs in virtual
+//! time without delays.
+
+use super::modbus_like::ModbusLike;
+use crate::error::{PhaetonError, Result};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+/// Request type of a capture record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptureOp {
+    /// Read holding registers
+    #[default]
+    #[serde(rename = "r")]
+    Read,
+    /// Write multiple registers
+    #[serde(rename = "w")]
+    Write,
+    /// Read a value of another D-Bus service
+    #[serde(rename = "d")]
+    DbusRead,
+    /// List the D-Bus services with a name prefix
+    #[serde(rename = "l")]
+    DbusList,
+}
+
+/// One request/response pair
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    /// Unix time in milliseconds
+    pub ts: u64,
+    pub op: CaptureOp,
+    #[serde(default)]
+    pub slave: u8,
+    #[serde(default)]
+    pub addr: u16,
+    #[serde(default)]
+    pub count: u16,
+    /// Values read, or written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<u16>,
+    /// Error message when the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// D-Bus service, or the name prefix listed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub service: String,
+    /// D-Bus object path
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    /// D-Bus value read (none when unavailable), or the service names listed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+impl CaptureRecord {
+    fn modbus(op: CaptureOp, slave: u8, addr: u16, count: u16) -> Self {
+        Self {
+            op,
+            slave,
+            addr,
+            count,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn dbus(op: CaptureOp, service: &str, path: &str) -> Self {
+        Self {
+            op,
+            service: service.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The request without its response, for matching on replay
+    fn request(&self) -> String {
+        match self.op {
+            CaptureOp::Read | CaptureOp::Write => format!(
+                "{:?} slave {} addr {} count {}",
+                self.op, self.slave, self.addr, self.count
+            ),
+            CaptureOp::DbusRead | CaptureOp::DbusList => {
+                format!("{:?} {} {}", self.op, self.service, self.path)
+            }
+        }
+    }
+}
+
+/// Read a JSONL capture file
+pub fn load_capture(path: &str) -> Result<Vec<CaptureRecord>> {
+    let file = std::fs::File::open(path)?;
+    std::io::BufReader::new(file)
+        .lines()
+        .filter(|l| l.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+enum SinkMessage {
+    Record(CaptureRecord),
+    Flush(tokio::sync::oneshot::Sender<()>),
+}
+
+/// Appends records to a capture file from a writer thread
+#[derive(Clone)]
+pub struct CaptureSink {
+    tx: std::sync::mpsc::Sender<SinkMessage>,
+    /// Time stamps the records; the driver's clock, so replay offsets match
+    /// the control timers
+    clock: super::wall_clock::WallClock,
+}
+
+impl CaptureSink {
+    /// Open the capture file; the writer thread ends with the last sink clone
+    pub fn open(path: &str) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| PhaetonError::io(format!("Cannot open capture file {}: {}", path, e)))?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("capture-writer".to_string())
+            .spawn(move || write_records(std::io::BufWriter::new(file), rx))
+            .map_err(|e| PhaetonError::io(format!("Cannot start capture writer: {}", e)))?;
+        Ok(Self {
+            tx,
+            clock: Default::default(),
+        })
+    }
+
+    pub(crate) fn with_clock(self, clock: super::wall_clock::WallClock) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Sink for `capture_file`, `None` when capturing is off
+    pub fn maybe_open(capture_file: &str) -> Result<Option<Self>> {
+        if capture_file.trim().is_empty() {
+            return Ok(None);
+        }
+        crate::logging::get_logger("modbus")
+            .info(&format!("Recording Modbus traffic to {}", capture_file));
+        Self::open(capture_file).map(Some)
+    }
+
+    pub fn record(&self, record: CaptureRecord) {
+        let ts = u64::try_from(self.clock.now().timestamp_millis()).unwrap_or_default();
+        let _ = self
+            .tx
+            .send(SinkMessage::Record(CaptureRecord { ts, ..record }));
+    }
+
+    /// Wait until the records sent so far are written
+    pub async fn flush(&self) {
+        let (done, written) = tokio::sync::oneshot::channel();
+        if self.tx.send(SinkMessage::Flush(done)).is_ok() {
+            let _ = written.await;
+        }
+    }
+}
+
+fn write_records(
+    mut out: std::io::BufWriter<std::fs::File>,
+    rx: std::sync::mpsc::Receiver<SinkMessage>,
+) {
+    // Capture is best-effort; a full disk must not stop charging control
+    while let Ok(message) = rx.recv() {
+        match message {
+            SinkMessage::Record(record) => {
+                if let Ok(line) = serde_json::to_string(&record) {
+                    let _ = writeln!(out, "{}", line);
+                }
+            }
+            SinkMessage::Flush(done) => {
+                let _ = out.flush();
+                let _ = done.send(());
+            }
+        }
+        // Keep the file current when the driver is idle
+        let _ = out.flush();
+    }
+}
+
+/// Connection wrapper appending every request to a capture file
+pub struct RecordingModbus {
+    inner: Box<dyn ModbusLike>,
+    sink: CaptureSink,
+}
+
+impl RecordingModbus {
+    pub fn new(inner: Box<dyn ModbusLike>, sink: CaptureSink) -> Self {
+        Self { inner, sink }
+    }
+}
+
+/// Wrap `inner` in a recorder when capturing is on
+pub fn maybe_record(inner: Box<dyn ModbusLike>, sink: Option<&CaptureSink>) -> Box<dyn ModbusLike> {
+    match sink {
+        Some(sink) => Box::new(RecordingModbus::new(inner, sink.clone())),
+        None => inner,
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusLike for RecordingModbus {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn connection_status(&self) -> Option<bool> {
+        self.inner.connection_status()
+    }
+
+    fn connection_stats(&self) -> Option<crate::modbus::ConnectionStats> {
+        self.inner.connection_stats()
+    }
+
+    // Keepalives are not recorded: they do not affect the driver
+    async fn keepalive(&mut self) -> Result<bool> {
+        self.inner.keepalive().await
+    }
+
+    async fn close(&mut self) {
+        self.inner.close().await
+    }
+
+    async fn ensure_connected(&mut self) -> bool {
+        self.inner.ensure_connected().await
+    }
+
+    async fn read_holding_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>> {
+        let res = self
+            .inner
+            .read_holding_registers(slave_id, address, count)
+            .await;
+        self.sink.record(CaptureRecord {
+            values: res.as_ref().map(Clone::clone).unwrap_or_default(),
+            error: res.as_ref().err().map(ToString::to_string),
+            ..CaptureRecord::modbus(CaptureOp::Read, slave_id, address, count)
+        });
+        res
+    }
+
+    async fn write_multiple_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        values: &[u16],
+    ) -> Result<()> {
+        let res = self
+            .inner
+            .write_multiple_registers(slave_id, address, values)
+            .await;
+        self.sink.record(CaptureRecord {
+            values: values.to_vec(),
+            error: res.as_ref().err().map(ToString::to_string),
+            ..CaptureRecord::modbus(CaptureOp::Write, slave_id, address, values.len() as u16)
+        });
+        res
+    }
+}
+
+/// Records of a replay, shared by the Modbus connection and the D-Bus reads
+pub(crate) struct ReplayState {
+    records: VecDeque<CaptureRecord>,
+    first_ts: Option<u64>,
+    started: tokio::time::Instant,
+    divergences: Vec<String>,
+}
+
+impl ReplayState {
+    /// Take the next record if it matches `request`, with the instant its
+    /// recorded offset is reached
+    fn take(&mut self, request: &CaptureRecord) -> Result<(CaptureRecord, tokio::time::Instant)> {
+        let expected = self.records.front().map(CaptureRecord::request);
+        if expected.as_deref() != Some(request.request().as_str()) {
+            let msg = format!(
+                "Replay diverged: {}, expected {:?}",
+                request.request(),
+                expected
+            );
+            self.divergences.push(msg.clone());
+            return Err(PhaetonError::modbus(msg));
+        }
+        let Some(record) = self.records.pop_front() else {
+            return Err(PhaetonError::modbus("Replay exhausted"));
+        };
+        let due = self.due_at(record.ts);
+        Ok((record, due))
+    }
+
+    /// Instant the record stamped `ts` was taken at, relative to the start
+    fn due_at(&self, ts: u64) -> tokio::time::Instant {
+        let offset = ts.saturating_sub(self.first_ts.unwrap_or(ts));
+        self.started + std::time::Duration::from_millis(offset)
+    }
+}
+
+/// Take the next record of a shared replay, waiting until its recorded offset
+pub(crate) async fn replay_next(
+    state: &Mutex<ReplayState>,
+    request: &CaptureRecord,
+) -> Result<CaptureRecord> {
+    let (record, due) = match state.lock() {
+        Ok(mut state) => state.take(request)?,
+        Err(_) => return Err(PhaetonError::modbus("Replay state poisoned")),
+    };
+    tokio::time::sleep_until(due).await;
+    Ok(record)
+}
+
+/// Capture attached to the D-Bus reads of a driver
+#[derive(Clone)]
+pub(crate) enum DbusCapture {
+    Record(CaptureSink),
+    Replay(Arc<Mutex<ReplayState>>),
+    /// Values by path instead of a D-Bus connection, recorded to the sink
+    #[cfg(test)]
+    Fixed(
+        Arc<Mutex<std::collections::HashMap<String, serde_json::Value>>>,
+        CaptureSink,
+    ),
+}
+
+/// Replays a capture; requests must arrive in the recorded order
+pub struct ReplayModbus {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayModbus {
+    pub fn new(records: Vec<CaptureRecord>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReplayState {
+                first_ts: records.first().map(|r| r.ts),
+                records: records.into(),
+                started: tokio::time::Instant::now(),
+                divergences: Vec::new(),
+            })),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        Ok(Self::new(load_capture(path)?))
+    }
+
+    /// Records not requested yet
+    pub fn remaining(&self) -> usize {
+        self.state.lock().map_or(0, |s| s.records.len())
+    }
+
+    /// Requests that did not match the capture (empty for an exact replay)
+    pub fn divergences(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .map(|s| s.divergences.clone())
+            .unwrap_or_default()
+    }
+
+    /// Wall time of the first record
+    pub(crate) fn start_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let first_ts = self.state.lock().ok()?.first_ts?;
+        chrono::DateTime::from_timestamp_millis(i64::try_from(first_ts).ok()?)
+    }
+
+    /// Wait until the next record is due
+    async fn until_next(&self) {
+        let due = self
+            .state
+            .lock()
+            .ok()
+            .and_then(|s| Some(s.due_at(s.records.front()?.ts)));
+        if let Some(due) = due {
+            tokio::time::sleep_until(due).await;
+        }
+    }
+
+    /// D-Bus side of the replay; `None` when the original run had no D-Bus
+    /// reads (no D-Bus connection)
+    pub(crate) fn dbus_capture(&self) -> Option<DbusCapture> {
+        let has_dbus = self
+            .state
+            .lock()
+            .ok()?
+            .records
+            .iter()
+            .any(|r| matches!(r.op, CaptureOp::DbusRead | CaptureOp::DbusList));
+        has_dbus.then(|| DbusCapture::Replay(Arc::clone(&self.state)))
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusLike for ReplayModbus {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // A poll cycle starts by checking the connection: start it when its
+    // first request was recorded, so the cycle's time budget matches
+    async fn ensure_connected(&mut self) -> bool {
+        self.until_next().await;
+        true
+    }
+
+    async fn read_holding_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>> {
+        let request = CaptureRecord::modbus(CaptureOp::Read, slave_id, address, count);
+        let record = replay_next(&self.state, &request).await?;
+        match record.error {
+            Some(e) => Err(PhaetonError::modbus(e)),
+            None => Ok(record.values),
+        }
+    }
+
+    async fn write_multiple_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        values: &[u16],
+    ) -> Result<()> {
+        let request =
+            CaptureRecord::modbus(CaptureOp::Write, slave_id, address, values.len() as u16);
+        let record = replay_next(&self.state, &request).await?;
+        if record.values != values
+            && let Ok(mut state) = self.state.lock()
+        {
+            state.divergences.push(format!(
+                "Replay diverged: wrote {:?} to slave {} addr {}, recorded {:?}",
+                values, slave_id, address, record.values
+            ));
+        }
+        match record.error {
+            Some(e) => Err(PhaetonError::modbus(e)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,180 @@
+use super::*;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+/// Station returning fixed registers
+struct FixedModbus(HashMap<(u8, u16, u16), Vec<u16>>);
+
+#[async_trait::async_trait]
+impl ModbusLike for FixedModbus {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    async fn read_holding_registers(
+        &mut self,
+        slave: u8,
+        addr: u16,
+        count: u16,
+    ) -> Result<Vec<u16>> {
+        self.0
+            .get(&(slave, addr, count))
+            .cloned()
+            .ok_or_else(|| PhaetonError::modbus("Illegal data address"))
+    }
+    async fn write_multiple_registers(&mut self, _: u8, _: u16, _: &[u16]) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn floats(values: &[f32]) -> Vec<u16> {
+    values
+        .iter()
+        .flat_map(|v| crate::modbus::encode_32bit_float(*v))
+        .collect()
+}
+
+async fn driver(mode: crate::controls::ChargingMode) -> crate::driver::AlfenDriver {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+    d.current_mode = mode;
+    d.start_stop = crate::controls::StartStopState::Enabled;
+    d.intended_set_current = 10.0;
+    d.config.controls.auto_phase_switch = false;
+    d.config.controls.pv_excess_ema_alpha = 1.0;
+    d.config.controls.min_charge_duration_seconds = 60;
+    d
+}
+
+fn station(d: &crate::driver::AlfenDriver) -> FixedModbus {
+    let (s, r) = (d.config.modbus.socket_slave_id, &d.config.registers);
+    FixedModbus(HashMap::from([
+        ((s, r.voltages, 6), floats(&[230.0, 230.0, 230.0])),
+        ((s, r.currents, 6), floats(&[6.0, 6.0, 6.0])),
+        ((s, r.power, 8), floats(&[1380.0, 1380.0, 1380.0, 4140.0])),
+        ((s, r.status, 5), vec![0x4332, 0, 0, 0, 0]),
+    ]))
+}
+
+/// Record poll cycles, advancing the clock by each step before a cycle;
+/// `before_cycle` may change the D-Bus values. Returns the setpoint
+/// sent after each cycle.
+async fn record(
+    d: &mut crate::driver::AlfenDriver,
+    path: &str,
+    steps_s: &[u64],
+    mut before_cycle: impl FnMut(usize),
+) -> Vec<f32> {
+    let start = chrono::DateTime::from_timestamp(1_750_000_000, 0).unwrap();
+    d.wall_clock = crate::driver::wall_clock::WallClock::starting_at(start);
+    let sink = CaptureSink::open(path).unwrap().with_clock(d.wall_clock);
+    if let Some(DbusCapture::Fixed(values, _)) = d.dbus_capture.take() {
+        d.dbus_capture = Some(DbusCapture::Fixed(values, sink.clone()));
+    }
+    d.attach_modbus(maybe_record(Box::new(station(d)), Some(&sink)));
+    let mut sent = Vec::new();
+    for (i, step) in steps_s.iter().enumerate() {
+        tokio::time::advance(std::time::Duration::from_secs(*step)).await;
+        before_cycle(i);
+        d.poll_cycle().await.unwrap();
+        sent.push(d.last_sent_current);
+    }
+    sink.flush().await;
+    sent
+}
+
+/// Replay a capture through a fresh driver, returning the setpoint sent
+/// after each cycle
+async fn replay(mut d: crate::driver::AlfenDriver, path: &str, cycles: usize) -> Vec<f32> {
+    d.attach_replay(ReplayModbus::from_file(path).unwrap());
+    let mut sent = Vec::new();
+    for _ in 0..cycles {
+        d.poll_cycle().await.unwrap();
+        sent.push(d.last_sent_current);
+    }
+    let replay = d
+        .modbus_manager
+        .as_mut()
+        .unwrap()
+        .as_any_mut()
+        .downcast_mut::<ReplayModbus>()
+        .unwrap();
+    assert_eq!(replay.divergences(), Vec::<String>::new());
+    assert_eq!(replay.remaining(), 0);
+    sent
+}
+
+#[tokio::test(start_paused = true)]
+async fn records_and_replays_poll_cycles() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+
+    let mut d = driver(crate::controls::ChargingMode::Manual).await;
+    let sent = record(&mut d, &path, &[0, 1, 1], |_| {}).await;
+    let recorded = load_capture(&path).unwrap();
+    assert!(recorded.iter().any(|r| r.op == CaptureOp::Write));
+    assert!(recorded.iter().any(|r| r.error.is_some()));
+    // Without D-Bus nothing but Modbus is captured
+    assert!(recorded.iter().all(|r| r.service.is_empty()));
+
+    let replayed = driver(crate::controls::ChargingMode::Manual).await;
+    assert_eq!(replay(replayed, &path, 3).await, sent);
+}
+
+#[tokio::test(start_paused = true)]
+async fn replays_auto_mode_timers_and_dbus_reads() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+
+    let mut d = driver(crate::controls::ChargingMode::Auto).await;
+    let pv = Arc::new(Mutex::new(HashMap::from([(
+        "/Dc/Pv/Power".to_string(),
+        serde_json::json!(6900.0),
+    )])));
+    let placeholder = CaptureSink::open(&path).unwrap();
+    d.dbus_capture = Some(DbusCapture::Fixed(Arc::clone(&pv), placeholder));
+    // Sun for three cycles, then clouds: the grace timer holds the
+    // minimum current for 60 s before stopping
+    let steps = [0, 10, 10, 10, 20, 20, 30];
+    let sent = record(&mut d, &path, &steps, |i| {
+        if i == 3 {
+            pv.lock().unwrap().clear();
+        }
+    })
+    .await;
+    assert_eq!(sent[1], 10.0);
+    assert_eq!(&sent[3..6], &[6.0, 6.0, 6.0]);
+    assert_eq!(sent[6], 0.0);
+    let recorded = load_capture(&path).unwrap();
+    assert!(
+        recorded
+            .iter()
+            .any(|r| r.op == CaptureOp::DbusRead && r.path == "/Dc/Pv/Power")
+    );
+
+    let replayed = driver(crate::controls::ChargingMode::Auto).await;
+    assert_eq!(replay(replayed, &path, steps.len()).await, sent);
+}
+
+#[tokio::test]
+async fn replay_reports_divergent_requests() {
+    let record = |op, addr, values: Vec<u16>| CaptureRecord {
+        ts: 1_000,
+        count: values.len() as u16,
+        values,
+        ..CaptureRecord::modbus(op, 1, addr, 0)
+    };
+    let mut replay = ReplayModbus::new(vec![
+        record(CaptureOp::Read, 306, vec![1, 2]),
+        record(CaptureOp::Write, 1210, vec![3, 4]),
+    ]);
+    assert!(replay.read_holding_registers(1, 320, 2).await.is_err());
+    assert_eq!(
+        replay.read_holding_registers(1, 306, 2).await.unwrap(),
+        vec![1, 2]
+    );
+    replay
+        .write_multiple_registers(1, 1210, &[5, 6])
+        .await
+        .unwrap();
+    assert_eq!(replay.divergences().len(), 2);
+}
@@ -1,6 +1,75 @@
+use super::capture::{CaptureOp, CaptureRecord, DbusCapture};
 use crate::error::Result;
 
 impl super::AlfenDriver {
+    /// Other D-Bus services can be read: connected, or replaying a capture
+    /// with D-Bus reads
+    pub(crate) fn remote_dbus_available(&self) -> bool {
+        self.dbus.is_some()
+            || self
+                .dbus_capture
+                .as_ref()
+                .is_some_and(|c| !matches!(c, DbusCapture::Record(_)))
+    }
+
+    /// Read a value of another D-Bus service, `None` when unavailable. Reads
+    /// are recorded to and replayed from a traffic capture.
+    pub(crate) async fn read_remote_value(
+        &self,
+        service: &str,
+        path: &str,
+    ) -> Option<serde_json::Value> {
+        let request = CaptureRecord::dbus(CaptureOp::DbusRead, service, path);
+        if let Some(DbusCapture::Replay(state)) = &self.dbus_capture {
+            return super::capture::replay_next(state, &request)
+                .await
+                .ok()?
+                .value;
+        }
+        let value = match (self.dbus.as_ref(), &self.dbus_capture) {
+            (Some(d), _) => d.lock().await.read_remote_value(service, path).await.ok(),
+            #[cfg(test)]
+            (None, Some(DbusCapture::Fixed(values, _))) => {
+                values.lock().ok().and_then(|v| v.get(path).cloned())
+            }
+            _ => None,
+        };
+        self.record_dbus(request, value.clone());
+        value
+    }
+
+    /// Names of the D-Bus services starting with `prefix`
+    pub(crate) async fn list_remote_services(&self, prefix: &str) -> Vec<String> {
+        let request = CaptureRecord::dbus(CaptureOp::DbusList, prefix, "");
+        if let Some(DbusCapture::Replay(state)) = &self.dbus_capture {
+            return super::capture::replay_next(state, &request)
+                .await
+                .ok()
+                .and_then(|r| serde_json::from_value(r.value?).ok())
+                .unwrap_or_default();
+        }
+        let names = match self.dbus.as_ref() {
+            Some(d) => d
+                .lock()
+                .await
+                .list_service_names_with_prefix(prefix)
+                .await
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        self.record_dbus(request, Some(serde_json::json!(names)));
+        names
+    }
+
+    fn record_dbus(&self, request: CaptureRecord, value: Option<serde_json::Value>) {
+        match &self.dbus_capture {
+            Some(DbusCapture::Record(sink)) => sink.record(CaptureRecord { value, ..request }),
+            #[cfg(test)]
+            Some(DbusCapture::Fixed(_, sink)) => sink.record(CaptureRecord { value, ..request }),
+            _ => {}
+        }
+    }
+
     pub fn get_db_value(&self, path: &str) -> Option<serde_json::Value> {
         if let Some(d) = &self.dbus {
             if let Ok(guard) = d.try_lock() {
@@ -42,61 +111,20 @@ impl super::AlfenDriver {
     }
 
     pub(crate) async fn refresh_charger_identity(&mut self) -> Result<()> {
-        if self.modbus_manager.is_none() || self.dbus.is_none() {
+        if self.modbus_manager.is_none() {
+            return Ok(());
+        }
+        if self.backend.alfen_extensions() {
+            self.refresh_station_clock().await;
+        }
+        if self.dbus.is_none() {
             return Ok(());
         }
         let manager = self.modbus_manager.as_mut().unwrap();
-
-        let manufacturer = manager
-            .read_holding_registers(
-                self.config.modbus.station_slave_id,
-                self.config.registers.manufacturer,
-                self.config.registers.manufacturer_count,
-            )
-            .await
-            .ok()
-            .map(|regs| crate::modbus::decode_string(&regs, None).unwrap_or_default())
-            .unwrap_or_default();
-
-        let firmware = manager
-            .read_holding_registers(
-                self.config.modbus.station_slave_id,
-                self.config.registers.firmware_version,
-                self.config.registers.firmware_version_count,
-            )
-            .await
-            .ok()
-            .map(|regs| crate::modbus::decode_string(&regs, None).unwrap_or_default())
-            .unwrap_or_default();
-
-        let serial = manager
-            .read_holding_registers(
-                self.config.modbus.station_slave_id,
-                self.config.registers.station_serial,
-                self.config.registers.station_serial_count,
-            )
-            .await
-            .ok()
-            .map(|regs| crate::modbus::decode_string(&regs, None).unwrap_or_default())
-            .unwrap_or_default();
-
-        // Read Station Max Current once per successful connection
-        let station_max_current = manager
-            .read_holding_registers(
-                self.config.modbus.station_slave_id,
-                self.config.registers.station_max_current,
-                2,
-            )
-            .await
-            .ok()
-            .and_then(|regs| {
-                if regs.len() >= 2 {
-                    crate::modbus::decode_32bit_float(&regs[0..2]).ok()
-                } else {
-                    None
-                }
-            })
-            .filter(|v| v.is_finite() && *v > 0.0);
+        let identity = self.backend.read_identity(manager.as_mut()).await?;
+        let (manufacturer, firmware, serial) =
+            (identity.manufacturer, identity.firmware, identity.serial);
+        let station_max_current = identity.max_current;
 
         if let Some(dbus) = &self.dbus {
             let mut updates: Vec<(String, serde_json::Value)> = Vec::with_capacity(3);
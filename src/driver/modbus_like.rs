@@ -1,5 +1,6 @@
 use crate::error::Result;
 use std::any::Any;
+use std::sync::atomic::Ordering;
 
 #[async_trait::async_trait]
 pub trait ModbusLike: Send {
@@ -8,6 +9,22 @@ pub trait ModbusLike: Send {
     fn connection_status(&self) -> Option<bool> {
         None
     }
+    /// Connection statistics, if the connection tracks them
+    fn connection_stats(&self) -> Option<crate::modbus::ConnectionStats> {
+        None
+    }
+    /// Send a keepalive request if the connection has been idle; returns
+    /// whether one was sent
+    async fn keepalive(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+    /// Close the connection (e.g. on shutdown)
+    async fn close(&mut self) {}
+    /// Make progress on reconnecting without blocking; returns whether the
+    /// connection is usable. Default: always usable.
+    async fn ensure_connected(&mut self) -> bool {
+        true
+    }
     async fn read_holding_registers(
         &mut self,
         slave_id: u8,
@@ -22,3 +39,179 @@ pub trait ModbusLike: Send {
         values: &[u16],
     ) -> Result<()>;
 }
+
+/// A Modbus connection shared by several drivers (e.g. the sockets of a twin
+/// station). The station only accepts a limited number of Modbus masters, so
+/// all sockets go through one TCP connection; each request locks it briefly.
+/// Writes preempt reads: reads wait while a write is queued.
+#[derive(Clone)]
+pub struct SharedModbus {
+    inner: std::sync::Arc<tokio::sync::Mutex<Box<dyn ModbusLike>>>,
+    connected: std::sync::Arc<std::sync::Mutex<Option<bool>>>,
+    stats: std::sync::Arc<std::sync::Mutex<Option<crate::modbus::ConnectionStats>>>,
+    pending_writes: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    writes_done: std::sync::Arc<tokio::sync::Notify>,
+}
+
+impl SharedModbus {
+    pub fn new(inner: Box<dyn ModbusLike>) -> Self {
+        let connected = inner.connection_status();
+        let stats = inner.connection_stats();
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(inner)),
+            connected: std::sync::Arc::new(std::sync::Mutex::new(connected)),
+            stats: std::sync::Arc::new(std::sync::Mutex::new(stats)),
+            pending_writes: std::sync::Arc::default(),
+            writes_done: std::sync::Arc::default(),
+        }
+    }
+
+    /// Wait until no write is queued
+    async fn yield_to_writes(&self) {
+        loop {
+            let done = self.writes_done.notified();
+            if self.pending_writes.load(Ordering::Acquire) == 0 {
+                return;
+            }
+            done.await;
+        }
+    }
+
+    fn record_status(&self, inner: &dyn ModbusLike) {
+        if let Ok(mut c) = self.connected.lock() {
+            *c = inner.connection_status();
+        }
+        if let Ok(mut s) = self.stats.lock() {
+            *s = inner.connection_stats();
+        }
+    }
+}
+
+/// Marks a write as queued until it completes or is cancelled
+struct QueuedWrite<'a>(&'a SharedModbus);
+
+impl<'a> QueuedWrite<'a> {
+    fn new(shared: &'a SharedModbus) -> Self {
+        shared.pending_writes.fetch_add(1, Ordering::AcqRel);
+        Self(shared)
+    }
+}
+
+impl Drop for QueuedWrite<'_> {
+    fn drop(&mut self) {
+        if self.0.pending_writes.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.writes_done.notify_waiters();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusLike for SharedModbus {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn connection_status(&self) -> Option<bool> {
+        self.connected.lock().ok().and_then(|c| *c)
+    }
+
+    fn connection_stats(&self) -> Option<crate::modbus::ConnectionStats> {
+        self.stats.lock().ok().and_then(|s| s.clone())
+    }
+
+    async fn keepalive(&mut self) -> Result<bool> {
+        // A busy connection needs no keepalive
+        let Ok(mut inner) = self.inner.try_lock() else {
+            return Ok(false);
+        };
+        let res = inner.keepalive().await;
+        self.record_status(inner.as_ref());
+        res
+    }
+
+    async fn close(&mut self) {
+        let mut inner = self.inner.lock().await;
+        inner.close().await;
+        self.record_status(inner.as_ref());
+    }
+
+    async fn ensure_connected(&mut self) -> bool {
+        let mut inner = self.inner.lock().await;
+        let up = inner.ensure_connected().await;
+        self.record_status(inner.as_ref());
+        up
+    }
+
+    async fn read_holding_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>> {
+        let mut inner = loop {
+            self.yield_to_writes().await;
+            let inner = self.inner.lock().await;
+            // A write queued while this read waited for the connection goes first
+            if self.pending_writes.load(Ordering::Acquire) == 0 {
+                break inner;
+            }
+        };
+        let res = inner.read_holding_registers(slave_id, address, count).await;
+        self.record_status(inner.as_ref());
+        res
+    }
+
+    async fn write_multiple_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        values: &[u16],
+    ) -> Result<()> {
+        let _queued = QueuedWrite::new(self);
+        let mut inner = self.inner.lock().await;
+        let res = inner
+            .write_multiple_registers(slave_id, address, values)
+            .await;
+        self.record_status(inner.as_ref());
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records the order of requests; each takes a moment
+    struct SlowModbus(Arc<Mutex<Vec<&'static str>>>);
+
+    #[async_trait::async_trait]
+    impl ModbusLike for SlowModbus {
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+        async fn read_holding_registers(&mut self, _: u8, _: u16, _: u16) -> Result<Vec<u16>> {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.0.lock().unwrap().push("read");
+            Ok(vec![])
+        }
+        async fn write_multiple_registers(&mut self, _: u8, _: u16, _: &[u16]) -> Result<()> {
+            self.0.lock().unwrap().push("write");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn queued_write_preempts_queued_read() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let shared = SharedModbus::new(Box::new(SlowModbus(log.clone())));
+        let (mut a, mut b, mut c) = (shared.clone(), shared.clone(), shared);
+        let busy = tokio::spawn(async move { a.read_holding_registers(1, 0, 1).await });
+        tokio::task::yield_now().await;
+        let read = tokio::spawn(async move { b.read_holding_registers(1, 0, 1).await });
+        tokio::task::yield_now().await;
+        let write = tokio::spawn(async move { c.write_multiple_registers(1, 0, &[1]).await });
+        let _ = tokio::join!(busy, read, write);
+        assert_eq!(*log.lock().unwrap(), ["read", "write", "read"]);
+    }
+}
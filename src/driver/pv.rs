@@ -1,31 +1,140 @@
+/// Home battery state from `com.victronenergy.system`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct BatteryState {
+    /// State of charge (%)
+    pub soc: Option<f64>,
+    /// Battery power (W, positive = charging)
+    pub power: Option<f64>,
+}
+
+/// Share the PV excess with the home battery per `controls.battery_policy`.
+/// The excess counts battery charging power as available (EV first); the
+/// other policies take it away or add battery discharge on top.
+pub(crate) fn apply_battery_policy(
+    excess_w: f64,
+    battery: BatteryState,
+    controls: &crate::config::ControlsConfig,
+) -> f64 {
+    let adjusted = match controls.battery_policy.as_str() {
+        "battery_first"
+            if battery
+                .soc
+                .is_none_or(|soc| soc < f64::from(controls.battery_priority_soc)) =>
+        {
+            excess_w - battery.power.unwrap_or(0.0).max(0.0)
+        }
+        "battery_buffer"
+            if battery
+                .soc
+                .is_some_and(|soc| soc > f64::from(controls.battery_buffer_soc)) =>
+        {
+            excess_w + f64::from(controls.battery_max_discharge_w.max(0.0))
+        }
+        _ => excess_w,
+    };
+    adjusted.max(0.0)
+}
+
 impl super::AlfenDriver {
-    pub(crate) async fn calculate_excess_pv_power(&self, ev_power_w: f64) -> Option<f32> {
-        let dbus_guard = self.dbus.as_ref()?.lock().await;
-        async fn get_f64(svc: &crate::dbus::DbusService, path: &str) -> f64 {
-            match svc
-                .read_remote_value("com.victronenergy.system", path)
+    /// PV excess with the EV power subtracted per installation phase, so EV
+    /// load is only taken off the consumption of the phase it is drawn from
+    pub(crate) async fn calculate_excess_pv_power(&self, ev_power_w: [f64; 3]) -> Option<f32> {
+        if !self.remote_dbus_available() {
+            return None;
+        }
+        let get_f64 = async |path: &str| {
+            self.read_remote_value("com.victronenergy.system", path)
+                .await
+                .and_then(|v| {
+                    v.as_f64()
+                        .or_else(|| v.as_i64().map(|x| x as f64))
+                        .or_else(|| v.as_u64().map(|x| x as f64))
+                })
+                .unwrap_or(0.0)
+        };
+        let dc_pv = get_f64("/Dc/Pv/Power").await;
+        let ac_pv_l1 = get_f64("/Ac/PvOnOutput/L1/Power").await;
+        let ac_pv_l2 = get_f64("/Ac/PvOnOutput/L2/Power").await;
+        let ac_pv_l3 = get_f64("/Ac/PvOnOutput/L3/Power").await;
+        let total_pv = dc_pv + ac_pv_l1 + ac_pv_l2 + ac_pv_l3;
+        let mut adjusted_consumption = 0.0;
+        for (i, line) in ["L1", "L2", "L3"].iter().enumerate() {
+            let path = format!("/Ac/Consumption/{}/Power", line);
+            let cons = get_f64(&path).await;
+            adjusted_consumption += (cons - ev_power_w[i]).max(0.0);
+        }
+        let excess = (total_pv - adjusted_consumption).max(0.0);
+        let battery = if self.config.controls.battery_policy == "ev_first" {
+            BatteryState::default()
+        } else {
+            let read = async |path| {
+                self.read_remote_value("com.victronenergy.system", path)
+                    .await
+                    .and_then(|v| v.as_f64())
+                    .filter(|v| v.is_finite())
+            };
+            BatteryState {
+                soc: read("/Dc/Battery/Soc").await,
+                power: read("/Dc/Battery/Power").await,
+            }
+        };
+        Some(apply_battery_policy(excess, battery, &self.config.controls) as f32)
+    }
+
+    /// Grid power from the Victron grid meter (W, positive = import); `None`
+    /// without D-Bus or when no phase could be read
+    pub(crate) async fn read_grid_power(&self) -> Option<f32> {
+        if !self.remote_dbus_available() {
+            return None;
+        }
+        let mut total = None;
+        for line in ["L1", "L2", "L3"] {
+            let path = format!("/Ac/Grid/{}/Power", line);
+            if let Some(w) = self
+                .read_remote_value("com.victronenergy.system", &path)
                 .await
+                .and_then(|v| v.as_f64())
             {
-                Ok(v) => v
-                    .as_f64()
-                    .or_else(|| v.as_i64().map(|x| x as f64))
-                    .or_else(|| v.as_u64().map(|x| x as f64))
-                    .unwrap_or(0.0),
-                Err(_) => 0.0,
+                total = Some(total.unwrap_or(0.0) + w);
             }
         }
-        let dc_pv = get_f64(&dbus_guard, "/Dc/Pv/Power").await;
-        let ac_pv_l1 = get_f64(&dbus_guard, "/Ac/PvOnOutput/L1/Power").await;
-        let ac_pv_l2 = get_f64(&dbus_guard, "/Ac/PvOnOutput/L2/Power").await;
-        let ac_pv_l3 = get_f64(&dbus_guard, "/Ac/PvOnOutput/L3/Power").await;
-        let total_pv = dc_pv + ac_pv_l1 + ac_pv_l2 + ac_pv_l3;
-        let cons_l1 = get_f64(&dbus_guard, "/Ac/Consumption/L1/Power").await;
-        let cons_l2 = get_f64(&dbus_guard, "/Ac/Consumption/L2/Power").await;
-        let cons_l3 = get_f64(&dbus_guard, "/Ac/Consumption/L3/Power").await;
-        let consumption = cons_l1 + cons_l2 + cons_l3;
-        let adjusted_consumption = (consumption - ev_power_w).max(0.0);
-        let excess = (total_pv - adjusted_consumption).max(0.0);
-        Some(excess as f32)
+        total.map(|w: f64| w as f32)
+    }
+
+    /// Grid current per installation phase (A, positive = import) from
+    /// `controls.main_fuse_service`, or the system service when empty. The
+    /// sign follows the phase power; without a current reading it is derived
+    /// from power and phase voltage.
+    pub(crate) async fn read_grid_currents(&self) -> [Option<f32>; 3] {
+        if !self.remote_dbus_available() {
+            return [None; 3];
+        }
+        let service = self.config.controls.main_fuse_service.trim();
+        let (service, prefix) = if service.is_empty() {
+            ("com.victronenergy.system", "/Ac/Grid")
+        } else {
+            (service, "/Ac")
+        };
+        let mut out = [None; 3];
+        for (i, line) in ["L1", "L2", "L3"].iter().enumerate() {
+            let read = async |quantity: &str| {
+                let path = format!("{}/{}/{}", prefix, line, quantity);
+                self.read_remote_value(service, &path)
+                    .await
+                    .and_then(|v| v.as_f64())
+                    .filter(|v| v.is_finite())
+            };
+            let current = read("Current").await;
+            let power = read("Power").await;
+            out[i] = match (current, power) {
+                (Some(a), Some(w)) => Some(a.abs().copysign(w)),
+                (Some(a), None) => Some(a),
+                (None, Some(w)) => Some(w / f64::from(self.phase_voltage(i))),
+                (None, None) => None,
+            }
+            .map(|a| a as f32);
+        }
+        out
     }
 }
 
@@ -39,8 +148,43 @@ mod tests {
         let (tx, rx) = mpsc::unbounded_channel();
         let d = AlfenDriver::new(rx, tx).await.unwrap();
         // No D-Bus attached -> None
-        assert!(d.calculate_excess_pv_power(0.0).await.is_none());
+        assert!(d.calculate_excess_pv_power([0.0; 3]).await.is_none());
+        assert!(d.read_grid_power().await.is_none());
+        assert_eq!(d.read_grid_currents().await, [None; 3]);
     }
 
     // Positive-case with D-Bus is not reliable in unit tests without a real D-Bus.
+
+    #[test]
+    fn battery_policy_shares_pv_excess() {
+        use super::{BatteryState, apply_battery_policy};
+        let mut c = crate::config::ControlsConfig::default();
+        let charging = |soc| BatteryState {
+            soc: Some(soc),
+            power: Some(1500.0),
+        };
+        // EV first: battery charging power stays available
+        assert_eq!(apply_battery_policy(4000.0, charging(40.0), &c), 4000.0);
+
+        c.battery_policy = "battery_first".to_string();
+        assert_eq!(apply_battery_policy(4000.0, charging(40.0), &c), 2500.0);
+        assert_eq!(apply_battery_policy(1000.0, charging(40.0), &c), 0.0);
+        // Unknown SoC keeps the battery first; above the threshold EV first
+        assert_eq!(
+            apply_battery_policy(
+                4000.0,
+                BatteryState {
+                    soc: None,
+                    ..charging(0.0)
+                },
+                &c
+            ),
+            2500.0
+        );
+        assert_eq!(apply_battery_policy(4000.0, charging(85.0), &c), 4000.0);
+
+        c.battery_policy = "battery_buffer".to_string();
+        assert_eq!(apply_battery_policy(1000.0, charging(60.0), &c), 4000.0);
+        assert_eq!(apply_battery_policy(1000.0, charging(45.0), &c), 1000.0);
+    }
 }
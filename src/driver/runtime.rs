@@ -7,6 +7,9 @@ use crate::error::Result;
 
 use super::types::DriverSnapshot;
 
+/// How often an idle Modbus connection is checked for a due keepalive
+pub(crate) const KEEPALIVE_CHECK: Duration = Duration::from_secs(5);
+
 impl super::AlfenDriver {
     /// Create a new driver instance using configuration loaded from defaults.
     pub async fn new(
@@ -17,7 +20,19 @@ impl super::AlfenDriver {
             eprintln!("Failed to load configuration: {}", e);
             e
         })?;
-        Self::new_with_config(commands_rx, commands_tx, config).await
+        Self::new_with_config(commands_rx, commands_tx, config, 1).await
+    }
+
+    /// Create a driver for one socket of a (possibly multi-socket) station.
+    /// `config` should already be adjusted for the socket, see
+    /// [`super::sockets::config_for_socket`].
+    pub async fn new_for_socket(
+        commands_rx: mpsc::UnboundedReceiver<super::types::DriverCommand>,
+        commands_tx: mpsc::UnboundedSender<super::types::DriverCommand>,
+        config: crate::config::Config,
+        socket: u8,
+    ) -> Result<Self> {
+        Self::new_with_config(commands_rx, commands_tx, config, socket.max(1)).await
     }
 
     /// Create a new driver instance using an optional override config path.
@@ -33,7 +48,7 @@ impl super::AlfenDriver {
                 eprintln!("Failed to load configuration: {}", e);
                 e
             })?;
-        Self::new_with_config(commands_rx, commands_tx, config).await
+        Self::new_with_config(commands_rx, commands_tx, config, 1).await
     }
 
     /// Internal constructor that builds the driver from a provided Config.
@@ -41,6 +56,7 @@ impl super::AlfenDriver {
         commands_rx: mpsc::UnboundedReceiver<super::types::DriverCommand>,
         commands_tx: mpsc::UnboundedSender<super::types::DriverCommand>,
         config: crate::config::Config,
+        socket: u8,
     ) -> Result<Self> {
         // Initialize logging
         crate::logging::init_logging(&config.logging)?;
@@ -54,10 +70,13 @@ impl super::AlfenDriver {
         let (state_tx, state_rx) = watch::channel(super::types::DriverState::Initializing);
 
         logger.info("Initializing EV charger driver");
+        let backend = crate::backend::from_config(&config)?;
+        let poll_scheduler = super::scheduler::PollScheduler::new(&config.scheduler);
 
         // Initialize persistence and load any saved state (best-effort)
-        let mut persistence =
-            crate::persistence::PersistenceManager::new("/data/phaeton_state.json");
+        let mut persistence = crate::persistence::PersistenceManager::new(
+            &super::sockets::state_file_for_socket(socket),
+        );
         let _ = persistence.load();
 
         // Initialize session manager and restore previous session state if available
@@ -69,11 +88,7 @@ impl super::AlfenDriver {
         // Restore control states from persistence
         let mut current_mode = crate::controls::ChargingMode::Manual;
         if let Some(mode_val) = persistence.get::<u32>("mode") {
-            current_mode = match mode_val {
-                1 => crate::controls::ChargingMode::Auto,
-                2 => crate::controls::ChargingMode::Scheduled,
-                _ => crate::controls::ChargingMode::Manual,
-            };
+            current_mode = crate::controls::ChargingMode::from_code(mode_val);
         }
 
         let mut start_stop = crate::controls::StartStopState::Stopped;
@@ -102,23 +117,24 @@ impl super::AlfenDriver {
             applied_current: 0.0,
             station_max_current: 0.0,
             device_instance: config.device_instance,
+            socket,
             product_name: None,
             firmware: None,
             serial: None,
             status: 0,
             active_phases: 0,
-            ac_power: 0.0,
-            ac_current: 0.0,
-            l1_voltage: 0.0,
-            l2_voltage: 0.0,
-            l3_voltage: 0.0,
-            l1_current: 0.0,
-            l2_current: 0.0,
-            l3_current: 0.0,
-            l1_power: 0.0,
-            l2_power: 0.0,
-            l3_power: 0.0,
-            total_energy_kwh: 0.0,
+            ac_power: None,
+            ac_current: None,
+            l1_voltage: None,
+            l2_voltage: None,
+            l3_voltage: None,
+            l1_current: None,
+            l2_current: None,
+            l3_current: None,
+            l1_power: None,
+            l2_power: None,
+            l3_power: None,
+            total_energy_kwh: None,
             pricing_currency: None,
             energy_rate: None,
             session: serde_json::json!({}),
@@ -130,30 +146,52 @@ impl super::AlfenDriver {
             modbus_connected: None,
             driver_state: "Initializing".to_string(),
             poll_steps_ms: None,
+            scn: None,
+            setpoint_accepted: None,
+            actual_applied_current: None,
+            validity: None,
+            meter: None,
+            meter_ok: None,
+            meter_age_ms: None,
+            meter_health: None,
+            thermal: None,
+            station_operative: None,
+            backoffice_connected: None,
+            conditions: Vec::new(),
+            mode3: None,
+            clock: None,
+            modbus_stats: None,
+            main_fuse: None,
         });
         let (status_snapshot_tx, status_snapshot_rx) =
             watch::channel::<Arc<DriverSnapshot>>(initial_snapshot);
 
         Ok(Self {
             config,
+            socket,
             state: state_tx,
             state_rx,
             modbus_manager: None,
+            backend,
+            poll_scheduler,
             logger,
             shutdown_tx,
             shutdown_rx,
             persistence,
             sessions,
             dbus: None,
+            dbus_capture: None,
             controls: crate::controls::ChargingControls::new(),
             current_mode,
             start_stop,
             intended_set_current,
             station_max_current: 32.0,
             last_sent_current: 0.0,
-            last_current_set_time: std::time::Instant::now(),
-            last_set_current_monotonic: std::time::Instant::now(),
+            last_current_set_time: tokio::time::Instant::now(),
+            last_set_current_monotonic: tokio::time::Instant::now(),
             last_status: 0,
+            session_status: 0,
+            last_base_status: 0,
 
             min_charge_timer_deadline: None,
             auto_mode_entered_at: None,
@@ -162,17 +200,20 @@ impl super::AlfenDriver {
             status_tx,
             status_snapshot_tx,
             status_snapshot_rx,
-            last_l1_voltage: 0.0,
-            last_l2_voltage: 0.0,
-            last_l3_voltage: 0.0,
-            last_l1_current: 0.0,
-            last_l2_current: 0.0,
-            last_l3_current: 0.0,
-            last_l1_power: 0.0,
-            last_l2_power: 0.0,
-            last_l3_power: 0.0,
-            last_total_power: 0.0,
-            last_energy_kwh: 0.0,
+            last_l1_voltage: None,
+            last_l2_voltage: None,
+            last_l3_voltage: None,
+            last_l1_current: None,
+            last_l2_current: None,
+            last_l3_current: None,
+            last_l1_power: None,
+            last_l2_power: None,
+            last_l3_power: None,
+            last_total_power: None,
+            last_energy_kwh: None,
+            voltage_ema: Default::default(),
+            grid_zero: Default::default(),
+            grid_zero_at: None,
             product_name: None,
             firmware_version: None,
             serial: None,
@@ -187,6 +228,26 @@ impl super::AlfenDriver {
             last_phase_switch: None,
             phase_settle_deadline: None,
             phase_switch_to: None,
+            scn_status: None,
+            scn_limit_written: None,
+            setpoint_accepted: None,
+            actual_applied_current: None,
+            setpoint_retry: None,
+            setpoint_verify: None,
+            validity: None,
+            meter_telemetry: None,
+            meter_health: None,
+            meter_age_ms: None,
+            main_fuse: None,
+            main_fuse_stop: Default::default(),
+            thermal: None,
+            station_operative: None,
+            backoffice_connected: None,
+            mode3: None,
+            clock: None,
+            last_clock_check: None,
+            wall_clock: Default::default(),
+            reassert_pending: false,
         })
     }
 
@@ -227,20 +288,15 @@ impl super::AlfenDriver {
 
         // Main polling loop
         let mut poll_interval = interval(Duration::from_millis(self.config.poll_interval_ms));
+        let mut keepalive = interval(KEEPALIVE_CHECK);
 
         loop {
             tokio::select! {
                 _ = poll_interval.tick() => {
-                    let poll_started = std::time::Instant::now();
-                    if let Err(e) = self.poll_cycle().await {
-                        self.logger.error(&format!("Poll cycle failed: {}", e));
-                        // Continue polling even on errors
-                    }
-                    let dur_ms = poll_started.elapsed().as_millis() as u64;
-                    self.total_polls = self.total_polls.saturating_add(1);
-                    if dur_ms > self.config.poll_interval_ms {
-                        self.overrun_count = self.overrun_count.saturating_add(1);
-                    }
+                    self.timed_poll_cycle().await;
+                }
+                _ = keepalive.tick() => {
+                    self.modbus_keepalive().await;
                 }
                 Some(cmd) = self.commands_rx.recv() => {
                     self.handle_command(cmd).await;
@@ -261,28 +317,91 @@ impl super::AlfenDriver {
         Ok(())
     }
 
-    /// Initialize Modbus connection
+    /// One poll cycle with duration and overrun accounting
+    async fn timed_poll_cycle(&mut self) {
+        let poll_started = tokio::time::Instant::now();
+        if let Err(e) = self.poll_cycle().await {
+            self.logger.error(&format!("Poll cycle failed: {}", e));
+            // Continue polling even on errors
+        }
+        let dur_ms = poll_started.elapsed().as_millis() as u64;
+        self.total_polls = self.total_polls.saturating_add(1);
+        if dur_ms > self.config.poll_interval_ms {
+            self.overrun_count = self.overrun_count.saturating_add(1);
+        }
+    }
+
+    /// Initialize Modbus connection. A connection attached beforehand (e.g. one
+    /// shared between the sockets of a twin station) is kept as is.
     pub(crate) async fn initialize_modbus(&mut self) -> Result<()> {
+        if self.modbus_manager.is_some() {
+            self.logger.info("Using attached Modbus connection");
+            return Ok(());
+        }
         let manager = crate::modbus::ModbusConnectionManager::new(
             &self.config.modbus,
-            self.config.controls.max_retries,
             Duration::from_secs_f64(self.config.controls.retry_delay),
         );
 
-        self.modbus_manager = Some(Box::new(manager));
+        let sink = super::capture::CaptureSink::maybe_open(&self.config.modbus.capture_file)?
+            .map(|sink| sink.with_clock(self.wall_clock));
+        self.modbus_manager = Some(super::capture::maybe_record(
+            Box::new(manager),
+            sink.as_ref(),
+        ));
+        self.dbus_capture = sink.map(super::capture::DbusCapture::Record);
         self.logger.info("Modbus connection manager initialized");
         Ok(())
     }
 
+    /// Keep an idle Modbus connection open (see `modbus.keepalive_s`)
+    pub(crate) async fn modbus_keepalive(&mut self) {
+        let Some(manager) = self.modbus_manager.as_mut() else {
+            return;
+        };
+        match manager.keepalive().await {
+            Ok(true) => self.logger.debug("Sent Modbus keepalive"),
+            Ok(false) => {}
+            Err(e) => self.logger.warn(&format!("Modbus keepalive failed: {}", e)),
+        }
+    }
+
+    /// Attach an existing Modbus connection instead of creating a new one
+    pub fn attach_modbus(&mut self, modbus: Box<dyn super::modbus_like::ModbusLike>) {
+        self.modbus_manager = Some(modbus);
+    }
+
+    /// Replay a traffic capture: Modbus and D-Bus reads come from the
+    /// capture and the wall clock starts at its first record
+    pub fn attach_replay(&mut self, replay: super::capture::ReplayModbus) {
+        if let Some(start) = replay.start_time() {
+            self.wall_clock = super::wall_clock::WallClock::starting_at(start);
+        }
+        self.dbus_capture = replay.dbus_capture();
+        self.attach_modbus(Box::new(replay));
+    }
+
+    /// Modbus connection for raw diagnostics (register inspector)
+    pub(crate) fn modbus_mut(
+        &mut self,
+    ) -> Option<&mut (dyn super::modbus_like::ModbusLike + 'static)> {
+        self.modbus_manager.as_deref_mut()
+    }
+
+    /// Socket number (1-based) on the station controlled by this driver
+    pub fn socket(&self) -> u8 {
+        self.socket
+    }
+
     // /// Single polling cycle
     // poll_cycle moved to runtime_poll.rs
     /// Shutdown the driver
     pub(crate) async fn shutdown(&mut self) -> Result<()> {
         self.logger.info("Shutting down driver");
 
-        if let Some(_manager) = self.modbus_manager.take() {
-            // Disconnect Modbus
-            // TODO: Implement proper disconnect
+        // Release the station's Modbus master slot
+        if let Some(mut manager) = self.modbus_manager.take() {
+            manager.close().await;
         }
 
         self.logger.info("Driver shutdown complete");
@@ -98,6 +98,24 @@ async fn init_dbus_if_configured(driver: &Arc<Mutex<AlfenDriver>>) -> Result<()>
     Ok(())
 }
 
+/// Keep the Modbus connection open while the poll loop is slow; a locked
+/// driver is busy polling and needs no keepalive. Ends after shutdown.
+fn spawn_keepalive_task(driver: Arc<Mutex<AlfenDriver>>) {
+    tokio::spawn(async move {
+        let mut ticker = interval(super::runtime::KEEPALIVE_CHECK);
+        loop {
+            ticker.tick().await;
+            let Ok(mut d) = driver.try_lock() else {
+                continue;
+            };
+            if d.modbus_manager.is_none() {
+                return;
+            }
+            d.modbus_keepalive().await;
+        }
+    });
+}
+
 async fn get_poll_interval_ms(driver: &Arc<Mutex<AlfenDriver>>) -> u64 {
     let d = driver.lock().await;
     d.config.poll_interval_ms
@@ -141,9 +159,12 @@ pub(crate) async fn run_on_arc_impl(driver: Arc<Mutex<AlfenDriver>>) -> Result<(
     // Initialization phase
     init_modbus_and_state(&driver).await?;
     init_dbus_if_configured(&driver).await?;
+    spawn_keepalive_task(driver.clone());
 
-    // Spawn background updater task (respects config flags)
-    spawn_updater_task(driver.clone());
+    // Spawn background updater task (respects config flags); once per process
+    if driver.lock().await.socket == 1 {
+        spawn_updater_task(driver.clone());
+    }
 
     let poll_interval_ms = get_poll_interval_ms(&driver).await;
     let mut ticker = interval(Duration::from_millis(poll_interval_ms));
@@ -1,42 +1,56 @@
 use crate::error::Result;
 use std::sync::Arc;
 
+mod availability;
+mod backend;
+mod clock;
+mod grid_zero;
 mod io;
+mod main_fuse;
 pub mod meas;
+mod meter_health;
+mod mode3;
 mod phase;
+mod rotation;
+mod schedule;
+mod scn;
 mod status;
+mod telemetry;
+mod thermal;
+mod validity;
+mod verify;
+mod voltage;
+use crate::driver::scheduler::PollGroup;
+pub(crate) use main_fuse::FuseStop;
 use meas::RealtimeMeasurements;
+pub(crate) use voltage::VoltageEma;
 
 impl super::AlfenDriver {
     // derive_status moved to status.rs
 
     async fn fetch_battery_soc_and_minimum_limit(&self) -> Option<(f64, f64)> {
-        let dbus_guard = self.dbus.as_ref()?.lock().await;
-        // Read battery SoC from com.victronenergy.system
-        async fn get_f64(svc: &crate::dbus::DbusService, service: &str, path: &str) -> Option<f64> {
-            match svc.read_remote_value(service, path).await {
-                Ok(v) => v
-                    .as_f64()
-                    .or_else(|| v.as_i64().map(|x| x as f64))
-                    .or_else(|| v.as_u64().map(|x| x as f64)),
-                Err(_) => None,
-            }
+        if !self.remote_dbus_available() {
+            return None;
         }
+        let get_f64 = async |service: &str, path: &str| {
+            self.read_remote_value(service, path).await.and_then(|v| {
+                v.as_f64()
+                    .or_else(|| v.as_i64().map(|x| x as f64))
+                    .or_else(|| v.as_u64().map(|x| x as f64))
+            })
+        };
 
-        let soc_opt = get_f64(&dbus_guard, "com.victronenergy.system", "/Dc/Battery/Soc").await;
+        // Read battery SoC from com.victronenergy.system
+        let soc_opt = get_f64("com.victronenergy.system", "/Dc/Battery/Soc").await;
         let soc = match soc_opt {
             Some(s) if s.is_finite() => s,
             _ => return None,
         };
 
         // Find MinimumSocLimit from any com.victronenergy.multi.* device
-        let names = dbus_guard
-            .list_service_names_with_prefix("com.victronenergy.multi")
-            .await
-            .unwrap_or_default();
+        let names = self.list_remote_services("com.victronenergy.multi").await;
         for svc_name in names {
-            if let Some(min) =
-                get_f64(&dbus_guard, &svc_name, "/Settings/Ess/MinimumSocLimit").await
+            if let Some(min) = get_f64(&svc_name, "/Settings/Ess/MinimumSocLimit").await
                 && min.is_finite()
             {
                 return Some((soc, min));
@@ -45,51 +59,36 @@ impl super::AlfenDriver {
         None
     }
 
-    #[cfg(test)]
-    async fn update_station_max_current_from_modbus(&mut self) {
-        let station_id = self.config.modbus.station_slave_id;
-        let addr_station_max = self.config.registers.station_max_current;
-        let manager = self.modbus_manager.as_mut().unwrap();
-        if let Ok(max_regs) = manager
-            .read_holding_registers(station_id, addr_station_max, 2)
-            .await
-            && max_regs.len() >= 2
-            && let Ok(max_c) = crate::modbus::decode_32bit_float(&max_regs[0..2])
-            && max_c.is_finite()
-            && max_c > 0.0
-        {
-            self.station_max_current = max_c;
-        }
-    }
-
     // read_realtime_values moved to io.rs
 
-    fn ev_power_for_subtract(&self, p_total: f64) -> f64 {
+    fn ev_power_for_subtract(&self, p_total: Option<f64>) -> f64 {
         let lag_ms = self.config.controls.ev_reporting_lag_ms as u128;
-        if self.last_set_current_monotonic.elapsed().as_millis() < lag_ms {
-            let phases = if self.applied_phases >= 3 {
-                3.0
-            } else if self.applied_phases == 1 {
-                1.0
-            } else {
-                // Unknown -> assume 3P to preserve previous behavior and test expectations
-                3.0
-            };
-            (self.last_sent_current as f64 * 230.0f64 * phases).max(0.0)
+        // A stale meter or unavailable power cannot be trusted either:
+        // estimate from the setpoint
+        let measured = p_total.filter(|_| !self.meter_stale());
+        if measured.is_none() || self.last_set_current_monotonic.elapsed().as_millis() < lag_ms {
+            // Unknown phases (0) -> assume 3P to preserve previous behavior
+            let phases = if self.applied_phases == 1 { 1 } else { 3 };
+            (self.last_sent_current as f64 * f64::from(self.watts_per_amp(phases))).max(0.0)
         } else {
-            p_total
+            measured.unwrap_or(0.0)
         }
     }
 
     fn should_send_update(&self, effective: f32) -> (bool, bool, bool) {
-        let interval_due = self.last_current_set_time.elapsed().as_millis()
-            >= self.config.controls.current_update_interval as u128;
-        let watchdog_due = self.last_current_set_time.elapsed().as_secs()
-            >= self.config.controls.watchdog_interval_seconds as u64;
-        let need_watchdog = interval_due || watchdog_due;
+        // Prefer the station's remaining setpoint validity; fall back to the
+        // fixed interval/watchdog when it cannot be read or is disabled
+        let reassert_due = self.reassert_pending
+            || self.validity_reassert_due().unwrap_or_else(|| {
+                let interval_due = self.last_current_set_time.elapsed().as_millis()
+                    >= self.config.controls.current_update_interval as u128;
+                let watchdog_due = self.last_current_set_time.elapsed().as_secs()
+                    >= self.config.controls.watchdog_interval_seconds as u64;
+                interval_due || watchdog_due
+            });
         let need_change = (effective - self.last_sent_current).abs()
             > self.config.controls.update_difference_threshold;
-        (need_watchdog || need_change, need_change, interval_due)
+        (reassert_due || need_change, need_change, reassert_due)
     }
 
     fn current_mode_reason(&self) -> &'static str {
@@ -97,20 +96,44 @@ impl super::AlfenDriver {
             crate::controls::ChargingMode::Manual => "manual",
             crate::controls::ChargingMode::Auto => "pv_auto",
             crate::controls::ChargingMode::Scheduled => "scheduled",
+            crate::controls::ChargingMode::MinPv => "min_pv",
         }
     }
 
     async fn write_effective_current(&mut self, effective: f32) -> bool {
-        let socket_id = self.config.modbus.socket_slave_id;
-        let addr_amps = self.config.registers.amps_config;
-        let regs = crate::modbus::encode_32bit_float(effective);
-        let write_res = self
-            .modbus_manager
-            .as_mut()
-            .unwrap()
-            .write_multiple_registers(socket_id, addr_amps, &regs)
-            .await;
-        write_res.is_ok()
+        if self.backend.alfen_extensions() && self.config.registers.uses_scn() {
+            return self.write_scn_setpoint(effective).await;
+        }
+        let Some(manager) = self.modbus_manager.as_mut() else {
+            return false;
+        };
+        match self
+            .backend
+            .write_current(manager.as_mut(), effective)
+            .await
+        {
+            Ok(()) => {
+                self.logger.debug(&format!(
+                    "Wrote setpoint {:.2} A ({})",
+                    effective,
+                    self.current_mode_reason()
+                ));
+                // The Alfen readback (1206/1214) is checked on a later cycle
+                if self.backend.alfen_extensions() {
+                    self.schedule_setpoint_verification(effective);
+                }
+                true
+            }
+            Err(e) => {
+                self.logger.warn(&format!(
+                    "Failed to write {:.1} A via {} backend: {}",
+                    effective,
+                    self.backend.name(),
+                    e
+                ));
+                false
+            }
+        }
     }
 
     async fn compute_effective_current_with_soc(
@@ -121,6 +144,7 @@ impl super::AlfenDriver {
     ) -> (f32, Option<bool>) {
         // Determine assumed phases for conversion based on applied phases
         let assumed_phases = if self.applied_phases >= 3 { 3 } else { 1 };
+        let voltages = self.charging_voltages(assumed_phases);
         let mut effective: f32 = self
             .controls
             .compute_effective_current(
@@ -131,10 +155,11 @@ impl super::AlfenDriver {
                 now_secs,
                 Some(excess_pv_power_w),
                 &self.config,
-                assumed_phases,
+                &voltages,
             )
             .await
             .unwrap_or(0.0);
+        self.apply_grid_zero(&mut effective).await;
         let soc_below_min = self.enforce_soc_limit_maybe(&mut effective).await;
         self.apply_insufficient_solar_grace_timer(soc_below_min, &mut effective);
         (effective, soc_below_min)
@@ -142,7 +167,7 @@ impl super::AlfenDriver {
 
     fn enforce_phase_settle_on_effective(&mut self, effective: &mut f32) {
         if let Some(deadline) = self.phase_settle_deadline {
-            if std::time::Instant::now() < deadline {
+            if tokio::time::Instant::now() < deadline {
                 if *effective > 0.0 {
                     self.logger
                         .debug("Phase switch settling active; forcing 0 A");
@@ -202,7 +227,7 @@ impl super::AlfenDriver {
         }
 
         let min_current = self.config.controls.min_set_current.max(0.0);
-        let now = std::time::Instant::now();
+        let now = tokio::time::Instant::now();
         let was_charging = self.last_sent_current >= (min_current - 0.05);
 
         // Only start (or keep) the grace timer if we were previously charging
@@ -290,13 +315,20 @@ impl super::AlfenDriver {
         cur_status: u8,
         effective: f32,
     ) -> Result<()> {
-        self.handle_session_transition(cur_status, m.energy_kwh);
-        self.sessions.update(m.total_power, m.energy_kwh)?;
+        // Stale or unavailable meter values would corrupt session energy;
+        // keep the session as is until the meter delivers again
+        let energy_kwh = m.energy_kwh.filter(|_| !self.meter_stale());
+        self.handle_session_transition(cur_status, energy_kwh);
+        if let Some(energy) = energy_kwh {
+            self.sessions.update(m.total_power.unwrap_or(0.0), energy)?;
+        }
         self.persist_state();
         self.update_last_measurements(m);
+        // Unavailable values are logged as NaN
+        let f = |v: Option<f64>| v.unwrap_or(f64::NAN);
         self.logger.debug(&format!(
             "V=({:.1},{:.1},{:.1})V I=({:.2},{:.2},{:.2})A P=({:.0},{:.0},{:.0})W total={:.0}W E={:.3}kWh status={} lag_ms={} last_sent_A={:.2}",
-            m.voltages.l1, m.voltages.l2, m.voltages.l3, m.currents.l1, m.currents.l2, m.currents.l3, m.powers.l1, m.powers.l2, m.powers.l3, m.total_power, m.energy_kwh, cur_status,
+            f(m.voltages.l1), f(m.voltages.l2), f(m.voltages.l3), f(m.currents.l1), f(m.currents.l2), f(m.currents.l3), f(m.powers.l1), f(m.powers.l2), f(m.powers.l3), f(m.total_power), f(m.energy_kwh), cur_status,
             self.last_set_current_monotonic.elapsed().as_millis(), self.last_sent_current
         ));
         let _ = self
@@ -305,9 +337,17 @@ impl super::AlfenDriver {
         Ok(())
     }
 
-    fn handle_session_transition(&mut self, cur_status: u8, energy_kwh: f64) {
-        let prev_status = self.last_status;
-        if cur_status == 2 && prev_status != 2 && self.sessions.current_session.is_none() {
+    fn handle_session_transition(&mut self, cur_status: u8, energy_kwh: Option<f64>) {
+        self.last_status = cur_status;
+        let prev_status = self.session_status;
+        let Some(energy_kwh) = energy_kwh else {
+            return;
+        };
+        if cur_status == 2
+            && prev_status != 2
+            && self.sessions.current_session.is_none()
+            && !self.station_inoperative()
+        {
             let _ = self.sessions.start_session(energy_kwh);
         } else if cur_status != 2
             && self.sessions.current_session.is_some()
@@ -318,7 +358,7 @@ impl super::AlfenDriver {
             let cost = last.energy_delivered_kwh * self.config.pricing.static_rate_eur_per_kwh;
             self.sessions.set_cost_on_last_session(cost);
         }
-        self.last_status = cur_status;
+        self.session_status = cur_status;
     }
 
     fn persist_state(&mut self) {
@@ -345,7 +385,7 @@ impl super::AlfenDriver {
         self.last_energy_kwh = m.energy_kwh;
     }
 
-    fn build_status_json(&self, effective: f32, p_total: f64) -> String {
+    fn build_status_json(&self, effective: f32, p_total: Option<f64>) -> String {
         let mut status_obj = serde_json::json!({
             "mode": self.current_mode_code(),
             "start_stop": self.start_stop_code(),
@@ -368,16 +408,22 @@ impl super::AlfenDriver {
 
     pub(crate) async fn poll_cycle(&mut self) -> Result<()> {
         self.logger.debug("Starting poll cycle");
-        if self.modbus_manager.is_some() {
+        self.last_poll_steps = Some(Default::default());
+        // While the connection is down (reconnecting in the background) the
+        // cycle skips Modbus and only publishes the snapshot
+        let online = match self.modbus_manager.as_mut() {
+            Some(m) => m.ensure_connected().await,
+            None => false,
+        };
+        let cycle_started = tokio::time::Instant::now();
+        if online {
             let m = self.read_realtime_values().await;
-            let now_secs = (std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default())
-            .as_secs_f64();
+            self.update_voltage_ema(&m.voltages);
+            self.refresh_groups_before_write().await;
+            let now_secs = self.wall_clock.unix_secs();
             let requested = self.intended_set_current;
 
-            let (excess_pv_power_w, pv_excess_ms) =
-                self.compute_pv_excess_smoothed(m.total_power).await;
+            let (excess_pv_power_w, pv_excess_ms) = self.compute_pv_excess_smoothed(&m).await;
             self.maybe_evaluate_auto_phase_switch(excess_pv_power_w)
                 .await;
             let (effective, soc_below_min, compute_effective_ms) = self
@@ -395,8 +441,11 @@ impl super::AlfenDriver {
         }
 
         self.refresh_identity_on_connection_edge().await;
+        if online {
+            self.refresh_groups_after_write(cycle_started).await;
+        }
         self.logger.debug("Poll cycle completed");
-        let t_snap0 = std::time::Instant::now();
+        let t_snap0 = tokio::time::Instant::now();
         let snapshot = Arc::new(self.build_typed_snapshot(Some(self.last_poll_duration_ms())));
         if let Some(ref mut steps) = self.last_poll_steps {
             steps.snapshot_build_ms = Some(t_snap0.elapsed().as_millis() as u64);
@@ -405,11 +454,12 @@ impl super::AlfenDriver {
         Ok(())
     }
 
-    async fn compute_pv_excess_smoothed(&mut self, total_power: f64) -> (f32, u64) {
-        let t0 = std::time::Instant::now();
-        let ev_power_for_subtract = self.ev_power_for_subtract(total_power);
+    async fn compute_pv_excess_smoothed(&mut self, m: &RealtimeMeasurements) -> (f32, u64) {
+        let t0 = tokio::time::Instant::now();
+        let ev_power_for_subtract = self.ev_power_for_subtract(m.total_power);
+        let ev_per_phase = self.ev_power_per_phase(ev_power_for_subtract, &m.powers);
         let raw: f32 = self
-            .calculate_excess_pv_power(ev_power_for_subtract)
+            .calculate_excess_pv_power(ev_per_phase)
             .await
             .unwrap_or(0.0);
         let ms = t0.elapsed().as_millis() as u64;
@@ -424,9 +474,7 @@ impl super::AlfenDriver {
     }
 
     async fn maybe_evaluate_auto_phase_switch(&mut self, excess_pv_power_w: f32) {
-        if matches!(self.current_mode, crate::controls::ChargingMode::Auto)
-            && self.config.controls.auto_phase_switch
-        {
+        if self.current_mode.follows_pv() && self.config.controls.auto_phase_switch {
             self.evaluate_auto_phase_switch(excess_pv_power_w).await;
         }
     }
@@ -437,24 +485,32 @@ impl super::AlfenDriver {
         now_secs: f64,
         excess_pv_power_w: f32,
     ) -> (f32, Option<bool>, u64) {
-        let t0 = std::time::Instant::now();
+        let t0 = tokio::time::Instant::now();
         let (mut effective, soc_below_min) = self
             .compute_effective_current_with_soc(requested, now_secs, excess_pv_power_w)
             .await;
         self.enforce_phase_settle_on_effective(&mut effective);
+        self.apply_thermal_derating(&mut effective);
+        self.apply_main_fuse_limit(&mut effective).await;
+        self.apply_scn_limit(&mut effective);
+        self.track_grid_zero(effective);
         let ms = t0.elapsed().as_millis() as u64;
         (effective, soc_below_min, ms)
     }
 
     async fn maybe_write_current(&mut self, effective: f32, excess_pv_power_w: f32) -> Option<u64> {
+        if self.station_inoperative() || self.scn_writes_blocked() {
+            return None;
+        }
         let (should_update, _need_change, _interval_due) =
             self.apply_current_if_needed(effective, excess_pv_power_w);
-        if should_update {
-            let t0 = std::time::Instant::now();
+        if should_update || self.setpoint_retry_due() {
+            let t0 = tokio::time::Instant::now();
             if self.write_effective_current(effective).await {
+                self.reassert_pending = false;
                 self.last_sent_current = effective;
-                self.last_current_set_time = std::time::Instant::now();
-                self.last_set_current_monotonic = std::time::Instant::now();
+                self.last_current_set_time = tokio::time::Instant::now();
+                self.last_set_current_monotonic = tokio::time::Instant::now();
             } else {
                 self.logger.warn("Failed to write set current via Modbus");
             }
@@ -466,7 +522,7 @@ impl super::AlfenDriver {
 
     fn derive_final_status(&mut self, base_status: i32, soc_below_min: Option<bool>) -> u8 {
         if let Some(deadline) = self.phase_settle_deadline
-            && std::time::Instant::now() < deadline
+            && tokio::time::Instant::now() < deadline
             && let Some(to) = self.phase_switch_to
         {
             if to >= 3 { 22 } else { 23 }
@@ -482,7 +538,7 @@ impl super::AlfenDriver {
         derived_status: u8,
         effective: f32,
     ) -> Result<u64> {
-        let t0 = std::time::Instant::now();
+        let t0 = tokio::time::Instant::now();
         self.finalize_cycle(m, derived_status, effective)?;
         Ok(t0.elapsed().as_millis() as u64)
     }
@@ -512,7 +568,11 @@ impl super::AlfenDriver {
             if prev != Some(conn) {
                 self.last_modbus_connected = Some(conn);
                 if conn {
+                    // Everything may have changed while disconnected
+                    self.poll_scheduler.reset();
                     let _ = self.refresh_charger_identity().await;
+                    self.poll_scheduler
+                        .mark_run(PollGroup::Identity, tokio::time::Instant::now());
                 }
             }
         }
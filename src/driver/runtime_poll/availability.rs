@@ -0,0 +1,150 @@
+//! Station availability (1200) and OCPP back office state (1104)
+
+/// Victron status published while the station is inoperative (10, Error);
+/// the cause is on `/Phaeton/StationOperative` and in the conditions
+pub(crate) const STATUS_INOPERATIVE: i32 = 10;
+
+impl crate::driver::AlfenDriver {
+    async fn read_u16(&mut self, slave: u8, addr: u16) -> Option<u16> {
+        self.modbus_manager
+            .as_mut()?
+            .read_holding_registers(slave, addr, 1)
+            .await
+            .ok()?
+            .first()
+            .copied()
+    }
+
+    /// Read availability and OCPP state and apply their transitions
+    pub(super) async fn refresh_availability(&mut self) {
+        let t0 = tokio::time::Instant::now();
+        let availability = self
+            .read_u16(
+                self.config.modbus.socket_slave_id,
+                self.config.registers.availability,
+            )
+            .await;
+        let ocpp_state = self
+            .read_u16(
+                self.config.modbus.station_slave_id,
+                self.config.registers.ocpp_state,
+            )
+            .await;
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_availability_ms = Some(t0.elapsed().as_millis() as u64);
+        self.update_availability(availability.map(|v| v == 1), ocpp_state.map(|v| v != 0));
+    }
+
+    pub(super) fn update_availability(
+        &mut self,
+        operative: Option<bool>,
+        backoffice_connected: Option<bool>,
+    ) {
+        // An unreadable register keeps the last known state
+        if let Some(operative) = operative
+            && self.station_operative != Some(operative)
+        {
+            if operative {
+                self.logger
+                    .info("Station is operative again; resuming setpoint control");
+            } else {
+                self.logger
+                    .warn("Station is inoperative; pausing setpoint writes");
+                self.interrupt_running_session();
+            }
+            self.station_operative = Some(operative);
+        }
+        if let Some(connected) = backoffice_connected
+            && self.backoffice_connected != Some(connected)
+        {
+            // First reading of a connected back office is not worth a log line
+            if !connected {
+                self.logger.warn("OCPP back office disconnected");
+            } else if self.backoffice_connected.is_some() {
+                self.logger.info("OCPP back office connected");
+            }
+            self.backoffice_connected = Some(connected);
+        }
+    }
+
+    fn interrupt_running_session(&mut self) {
+        if self.sessions.current_session.is_none() {
+            return;
+        }
+        let energy = self.last_energy_kwh.filter(|_| !self.meter_stale());
+        if let Err(e) = self.sessions.interrupt_session(energy) {
+            self.logger
+                .error(&format!("Failed to interrupt charging session: {}", e));
+        }
+    }
+
+    pub(super) fn station_inoperative(&self) -> bool {
+        self.station_operative == Some(false)
+    }
+
+    /// Active abnormal conditions reported in the snapshot
+    pub(crate) fn active_conditions(&self) -> Vec<String> {
+        [
+            (self.station_inoperative(), "station_inoperative"),
+            (
+                self.backoffice_connected == Some(false),
+                "backoffice_disconnected",
+            ),
+            (self.thermal_stopped(), "overheating"),
+            (self.meter_stale(), "meter_stale"),
+        ]
+        .into_iter()
+        .filter(|(active, _)| *active)
+        .map(|(_, name)| name.to_string())
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockModbus;
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn inoperative_station_interrupts_session_and_blocks_writes() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let socket = d.config().modbus.socket_slave_id;
+        let station = d.config().modbus.station_slave_id;
+        d.sessions.start_session(10.0).unwrap();
+        d.last_energy_kwh = Some(12.5);
+        d.modbus_manager = Some(Box::new(
+            MockModbus::new()
+                .with_read(socket, 1200, 1, vec![0])
+                .with_read(station, 1104, 1, vec![0]),
+        ));
+        d.refresh_availability().await;
+
+        assert!(d.station_inoperative());
+        assert!(d.sessions.current_session.is_none());
+        let last = d.sessions.last_session.as_ref().unwrap();
+        assert!(matches!(
+            last.status,
+            crate::session::SessionStatus::Interrupted
+        ));
+        assert!((last.energy_delivered_kwh - 2.5).abs() < 1e-9);
+        assert_eq!(d.maybe_write_current(16.0, 0.0).await, None);
+        assert_eq!(d.derive_status(1, None), STATUS_INOPERATIVE);
+
+        let snap = d.build_typed_snapshot(None);
+        assert_eq!(snap.station_operative, Some(false));
+        assert_eq!(snap.backoffice_connected, Some(false));
+        assert_eq!(
+            snap.conditions,
+            vec!["station_inoperative", "backoffice_disconnected"]
+        );
+
+        // Unreadable registers keep the last state; recovery clears it
+        d.update_availability(None, None);
+        assert!(d.station_inoperative());
+        d.update_availability(Some(true), Some(true));
+        assert!(d.build_typed_snapshot(None).conditions.is_empty());
+    }
+}
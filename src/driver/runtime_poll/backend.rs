@@ -0,0 +1,120 @@
+// Realtime reads through a non-Alfen charger backend
+//
+// Profile backends expose only the common measurement set, so the Alfen
+// meter header, telemetry block and Mode 3 tracking are skipped. Missing
+// per-phase power is derived from V·I and a missing total from the phases,
+// the same way the Alfen decoder fills gaps. Status and setpoint writes go
+// through the backend for every charger (see io.rs and runtime_poll.rs);
+// setpoints of profile backends skip the Alfen readback and validity checks.
+
+use super::meas::{LineTriplet, RealtimeMeasurements};
+
+impl crate::driver::AlfenDriver {
+    pub(super) async fn read_backend_values(&mut self) -> RealtimeMeasurements {
+        let t_meas = tokio::time::Instant::now();
+        let meas = match self.modbus_manager.as_mut() {
+            Some(manager) => self
+                .backend
+                .read_measurements(manager.as_mut())
+                .await
+                .unwrap_or_default(),
+            None => Default::default(),
+        };
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_voltages_ms = Some(t_meas.elapsed().as_millis() as u64);
+        let status = self.read_status_if_due().await;
+        self.meter_telemetry = None;
+        self.update_meter_health(None);
+
+        let [v1, v2, v3] = meas.voltages;
+        let [i1, i2, i3] = meas.currents;
+        let phase =
+            |p: Option<f64>, v: Option<f64>, i: Option<f64>| p.or_else(|| Some((v? * i?).round()));
+        let [p1, p2, p3] = meas.powers;
+        let powers = LineTriplet {
+            l1: phase(p1, v1, i1),
+            l2: phase(p2, v2, i2),
+            l3: phase(p3, v3, i3),
+        };
+        let total_power = meas.total_power.or_else(|| {
+            let known = [powers.l1, powers.l2, powers.l3];
+            known
+                .iter()
+                .any(Option::is_some)
+                .then(|| known.iter().flatten().sum())
+        });
+        let mut m = RealtimeMeasurements {
+            voltages: LineTriplet {
+                l1: v1,
+                l2: v2,
+                l3: v3,
+            },
+            currents: LineTriplet {
+                l1: i1,
+                l2: i2,
+                l3: i3,
+            },
+            powers,
+            total_power,
+            energy_kwh: meas.energy_kwh,
+            status,
+        };
+        self.apply_phase_rotation(&mut m);
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockModbus;
+    use crate::backend::ChargerProfile;
+    use tokio::sync::mpsc;
+
+    const PROFILE: &str = r#"
+name: Test wallbox
+slave_id: 5
+measurements:
+  voltages:
+    - {address: 10, type: u16, scale: 0.1}
+    - {address: 11, type: u16, scale: 0.1}
+    - {address: 12, type: u16, scale: 0.1}
+  currents:
+    - {address: 20, type: u16, scale: 0.1}
+    - {address: 21, type: u16, scale: 0.1}
+    - {address: 22, type: u16, scale: 0.1}
+  energy: {address: 30, type: u32, scale: 0.001}
+status:
+  register: {address: 40, type: u16}
+  values: {0: 0, 1: 1, 3: 2}
+current: {address: 50, type: u16, scale: 0.1}
+"#;
+
+    #[tokio::test]
+    async fn reads_profile_measurements_and_status() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        d.backend = Box::new(crate::backend::ProfileBackend::new(
+            ChargerProfile::from_yaml(PROFILE).unwrap(),
+        ));
+        d.modbus_manager = Some(Box::new(
+            MockModbus::new()
+                .with_read(5, 10, 1, vec![2300])
+                .with_read(5, 11, 1, vec![2310])
+                .with_read(5, 12, 1, vec![2320])
+                .with_read(5, 20, 1, vec![100])
+                .with_read(5, 21, 1, vec![0])
+                .with_read(5, 22, 1, vec![0])
+                .with_read(5, 30, 2, vec![0, 12_345])
+                .with_read(5, 40, 1, vec![3]),
+        ));
+        let m = d.read_realtime_values().await;
+        assert_eq!(m.voltages.l1, Some(230.0));
+        assert_eq!(m.currents.l1, Some(10.0));
+        assert_eq!(m.powers.l1, Some(2300.0));
+        assert_eq!(m.total_power, Some(2300.0));
+        assert!((m.energy_kwh.unwrap() - 12.345).abs() < 1e-9);
+        assert_eq!(m.status, 2);
+        assert!(d.meter_health.is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! Station clock, uptime and timezone checks (registers 168..178)
+
+use crate::driver::types::StationClock;
+use chrono::{DateTime, FixedOffset, NaiveDate, Offset, Utc};
+
+/// Number of registers in the clock block (168..178)
+const CLOCK_BLOCK_LEN: u16 = 11;
+
+/// Values decoded from the clock block
+pub(super) struct ClockRead {
+    local: Option<DateTime<FixedOffset>>,
+    uptime_ms: u64,
+    offset_minutes: i16,
+}
+
+pub(super) fn decode_station_clock(regs: &[u16]) -> Option<ClockRead> {
+    let regs = regs.get(..CLOCK_BLOCK_LEN as usize)?;
+    let offset_minutes = regs[10] as i16;
+    let uptime_ms = regs[6..10]
+        .iter()
+        .fold(0u64, |acc, w| (acc << 16) | u64::from(*w));
+    let local = FixedOffset::east_opt(i32::from(offset_minutes) * 60).and_then(|offset| {
+        NaiveDate::from_ymd_opt(
+            i32::from(regs[0] as i16),
+            u32::from(regs[1]),
+            u32::from(regs[2]),
+        )?
+        .and_hms_opt(u32::from(regs[3]), u32::from(regs[4]), u32::from(regs[5]))?
+        .and_local_timezone(offset)
+        .single()
+    });
+    Some(ClockRead {
+        local,
+        uptime_ms,
+        offset_minutes,
+    })
+}
+
+impl crate::driver::AlfenDriver {
+    /// Re-read the station clock once the check interval has elapsed
+    pub(super) async fn maybe_refresh_station_clock(&mut self) {
+        let interval = std::time::Duration::from_secs(u64::from(
+            self.config.controls.clock_check_interval_seconds,
+        ));
+        if self
+            .last_clock_check
+            .is_some_and(|t| t.elapsed() < interval)
+        {
+            return;
+        }
+        self.refresh_station_clock().await;
+    }
+
+    /// Read the clock block; an unreadable block keeps the last state
+    pub(crate) async fn refresh_station_clock(&mut self) {
+        self.last_clock_check = Some(tokio::time::Instant::now());
+        let slave = self.config.modbus.station_slave_id;
+        let addr = self.config.registers.station_clock;
+        let read = match self.modbus_manager.as_mut() {
+            Some(m) => m
+                .read_holding_registers(slave, addr, CLOCK_BLOCK_LEN)
+                .await
+                .ok()
+                .and_then(|regs| decode_station_clock(&regs)),
+            None => None,
+        };
+        if let Some(read) = read {
+            self.apply_station_clock(read, self.wall_clock.now());
+        }
+    }
+
+    pub(super) fn apply_station_clock(&mut self, read: ClockRead, now: DateTime<Utc>) {
+        let mut c = self.clock.take().unwrap_or_default();
+        let uptime_s = read.uptime_ms / 1000;
+        if c.uptime_s.is_some_and(|prev| uptime_s < prev) {
+            self.note_station_reboot(&mut c, uptime_s, now);
+        }
+        c.uptime_s = Some(uptime_s);
+        c.station_time = read.local.map(|t| t.to_rfc3339());
+
+        let drift = read.local.map(|t| {
+            t.with_timezone(&Utc)
+                .signed_duration_since(now)
+                .num_seconds()
+        });
+        let limit = i64::from(self.config.controls.clock_drift_warning_seconds);
+        let was_drifting = c.drift_s.is_some_and(|d| d.abs() > limit);
+        if let Some(d) = drift
+            && d.abs() > limit
+            && !was_drifting
+        {
+            self.logger.warn(&format!(
+                "Station clock differs {} s from the host clock",
+                d
+            ));
+        }
+        c.drift_s = drift;
+
+        let expected = self
+            .config
+            .timezone
+            .parse::<chrono_tz::Tz>()
+            .ok()
+            .map(|tz| now.with_timezone(&tz).offset().fix().local_minus_utc() / 60);
+        let mismatch = expected.is_some_and(|e| e != i32::from(read.offset_minutes));
+        if mismatch && !c.timezone_mismatch {
+            self.logger.warn(&format!(
+                "Station timezone offset {} min does not match configured timezone {} ({} min)",
+                read.offset_minutes,
+                self.config.timezone,
+                expected.unwrap_or_default()
+            ));
+        }
+        c.timezone_offset_minutes = Some(read.offset_minutes);
+        c.expected_offset_minutes = expected;
+        c.timezone_mismatch = mismatch;
+        c.checked_at = Some(now.to_rfc3339());
+        self.clock = Some(c);
+    }
+
+    fn note_station_reboot(&mut self, c: &mut StationClock, uptime_s: u64, now: DateTime<Utc>) {
+        c.reboots += 1;
+        c.last_reboot_at = Some(now.to_rfc3339());
+        self.logger.warn(&format!(
+            "Station reboot detected (uptime {} s); reasserting the setpoint",
+            uptime_s
+        ));
+        // The reboot cleared the max current validity
+        self.reassert_pending = true;
+        self.sessions.record_event(
+            "station_reboot",
+            &format!("Station rebooted (uptime {} s)", uptime_s),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::MockModbus;
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn block(time: [u16; 6], uptime_ms: u64, offset_minutes: i16) -> Vec<u16> {
+        let mut regs = time.to_vec();
+        regs.extend((0..4).rev().map(|i| (uptime_ms >> (16 * i)) as u16));
+        regs.push(offset_minutes as u16);
+        regs
+    }
+
+    #[test]
+    fn decodes_clock_block() {
+        let read = decode_station_clock(&block([2026, 3, 1, 13, 30, 5], 90_500, 60)).unwrap();
+        assert_eq!(read.uptime_ms, 90_500);
+        assert_eq!(read.offset_minutes, 60);
+        assert_eq!(
+            read.local.unwrap().to_rfc3339(),
+            "2026-03-01T13:30:05+01:00"
+        );
+        // Invalid date keeps uptime and offset usable
+        assert!(
+            decode_station_clock(&block([0, 0, 0, 0, 0, 0], 1, 0))
+                .unwrap()
+                .local
+                .is_none()
+        );
+        assert!(decode_station_clock(&[2026, 3]).is_none());
+    }
+
+    #[tokio::test]
+    async fn detects_reboot_drift_and_timezone_mismatch() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        // No daylight saving, so the expected offset does not depend on the date
+        cfg.timezone = "Asia/Kolkata".to_string();
+        cfg.controls.clock_drift_warning_seconds = 60;
+        d.update_config(cfg).unwrap();
+        let slave = d.config().modbus.station_slave_id;
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(
+            slave,
+            168,
+            11,
+            block([2026, 1, 15, 13, 0, 0], 3_600_000, 330),
+        )));
+        d.refresh_station_clock().await;
+        let c = d.clock.clone().unwrap();
+        assert_eq!(c.uptime_s, Some(3600));
+        assert_eq!(c.expected_offset_minutes, Some(330));
+        assert!(!c.timezone_mismatch);
+        assert!(!d.reassert_pending);
+
+        // Uptime went backwards: reboot; station still on UTC and 2 min ahead
+        d.sessions.start_session(1.0).unwrap();
+        let now = "2026-01-15T12:58:00Z".parse().unwrap();
+        d.apply_station_clock(
+            decode_station_clock(&block([2026, 1, 15, 13, 0, 0], 12_000, 0)).unwrap(),
+            now,
+        );
+        let c = d.clock.clone().unwrap();
+        assert_eq!(c.reboots, 1);
+        assert_eq!(c.drift_s, Some(120));
+        assert!(c.timezone_mismatch);
+        assert!(d.reassert_pending);
+        assert!(d.should_send_update(d.last_sent_current).0);
+        let events = &d.sessions.current_session.as_ref().unwrap().events;
+        assert_eq!(events[0].kind, "station_reboot");
+    }
+}
@@ -0,0 +1,101 @@
+//! Grid-zero Auto mode (`controls.auto_controller: grid_zero`)
+
+use crate::controls::{ChargingMode, GridZeroTuning, StartStopState};
+use tokio::time::Instant;
+
+/// Longest step fed to the integrator after a gap in updates (s)
+const MAX_STEP_S: f32 = 10.0;
+
+impl crate::driver::AlfenDriver {
+    fn grid_zero_enabled(&self) -> bool {
+        self.current_mode.follows_pv()
+            && matches!(self.start_stop, StartStopState::Enabled)
+            && self.config.controls.auto_controller == "grid_zero"
+    }
+
+    pub(super) async fn apply_grid_zero(&mut self, effective: &mut f32) {
+        if !self.grid_zero_enabled() {
+            self.grid_zero.deactivate();
+            return;
+        }
+        let Some(grid_w) = self.read_grid_power().await else {
+            if self.grid_zero.is_active() {
+                self.logger
+                    .warn("Grid-zero: grid power unavailable; using PV excess");
+            }
+            self.grid_zero.deactivate();
+            return;
+        };
+        let now = Instant::now();
+        if !self.grid_zero.is_active() {
+            // Bumpless start from what the station currently has
+            self.grid_zero.reset_to(self.last_sent_current);
+            self.grid_zero_at = Some(now);
+        }
+        let dt = self
+            .grid_zero_at
+            .map_or(0.0, |t| now.duration_since(t).as_secs_f32())
+            .min(MAX_STEP_S);
+        self.grid_zero_at = Some(now);
+        let phases = if self.applied_phases >= 3 { 3 } else { 1 };
+        let tuning = GridZeroTuning::from_config(&self.config.controls);
+        let watts_per_amp = self.watts_per_amp(phases);
+        let min_current = self.config.controls.min_set_current.max(0.0);
+        let min_pv = matches!(self.current_mode, ChargingMode::MinPv);
+        if !min_pv && self.grid_zero.output() < min_current - 0.05 {
+            // Stopped: start only with enough headroom, then from the estimate
+            let available = (tuning.setpoint_w - grid_w) / watts_per_amp.max(1.0);
+            let start_at = min_current + self.config.controls.grid_start_margin_a.max(0.0);
+            if available < start_at {
+                self.grid_zero.reset_to(0.0);
+                *effective = 0.0;
+                return;
+            }
+            self.logger.info(&format!(
+                "Grid-zero: {:.2} A available; starting to charge",
+                available
+            ));
+            self.grid_zero
+                .reset_to(available.min(self.station_max_current));
+        }
+        let amps =
+            self.grid_zero
+                .update(&tuning, grid_w, watts_per_amp, self.station_max_current, dt);
+        *effective = if min_pv {
+            amps.max(min_current).min(self.station_max_current)
+        } else if amps < min_current {
+            0.0
+        } else {
+            amps
+        };
+        self.logger.debug(&format!(
+            "Grid-zero: grid={:.0} W setpoint={:.0} W -> {:.2} A",
+            grid_w, self.config.controls.grid_setpoint_w, amps
+        ));
+    }
+
+    /// Feed the final setpoint of the cycle back to the controller
+    pub(super) fn track_grid_zero(&mut self, effective: f32) {
+        self.grid_zero.track(effective);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn keeps_open_loop_current_without_grid_meter() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.controls.auto_controller = "grid_zero".to_string();
+        d.update_config(cfg).unwrap();
+        d.current_mode = crate::controls::ChargingMode::Auto;
+        d.start_stop = crate::controls::StartStopState::Enabled;
+        let mut effective = 8.0;
+        d.apply_grid_zero(&mut effective).await;
+        assert_eq!(effective, 8.0);
+        assert!(!d.grid_zero.is_active());
+    }
+}
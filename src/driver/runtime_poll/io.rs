@@ -1,150 +1,123 @@
 use super::meas::RealtimeMeasurements;
+use super::meter_health::METER_HEADER_LEN;
+use crate::driver::scheduler::{PollGroup, ReadRange, read_coalesced};
+
+/// Cut `range` out of a register block starting at `base`
+fn slice_block(block: &[u16], base: u16, range: ReadRange) -> Option<Vec<u16>> {
+    let off = usize::from(range.address.checked_sub(base)?);
+    block
+        .get(off..off + usize::from(range.count))
+        .map(<[u16]>::to_vec)
+}
 
 impl crate::driver::AlfenDriver {
     pub(super) async fn read_realtime_values(&mut self) -> RealtimeMeasurements {
-        let socket_id = self.config.modbus.socket_slave_id;
-        let addr_voltages = self.config.registers.voltages;
-        let addr_currents = self.config.registers.currents;
-        let addr_power = self.config.registers.power;
-        let addr_energy = self.config.registers.energy;
-        let addr_status = self.config.registers.status;
+        if self.backend.alfen_extensions() {
+            self.read_alfen_values().await
+        } else {
+            self.read_backend_values().await
+        }
+    }
 
-        let manager = self.modbus_manager.as_mut().unwrap();
+    /// Meter header, voltages, currents, powers and energy on the socket slave
+    fn measurement_ranges(&self) -> [ReadRange; 5] {
+        let slave = self.config.modbus.socket_slave_id;
+        let r = &self.config.registers;
+        [
+            ReadRange::new(slave, r.meter_block, METER_HEADER_LEN),
+            ReadRange::new(slave, r.voltages, 6),
+            ReadRange::new(slave, r.currents, 6),
+            ReadRange::new(slave, r.power, 8),
+            ReadRange::new(slave, r.energy, 4),
+        ]
+    }
 
-        // Perform a single bulk read over the contiguous socket register range
-        let start_addr = *[addr_voltages, addr_currents, addr_power, addr_energy]
-            .iter()
-            .min()
-            .unwrap();
-        let end_exclusive = {
-            let v_end = addr_voltages as u32 + 6;
-            let c_end = addr_currents as u32 + 6;
-            let p_end = addr_power as u32 + 8;
-            let e_end = addr_energy as u32 + 4;
-            *[v_end, c_end, p_end, e_end].iter().max().unwrap() as u16
+    /// Coalesced read of `wanted`; every range is unread without a connection
+    async fn read_ranges(&mut self, wanted: &[ReadRange]) -> Vec<Option<Vec<u16>>> {
+        let gap = self.config.scheduler.coalesce_max_gap;
+        let Some(manager) = self.modbus_manager.as_mut() else {
+            return vec![None; wanted.len()];
         };
-        let bulk_count: u16 = end_exclusive.saturating_sub(start_addr);
-
-        let t_bulk = std::time::Instant::now();
-        let bulk_regs = manager
-            .read_holding_registers(socket_id, start_addr, bulk_count)
-            .await
-            .ok();
-        let bulk_ms = t_bulk.elapsed().as_millis() as u64;
+        read_coalesced(manager.as_mut(), wanted, gap).await
+    }
 
-        // Helper to slice a subset from the bulk read by absolute address and count
-        fn slice_from_bulk(
-            bulk_start: u16,
-            bulk_regs: &Option<Vec<u16>>,
-            addr: u16,
-            count: u16,
-        ) -> Option<Vec<u16>> {
-            if let Some(regs) = bulk_regs.as_ref() {
-                let off = (addr as i32 - bulk_start as i32) as isize;
-                if off >= 0 {
-                    let off = off as usize;
-                    let end = off + count as usize;
-                    if end <= regs.len() {
-                        return Some(regs[off..end].to_vec());
-                    }
-                }
-            }
+    async fn read_alfen_values(&mut self) -> RealtimeMeasurements {
+        // With full telemetry enabled the whole meter block provides the
+        // measurements; ranges it lacks are read on their own
+        let t_meas = tokio::time::Instant::now();
+        let meter_regs = if self.config.registers.full_meter_telemetry {
+            self.read_meter_block().await
+        } else {
             None
-        }
-
-        // Decode from bulk; if bulk read failed or returned too-short data, fall back to individual reads
-        let mut voltages = slice_from_bulk(start_addr, &bulk_regs, addr_voltages, 6);
-        let mut currents = slice_from_bulk(start_addr, &bulk_regs, addr_currents, 6);
-        let mut power_regs = slice_from_bulk(start_addr, &bulk_regs, addr_power, 8);
-        let mut energy_regs = slice_from_bulk(start_addr, &bulk_regs, addr_energy, 4);
-
-        let have_all = voltages.is_some()
-            && currents.is_some()
-            && power_regs.is_some()
-            && energy_regs.is_some();
-        if !have_all {
-            // Fallback path: issue the four reads separately and record timings for the chart
-            let t0 = std::time::Instant::now();
-            voltages = manager
-                .read_holding_registers(socket_id, addr_voltages, 6)
-                .await
-                .ok();
-            let read_voltages_ms = t0.elapsed().as_millis() as u64;
-
-            let t1 = std::time::Instant::now();
-            currents = manager
-                .read_holding_registers(socket_id, addr_currents, 6)
-                .await
-                .ok();
-            let read_currents_ms = t1.elapsed().as_millis() as u64;
-
-            let t2 = std::time::Instant::now();
-            power_regs = manager
-                .read_holding_registers(socket_id, addr_power, 8)
-                .await
-                .ok();
-            let read_powers_ms = t2.elapsed().as_millis() as u64;
-
-            let t3 = std::time::Instant::now();
-            energy_regs = manager
-                .read_holding_registers(socket_id, addr_energy, 4)
-                .await
-                .ok();
-            let read_energy_ms = t3.elapsed().as_millis() as u64;
+        };
+        self.meter_telemetry = meter_regs
+            .as_deref()
+            .map(super::telemetry::decode_meter_telemetry);
 
-            self.last_poll_steps
-                .get_or_insert_with(Default::default)
-                .read_voltages_ms = Some(read_voltages_ms);
-            if let Some(ref mut steps) = self.last_poll_steps {
-                steps.read_currents_ms = Some(read_currents_ms);
-                steps.read_powers_ms = Some(read_powers_ms);
-                steps.read_energy_ms = Some(read_energy_ms);
-            }
-        } else {
-            // Attribute the bulk time to the voltages step for charting consistency
-            self.last_poll_steps
-                .get_or_insert_with(Default::default)
-                .read_voltages_ms = Some(bulk_ms);
-            if let Some(ref mut steps) = self.last_poll_steps {
-                steps.read_currents_ms = None;
-                steps.read_powers_ms = None;
-                steps.read_energy_ms = None;
-                steps.read_station_max_ms = None;
+        let ranges = self.measurement_ranges();
+        let base = self.config.registers.meter_block;
+        let mut regs: Vec<Option<Vec<u16>>> = ranges
+            .iter()
+            .map(|r| {
+                meter_regs
+                    .as_deref()
+                    .and_then(|block| slice_block(block, base, *r))
+            })
+            .collect();
+        let missing: Vec<usize> = (0..ranges.len()).filter(|i| regs[*i].is_none()).collect();
+        if !missing.is_empty() {
+            let wanted: Vec<ReadRange> = missing.iter().map(|i| ranges[*i]).collect();
+            for (i, v) in missing.into_iter().zip(self.read_ranges(&wanted).await) {
+                regs[i] = v;
             }
         }
+        // The coalesced read is attributed to the voltages step
+        let steps = self.last_poll_steps.get_or_insert_with(Default::default);
+        steps.read_voltages_ms = Some(t_meas.elapsed().as_millis() as u64);
 
-        // Status is far away; read separately
-        let t_status = std::time::Instant::now();
-        let status_regs = manager
-            .read_holding_registers(socket_id, addr_status, 5)
-            .await
-            .ok();
-        let read_status_ms = t_status.elapsed().as_millis() as u64;
-
+        let [meter_header, voltages, currents, power_regs, energy_regs] =
+            <[Option<Vec<u16>>; 5]>::try_from(regs).unwrap_or_default();
         let voltages_triplet = Self::decode_triplet(&voltages);
         let currents_triplet = Self::decode_triplet(&currents);
         let (powers_triplet, total_power) =
             Self::decode_powers(&power_regs, &voltages_triplet, &currents_triplet);
         let energy_kwh = Self::decode_energy_kwh(&energy_regs);
-        let status = Self::compute_status_from_regs(&status_regs);
+        self.update_meter_health(meter_header.as_deref());
+        let status = self.read_status_if_due().await;
 
-        // Record timings for this segment
-        if let Some(ref mut steps) = self.last_poll_steps {
-            steps.read_status_ms = Some(read_status_ms);
-        } else {
-            // If last_poll_steps was None and not set above (shouldn't happen), set status time now
-            self.last_poll_steps
-                .get_or_insert_with(Default::default)
-                .read_status_ms = Some(read_status_ms);
-        }
-
-        RealtimeMeasurements {
+        let mut m = RealtimeMeasurements {
             voltages: voltages_triplet,
             currents: currents_triplet,
             powers: powers_triplet,
             total_power,
             energy_kwh,
             status,
+        };
+        self.apply_phase_rotation(&mut m);
+        m
+    }
+
+    /// Charger status through the backend when the status group is due,
+    /// else the last reading; Alfen stations also track the Mode 3 state
+    pub(super) async fn read_status_if_due(&mut self) -> i32 {
+        if !self
+            .poll_scheduler
+            .is_due(PollGroup::Status, tokio::time::Instant::now())
+        {
+            return self.last_base_status;
+        }
+        let t_status = tokio::time::Instant::now();
+        let Some(manager) = self.modbus_manager.as_mut() else {
+            return self.last_base_status;
+        };
+        let status = self.backend.read_status(manager.as_mut()).await.ok();
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_status_ms = Some(t_status.elapsed().as_millis() as u64);
+        if self.backend.alfen_extensions() {
+            self.update_mode3(status.as_ref().map(|s| s.raw.as_str()));
         }
+        self.last_base_status = status.map_or(0, |s| i32::from(s.code));
+        self.last_base_status
     }
 }
@@ -0,0 +1,198 @@
+//! Main fuse protection from the Victron grid meter's per-phase currents
+
+use crate::driver::types::MainFuseStatus;
+use tokio::time::{Duration, Instant};
+
+/// Stop and resume state of the main fuse cap
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FuseStop {
+    stopped: bool,
+    /// Since when the headroom has allowed resuming
+    clear_since: Option<Instant>,
+}
+
+impl FuseStop {
+    /// Track the EV limit (`None` without grid readings) against the minimum
+    /// current; returns whether charging stays stopped
+    fn update(
+        &mut self,
+        ev_limit: Option<f32>,
+        wanted: f32,
+        min_current: f32,
+        resume_a: f32,
+        hold: Duration,
+        now: Instant,
+    ) -> bool {
+        if !self.stopped {
+            self.stopped = wanted > 0.0 && ev_limit.is_some_and(|max| max < min_current);
+            self.clear_since = None;
+            return self.stopped;
+        }
+        if ev_limit.is_some_and(|max| max < min_current + resume_a) {
+            self.clear_since = None;
+            return true;
+        }
+        let since = *self.clear_since.get_or_insert(now);
+        self.stopped = now.saturating_duration_since(since) < hold;
+        self.stopped
+    }
+}
+
+/// Highest EV current that keeps every phase in `phases` within `limit_a`.
+/// The grid current includes the EV's own draw (`ev_a`), which is taken out
+/// first. Phases without a grid reading are skipped; `None` when none has one.
+pub(super) fn ev_current_limit(
+    limit_a: f32,
+    grid_a: [Option<f32>; 3],
+    ev_a: [f32; 3],
+    phases: &[usize],
+) -> Option<f32> {
+    phases
+        .iter()
+        .filter_map(|&i| grid_a[i].map(|g| limit_a - (g - ev_a[i])))
+        .reduce(f32::min)
+}
+
+impl crate::driver::AlfenDriver {
+    /// Installation phases the EV charges on; unknown counts as all three
+    pub(super) fn phases_in_use(&self) -> Vec<usize> {
+        if self.applied_phases == 1 {
+            vec![self.phase_map()[0]]
+        } else {
+            vec![0, 1, 2]
+        }
+    }
+
+    /// EV current per installation phase: measured when the meter is fresh,
+    /// else the last setpoint on the phases in use
+    fn ev_phase_currents(&self) -> [f32; 3] {
+        let measured = [
+            self.last_l1_current,
+            self.last_l2_current,
+            self.last_l3_current,
+        ];
+        if !self.meter_stale() && measured.iter().all(Option::is_some) {
+            return measured.map(|a| a.unwrap_or(0.0).max(0.0) as f32);
+        }
+        let mut out = [0.0; 3];
+        for i in self.phases_in_use() {
+            out[i] = self.last_sent_current.max(0.0);
+        }
+        out
+    }
+
+    pub(super) async fn apply_main_fuse_limit(&mut self, effective: &mut f32) {
+        let c = &self.config.controls;
+        if c.main_fuse_a <= 0.0 {
+            self.main_fuse = None;
+            return;
+        }
+        let limit = (c.main_fuse_a - c.main_fuse_margin_a.max(0.0)).max(0.0);
+        let min_current = c.min_set_current.max(0.0);
+        let grid = self.read_grid_currents().await;
+        let ev_limit =
+            ev_current_limit(limit, grid, self.ev_phase_currents(), &self.phases_in_use());
+        let stopped = self.main_fuse_stop.update(
+            ev_limit,
+            *effective,
+            min_current,
+            c.main_fuse_resume_a.max(0.0),
+            Duration::from_secs_f32(c.main_fuse_hold_s.max(0.0)),
+            Instant::now(),
+        );
+        let was_limiting = self.main_fuse.as_ref().is_some_and(|s| s.limiting);
+        let limiting =
+            *effective > 0.0 && (stopped || ev_limit.is_some_and(|max| *effective > max));
+        if limiting {
+            let capped = match ev_limit {
+                Some(max) if !stopped => max,
+                _ => 0.0,
+            };
+            let msg = format!(
+                "Main fuse: grid {:?} A; capping {:.2} A -> {:.2} A (limit {:.1} A)",
+                grid, *effective, capped, limit
+            );
+            if was_limiting {
+                self.logger.debug(&msg);
+            } else {
+                self.logger.warn(&msg);
+            }
+            *effective = capped;
+            // Write the reduction now, even below the update threshold
+            if capped < self.last_sent_current {
+                self.reassert_pending = true;
+            }
+        } else if was_limiting {
+            self.logger.info("Main fuse: load back within limit");
+        }
+        self.main_fuse = Some(MainFuseStatus {
+            grid_current_a: grid,
+            ev_limit_a: ev_limit,
+            limiting,
+            stopped,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_ev_current_by_most_loaded_phase() {
+        // 25 A fuse with 2 A margin; EV at 16 A on three phases, oven on L2
+        let grid = [Some(20.0), Some(31.0), Some(18.0)];
+        let ev = [16.0, 16.0, 16.0];
+        assert_eq!(ev_current_limit(23.0, grid, ev, &[0, 1, 2]), Some(8.0));
+        // Single phase charging on L1 is not limited by L2
+        let ev_1p = [16.0, 0.0, 0.0];
+        assert_eq!(ev_current_limit(23.0, grid, ev_1p, &[0]), Some(19.0));
+        // Export on a phase adds headroom; missing readings are skipped
+        let grid = [Some(-10.0), None, None];
+        assert_eq!(ev_current_limit(23.0, grid, ev_1p, &[0, 1, 2]), Some(49.0));
+        assert_eq!(ev_current_limit(23.0, [None; 3], ev, &[0, 1, 2]), None);
+    }
+
+    #[test]
+    fn stops_below_minimum_and_resumes_after_hold() {
+        let mut s = FuseStop::default();
+        let hold = Duration::from_secs(60);
+        let t0 = Instant::now();
+        let at = |secs| t0 + Duration::from_secs(secs);
+        let mut update = |limit, now| s.update(Some(limit), 16.0, 6.0, 2.0, hold, now);
+        assert!(!update(10.0, t0));
+        // The oven leaves 4 A: charging stops
+        assert!(update(4.0, at(1)));
+        // Without the EV's draw the headroom is back at 7 A, within the
+        // resume margin: stay stopped
+        assert!(update(7.0, at(2)));
+        assert!(update(7.0, at(120)));
+        // Enough headroom, but not for the hold time yet
+        assert!(update(9.0, at(121)));
+        assert!(update(9.0, at(150)));
+        // A dip restarts the hold time
+        assert!(update(5.0, at(151)));
+        assert!(update(9.0, at(152)));
+        assert!(update(9.0, at(200)));
+        assert!(!update(9.0, at(212)));
+        // Resumed: the normal cap applies again
+        assert!(!update(8.0, at(213)));
+    }
+
+    #[tokio::test]
+    async fn disabled_without_fuse_rating_or_readings() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut effective = 16.0;
+        d.apply_main_fuse_limit(&mut effective).await;
+        assert_eq!((effective, d.main_fuse.is_none()), (16.0, true));
+
+        let mut cfg = d.config().clone();
+        cfg.controls.main_fuse_a = 25.0;
+        d.update_config(cfg).unwrap();
+        d.apply_main_fuse_limit(&mut effective).await;
+        assert_eq!(effective, 16.0);
+        let status = d.main_fuse.clone().unwrap();
+        assert!(!status.limiting && status.ev_limit_a.is_none());
+    }
+}
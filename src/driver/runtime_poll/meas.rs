@@ -1,17 +1,28 @@
 // Measurement helpers and types for runtime polling
 
+/// Per-phase values; `None` when the station reports them as unavailable
 pub(super) struct LineTriplet {
-    pub(super) l1: f64,
-    pub(super) l2: f64,
-    pub(super) l3: f64,
+    pub(super) l1: Option<f64>,
+    pub(super) l2: Option<f64>,
+    pub(super) l3: Option<f64>,
+}
+
+impl LineTriplet {
+    fn unavailable() -> Self {
+        LineTriplet {
+            l1: None,
+            l2: None,
+            l3: None,
+        }
+    }
 }
 
 pub(super) struct RealtimeMeasurements {
     pub(super) voltages: LineTriplet,
     pub(super) currents: LineTriplet,
     pub(super) powers: LineTriplet,
-    pub(super) total_power: f64,
-    pub(super) energy_kwh: f64,
+    pub(super) total_power: Option<f64>,
+    pub(super) energy_kwh: Option<f64>,
     pub(super) status: i32,
 }
 
@@ -20,75 +31,52 @@ impl crate::driver::AlfenDriver {
         if let Some(v) = regs
             && v.len() >= 6
         {
-            let a = crate::modbus::decode_32bit_float(&v[0..2]).unwrap_or(0.0) as f64;
-            let b = crate::modbus::decode_32bit_float(&v[2..4]).unwrap_or(0.0) as f64;
-            let c = crate::modbus::decode_32bit_float(&v[4..6]).unwrap_or(0.0) as f64;
+            let at = |i: usize| crate::modbus::decode_32bit_float_opt(&v[i..i + 2]).map(f64::from);
             return LineTriplet {
-                l1: a,
-                l2: b,
-                l3: c,
+                l1: at(0),
+                l2: at(2),
+                l3: at(4),
             };
         }
-        LineTriplet {
-            l1: 0.0,
-            l2: 0.0,
-            l3: 0.0,
-        }
+        LineTriplet::unavailable()
     }
 
-    pub(super) fn decode_energy_kwh(regs: &Option<Vec<u16>>) -> f64 {
-        if let Some(v) = regs
-            && v.len() >= 4
-        {
-            return crate::modbus::decode_64bit_float(&v[0..4]).unwrap_or(0.0) / 1000.0;
-        }
-        0.0
+    pub(super) fn decode_energy_kwh(regs: &Option<Vec<u16>>) -> Option<f64> {
+        let v = regs.as_ref().filter(|v| v.len() >= 4)?;
+        crate::modbus::decode_64bit_float_opt(&v[0..4]).map(|wh| wh / 1000.0)
     }
 
     pub(super) fn decode_powers(
         power_regs: &Option<Vec<u16>>,
         voltages: &LineTriplet,
         currents: &LineTriplet,
-    ) -> (LineTriplet, f64) {
-        let (mut l1, mut l2, mut l3, mut total) = if let Some(v) = power_regs {
-            if v.len() >= 8 {
-                let p1 = crate::modbus::decode_32bit_float(&v[0..2]).unwrap_or(0.0) as f64;
-                let p2 = crate::modbus::decode_32bit_float(&v[2..4]).unwrap_or(0.0) as f64;
-                let p3 = crate::modbus::decode_32bit_float(&v[4..6]).unwrap_or(0.0) as f64;
-                let pt = crate::modbus::decode_32bit_float(&v[6..8]).unwrap_or(0.0) as f64;
-                let sanitize = |x: f64| if x.is_finite() { x } else { 0.0 };
-                (sanitize(p1), sanitize(p2), sanitize(p3), sanitize(pt))
-            } else {
-                (0.0, 0.0, 0.0, 0.0)
+    ) -> (LineTriplet, Option<f64>) {
+        let (p1, p2, p3, pt) = match power_regs {
+            Some(v) if v.len() >= 8 => {
+                let at =
+                    |i: usize| crate::modbus::decode_32bit_float_opt(&v[i..i + 2]).map(f64::from);
+                (at(0), at(2), at(4), at(6))
             }
-        } else {
-            (0.0, 0.0, 0.0, 0.0)
+            _ => (None, None, None, None),
         };
 
-        let approx = |v: f64, i: f64| (v * i).round();
-        if l1.abs() < 1.0 {
-            l1 = approx(voltages.l1, currents.l1);
-        }
-        if l2.abs() < 1.0 {
-            l2 = approx(voltages.l2, currents.l2);
-        }
-        if l3.abs() < 1.0 {
-            l3 = approx(voltages.l3, currents.l3);
-        }
-        if total.abs() < 1.0 {
-            total = l1 + l2 + l3;
-        }
+        // Near-zero or unavailable phase power: approximate from V*I when both are known
+        let phase = |p: Option<f64>, v: Option<f64>, i: Option<f64>| match (p, v, i) {
+            (Some(p), _, _) if p.abs() >= 1.0 => Some(p),
+            (_, Some(v), Some(i)) => Some((v * i).round()),
+            _ => p,
+        };
+        let l1 = phase(p1, voltages.l1, currents.l1);
+        let l2 = phase(p2, voltages.l2, currents.l2);
+        let l3 = phase(p3, voltages.l3, currents.l3);
+        let total = match pt {
+            Some(t) if t.abs() >= 1.0 => Some(t),
+            _ if l1.is_some() || l2.is_some() || l3.is_some() => {
+                Some(l1.unwrap_or(0.0) + l2.unwrap_or(0.0) + l3.unwrap_or(0.0))
+            }
+            _ => pt,
+        };
 
         (LineTriplet { l1, l2, l3 }, total)
     }
-
-    pub(super) fn compute_status_from_regs(status_regs: &Option<Vec<u16>>) -> i32 {
-        if let Some(v) = status_regs
-            && v.len() >= 5
-        {
-            let s = crate::modbus::decode_string(&v[0..5], None).unwrap_or_default();
-            return Self::map_alfen_status_to_victron(&s) as i32;
-        }
-        0
-    }
 }
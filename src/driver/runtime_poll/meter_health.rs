@@ -0,0 +1,194 @@
+//! Socket meter health (registers 300..305)
+
+use crate::driver::types::MeterHealth;
+
+const STATE_INITIALISED: u16 = 0x01;
+const STATE_WARNING: u16 = 0x04;
+const STATE_ERROR: u16 = 0x08;
+
+/// Number of header registers: state, last value timestamp (u64), type
+pub(super) const METER_HEADER_LEN: u16 = 6;
+
+fn meter_type_name(t: u16) -> &'static str {
+    match t {
+        0 => "rtu",
+        1 => "tcp",
+        2 => "udp",
+        3 => "p1",
+        _ => "other",
+    }
+}
+
+/// Decode the meter header into its health summary and value age (ms)
+pub(super) fn decode_meter_header(regs: &[u16], stale_after_ms: u64) -> Option<(MeterHealth, u64)> {
+    let regs = regs.get(..METER_HEADER_LEN as usize)?;
+    let state = regs[0];
+    let age_ms = regs[1..5]
+        .iter()
+        .fold(0u64, |acc, w| (acc << 16) | u64::from(*w));
+    let status = if state & STATE_ERROR != 0 {
+        "error"
+    } else if state & STATE_INITIALISED == 0 || age_ms > stale_after_ms {
+        "stale"
+    } else if state & STATE_WARNING != 0 {
+        "warning"
+    } else {
+        "ok"
+    };
+    Some((
+        MeterHealth {
+            state,
+            meter_type: meter_type_name(regs[5]).to_string(),
+            status: status.to_string(),
+        },
+        age_ms,
+    ))
+}
+
+impl crate::driver::AlfenDriver {
+    /// Update the cached meter health from this cycle's header registers
+    pub(super) fn update_meter_health(&mut self, header: Option<&[u16]>) {
+        let threshold = self.config.controls.meter_stale_threshold_ms;
+        let decoded = header.and_then(|h| decode_meter_header(h, threshold));
+        let prev = self.meter_health.as_ref().map(|h| h.status.clone());
+        match decoded {
+            Some((health, age_ms)) => {
+                if prev.as_deref() != Some(health.status.as_str()) {
+                    self.log_meter_transition(&health, age_ms);
+                }
+                self.meter_health = Some(health);
+                self.meter_age_ms = Some(age_ms);
+            }
+            None => {
+                self.meter_health = None;
+                self.meter_age_ms = None;
+            }
+        }
+    }
+
+    fn log_meter_transition(&self, health: &MeterHealth, age_ms: u64) {
+        match health.status.as_str() {
+            "ok" => self.logger.info("Socket meter delivers fresh values"),
+            "warning" => self.logger.warn(&format!(
+                "Socket meter reports a warning (state=0x{:02X})",
+                health.state
+            )),
+            _ => self.logger.warn(&format!(
+                "Socket meter {} (state=0x{:02X}, last value {} ms ago); ignoring its measurements for lag compensation and sessions",
+                health.status, health.state, age_ms
+            )),
+        }
+    }
+
+    /// Whether the meter delivers usable values; `None` when unknown
+    pub(crate) fn meter_ok(&self) -> Option<bool> {
+        self.meter_health
+            .as_ref()
+            .map(|h| h.status == "ok" || h.status == "warning")
+    }
+
+    /// Whether this cycle's measurements must not be trusted
+    pub(super) fn meter_stale(&self) -> bool {
+        self.meter_ok() == Some(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::meas::{LineTriplet, RealtimeMeasurements};
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn header(state: u16, age_ms: u64) -> Vec<u16> {
+        vec![
+            state,
+            (age_ms >> 48) as u16,
+            (age_ms >> 32) as u16,
+            (age_ms >> 16) as u16,
+            age_ms as u16,
+            3,
+        ]
+    }
+
+    #[test]
+    fn decodes_state_age_and_type() {
+        let (h, age) = decode_meter_header(&header(0x03, 70_000), 10_000).unwrap();
+        assert_eq!(age, 70_000);
+        assert_eq!(h.status, "stale");
+        assert_eq!(h.meter_type, "p1");
+        assert_eq!(
+            decode_meter_header(&header(0x03, 500), 10_000)
+                .unwrap()
+                .0
+                .status,
+            "ok"
+        );
+        assert_eq!(
+            decode_meter_header(&header(0x07, 500), 10_000)
+                .unwrap()
+                .0
+                .status,
+            "warning"
+        );
+        assert_eq!(
+            decode_meter_header(&header(0x0B, 500), 10_000)
+                .unwrap()
+                .0
+                .status,
+            "error"
+        );
+        assert_eq!(
+            decode_meter_header(&header(0x00, 500), 10_000)
+                .unwrap()
+                .0
+                .status,
+            "stale"
+        );
+        assert!(decode_meter_header(&[1, 2], 10_000).is_none());
+    }
+
+    #[tokio::test]
+    async fn stale_meter_uses_setpoint_estimate_and_skips_sessions() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        d.last_sent_current = 10.0;
+        d.applied_phases = 1;
+        // Outside the lag window the measured power is used while the meter is fresh
+        d.last_set_current_monotonic = tokio::time::Instant::now()
+            - std::time::Duration::from_millis(d.config.controls.ev_reporting_lag_ms as u64 + 10);
+        d.update_meter_health(Some(&header(0x03, 200)));
+        assert_eq!(d.meter_ok(), Some(true));
+        assert_eq!(d.ev_power_for_subtract(Some(1234.0)), 1234.0);
+
+        d.update_meter_health(Some(&header(0x08, 200)));
+        assert!(d.meter_stale());
+        assert_eq!(d.ev_power_for_subtract(Some(0.0)), 2300.0);
+
+        // A charging status from a dead meter does not start a session
+        let zero = || LineTriplet {
+            l1: Some(0.0),
+            l2: Some(0.0),
+            l3: Some(0.0),
+        };
+        let m = RealtimeMeasurements {
+            voltages: zero(),
+            currents: zero(),
+            powers: zero(),
+            total_power: Some(0.0),
+            energy_kwh: Some(1.0),
+            status: 2,
+        };
+        d.finalize_cycle(&m, 2, 10.0).unwrap();
+        assert!(d.sessions.current_session.is_none());
+
+        let snap = d.build_typed_snapshot(None);
+        assert_eq!(snap.meter_ok, Some(false));
+        assert_eq!(snap.meter_age_ms, Some(200));
+        assert_eq!(snap.meter_health.unwrap().status, "error");
+
+        // The plug-in edge is kept until the meter delivers again
+        d.update_meter_health(Some(&header(0x03, 200)));
+        d.finalize_cycle(&m, 2, 10.0).unwrap();
+        assert!(d.sessions.current_session.is_some());
+    }
+}
@@ -0,0 +1,145 @@
+//! IEC 61851 Mode 3 state tracking (register 1201)
+
+use crate::driver::types::Mode3Transition;
+
+/// Number of transitions kept in the history
+const HISTORY_LEN: usize = 20;
+
+const STATUS_CHARGED: u8 = 3;
+/// Victron "Waiting for RFID", the closest code for a pending authorization
+const STATUS_WAIT_AUTHORIZATION: u8 = 5;
+/// Victron "Ground fault", the closest code for an EVSE fault (state F)
+const STATUS_EVSE_FAULT: u8 = 8;
+/// Victron "CP input test error" for control pilot errors (state E)
+const STATUS_CP_ERROR: u8 = 10;
+
+/// Trim NUL padding and whitespace and uppercase a raw Mode 3 string
+pub(super) fn normalize_mode3(raw: &str) -> String {
+    raw.trim_matches(|c: char| c == char::from(0) || c.is_whitespace())
+        .to_uppercase()
+}
+
+pub(super) fn mode3_description(state: &str) -> &'static str {
+    match state {
+        "A" => "No vehicle connected",
+        "B1" => "Vehicle connected, waiting for authorization",
+        "B2" => "Vehicle connected, not requesting charge",
+        "C1" => "Vehicle requests charge, station paused",
+        "C2" => "Charging",
+        "D1" => "Vehicle requires ventilation, station paused",
+        "D2" => "Charging with ventilation",
+        "E" => "Control pilot error (no power or short circuit)",
+        "F" => "Station fault",
+        _ => "Unknown",
+    }
+}
+
+/// Victron EV charger status for a Mode 3 state; `charged` tells a full car
+/// (B2 after charging) apart from one that has not started yet
+pub(super) fn mode3_to_victron(state: &str, charged: bool) -> u8 {
+    match state {
+        "B1" => STATUS_WAIT_AUTHORIZATION,
+        "B2" if charged => STATUS_CHARGED,
+        "B2" | "C1" | "D1" => 1,
+        "C2" | "D2" => 2,
+        "E" => STATUS_CP_ERROR,
+        "F" => STATUS_EVSE_FAULT,
+        _ => 0,
+    }
+}
+
+impl crate::driver::AlfenDriver {
+    /// Track the Mode 3 state read this cycle; an unreadable state keeps
+    /// the previous one
+    pub(super) fn update_mode3(&mut self, raw: Option<&str>) {
+        let Some(state) = raw.map(normalize_mode3).filter(|s| !s.is_empty()) else {
+            return;
+        };
+        let mut m = self.mode3.take().unwrap_or_default();
+        if m.state != state {
+            let now = self.wall_clock.now().to_rfc3339();
+            if !m.state.is_empty() {
+                self.logger.info(&format!(
+                    "Mode 3 state {} -> {} ({})",
+                    m.state,
+                    state,
+                    mode3_description(&state)
+                ));
+                m.transitions.push(Mode3Transition {
+                    from: m.state.clone(),
+                    to: state.clone(),
+                    at: now.clone(),
+                });
+                if m.transitions.len() > HISTORY_LEN {
+                    m.transitions.remove(0);
+                }
+            }
+            m.description = mode3_description(&state).to_string();
+            m.since = Some(now);
+            m.state = state;
+        }
+        match m.state.as_str() {
+            "A" => m.charged = false,
+            "C2" | "D2" => m.charged = true,
+            _ => {}
+        }
+        self.mode3 = Some(m);
+    }
+
+    /// Extended Victron status from the tracked Mode 3 state, provided it
+    /// agrees with this cycle's base status
+    pub(super) fn mode3_status_code(&self, status_base: i32) -> Option<i32> {
+        let m = self.mode3.as_ref()?;
+        (i32::from(Self::map_alfen_status_to_victron(&m.state)) == status_base)
+            .then(|| i32::from(mode3_to_victron(&m.state, m.charged)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn maps_mode3_states_to_victron_codes() {
+        assert_eq!(mode3_to_victron("A", false), 0);
+        assert_eq!(mode3_to_victron("B1", false), 5);
+        assert_eq!(mode3_to_victron("B2", false), 1);
+        assert_eq!(mode3_to_victron("B2", true), 3);
+        assert_eq!(mode3_to_victron("C1", true), 1);
+        assert_eq!(mode3_to_victron("D2", false), 2);
+        assert_eq!(mode3_to_victron("E", false), 10);
+        assert_eq!(mode3_to_victron("F", false), 8);
+        assert_eq!(normalize_mode3(" c2\0\0"), "C2");
+    }
+
+    #[tokio::test]
+    async fn tracks_transitions_and_charged_car() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        d.start_stop = crate::controls::StartStopState::Enabled;
+        for s in ["A", "B1", "B2", "C2", "C2", "B2"] {
+            d.update_mode3(Some(s));
+        }
+        d.update_mode3(None);
+        let m = d.mode3.clone().unwrap();
+        assert_eq!(m.state, "B2");
+        assert!(m.charged);
+        let path: Vec<_> = m.transitions.iter().map(|t| t.to.as_str()).collect();
+        assert_eq!(path, ["B1", "B2", "C2", "B2"]);
+        assert_eq!(d.derive_status(1, None), 3);
+        // A base status that disagrees with the tracked state wins
+        assert_eq!(d.derive_status(0, None), 0);
+
+        d.update_mode3(Some("A"));
+        d.update_mode3(Some("F"));
+        assert!(!d.mode3.as_ref().unwrap().charged);
+        assert_eq!(d.derive_status(0, None), 8);
+
+        for _ in 0..HISTORY_LEN {
+            d.update_mode3(Some("A"));
+            d.update_mode3(Some("B1"));
+        }
+        assert_eq!(d.mode3.as_ref().unwrap().transitions.len(), HISTORY_LEN);
+    }
+}
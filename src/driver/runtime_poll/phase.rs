@@ -2,7 +2,7 @@ impl crate::driver::AlfenDriver {
     pub(super) async fn evaluate_auto_phase_switch(&mut self, excess_pv_power_w: f32) {
         // If currently settling after a switch, do nothing until deadline
         if let Some(deadline) = self.phase_settle_deadline {
-            if std::time::Instant::now() < deadline {
+            if tokio::time::Instant::now() < deadline {
                 return;
             }
             self.phase_settle_deadline = None;
@@ -13,19 +13,18 @@ impl crate::driver::AlfenDriver {
             let min_gap = std::time::Duration::from_secs(
                 self.config.controls.phase_switch_grace_seconds as u64,
             );
-            if std::time::Instant::now().duration_since(last) < min_gap {
+            if tokio::time::Instant::now().duration_since(last) < min_gap {
                 return;
             }
         }
 
-        // Compute thresholds based on configured min/max and 230V
-        let v = 230.0f32;
+        // Compute thresholds based on configured min/max and the phase voltages
         let min_a = self.config.controls.min_set_current.max(0.0);
         let max_a = self.config.controls.max_set_current.max(min_a);
         let hys = self.config.controls.auto_phase_hysteresis_watts.max(0.0);
 
-        let one_p_max_w = max_a * v * 1.0;
-        let three_p_min_w = min_a * v * 3.0;
+        let one_p_max_w = max_a * self.watts_per_amp(1);
+        let three_p_min_w = min_a * self.watts_per_amp(3);
 
         let want_three = excess_pv_power_w > (three_p_min_w + hys);
         let want_one = excess_pv_power_w < (one_p_max_w - hys);
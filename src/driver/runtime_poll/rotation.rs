@@ -0,0 +1,133 @@
+//! Phase rotation between charger and installation phases
+
+use super::meas::{LineTriplet, RealtimeMeasurements};
+
+/// Move per-phase values from charger order to installation order
+fn rotate<T: Copy>(values: [T; 3], map: [usize; 3]) -> [T; 3] {
+    let mut out = values;
+    for (i, v) in values.into_iter().enumerate() {
+        out[map[i]] = v;
+    }
+    out
+}
+
+/// Index of a line-to-line pair (L1L2, L2L3, L3L1) by its two phases
+pub(super) fn line_pair_index(a: usize, b: usize) -> usize {
+    match (a.min(b), a.max(b)) {
+        (0, 1) => 0,
+        (1, 2) => 1,
+        _ => 2,
+    }
+}
+
+impl LineTriplet {
+    fn rotated(&self, map: [usize; 3]) -> Self {
+        let [l1, l2, l3] = rotate([self.l1, self.l2, self.l3], map);
+        LineTriplet { l1, l2, l3 }
+    }
+}
+
+impl crate::driver::AlfenDriver {
+    /// Installation phase index of the charger's L1, L2 and L3
+    pub(crate) fn phase_map(&self) -> [usize; 3] {
+        self.config.controls.phase_map().unwrap_or([0, 1, 2])
+    }
+
+    /// Remap this cycle's per-phase values to installation phases
+    pub(super) fn apply_phase_rotation(&mut self, m: &mut RealtimeMeasurements) {
+        let map = self.phase_map();
+        if map == [0, 1, 2] {
+            return;
+        }
+        m.voltages = m.voltages.rotated(map);
+        m.currents = m.currents.rotated(map);
+        m.powers = m.powers.rotated(map);
+        if let Some(t) = self.meter_telemetry.as_mut() {
+            t.power_factor = rotate(t.power_factor, map);
+            t.apparent_power = rotate(t.apparent_power, map);
+            t.reactive_power = rotate(t.reactive_power, map);
+            t.energy_delivered_kwh = rotate(t.energy_delivered_kwh, map);
+            t.energy_consumed_kwh = rotate(t.energy_consumed_kwh, map);
+            t.apparent_energy_kvah = rotate(t.apparent_energy_kvah, map);
+            t.reactive_energy_kvarh = rotate(t.reactive_energy_kvarh, map);
+            let ll = t.voltage_ll;
+            for (k, v) in ll.into_iter().enumerate() {
+                t.voltage_ll[line_pair_index(map[k], map[(k + 1) % 3])] = v;
+            }
+        }
+    }
+
+    /// Spread the EV power to subtract over the installation phases: by the
+    /// measured per-phase share when available, else by the wiring (single
+    /// phase charging lands on the phase charger L1 is wired to)
+    pub(super) fn ev_power_per_phase(&self, total: f64, powers: &LineTriplet) -> [f64; 3] {
+        let measured = [powers.l1, powers.l2, powers.l3].map(|p| p.unwrap_or(0.0).max(0.0));
+        let sum: f64 = measured.iter().sum();
+        if sum > 0.0 && !self.meter_stale() {
+            return measured.map(|p| total * p / sum);
+        }
+        if self.applied_phases == 1 {
+            let mut out = [0.0; 3];
+            out[self.phase_map()[0]] = total;
+            out
+        } else {
+            [total / 3.0; 3]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{MockModbus, regs_from_f32};
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn rotates_values_and_line_pairs() {
+        // Charger L1 on installation L2, L2 on L3, L3 on L1
+        let map = [1, 2, 0];
+        assert_eq!(rotate([10, 20, 30], map), [30, 10, 20]);
+        // Charger L1L2 is installation L2L3
+        assert_eq!(line_pair_index(map[0], map[1]), 1);
+        assert_eq!(line_pair_index(map[2], map[0]), 0);
+    }
+
+    #[tokio::test]
+    async fn remaps_measurements_and_single_phase_power() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.controls.phase_rotation = "L2L3L1".to_string();
+        d.update_config(cfg).unwrap();
+        let slave = d.config().modbus.socket_slave_id;
+        // Meter header, voltages (306), currents (320) and powers (338)
+        let mut regs = vec![0x03, 0, 0, 0, 0, 3];
+        for v in [230.0f32, 231.0, 232.0] {
+            regs.extend(regs_from_f32(v));
+        }
+        regs.resize((320 - 300) as usize, 0);
+        for a in [16.0f32, 0.0, 0.0] {
+            regs.extend(regs_from_f32(a));
+        }
+        regs.resize((338 - 300) as usize, 0);
+        for p in [3680.0f32, 0.0, 0.0, 3680.0] {
+            regs.extend(regs_from_f32(p));
+        }
+        regs.resize((378 - 300) as usize, 0);
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(slave, 300, 78, regs)));
+        let m = d.read_realtime_values().await;
+        assert_eq!(m.currents.l2, Some(16.0));
+        assert_eq!(m.powers.l2, Some(3680.0));
+        assert_eq!(m.voltages.l1, Some(232.0));
+        assert_eq!(d.ev_power_per_phase(3680.0, &m.powers), [0.0, 3680.0, 0.0]);
+
+        // Without measurements single phase charging lands on installation L2
+        d.applied_phases = 1;
+        let none = LineTriplet {
+            l1: None,
+            l2: None,
+            l3: None,
+        };
+        assert_eq!(d.ev_power_per_phase(2300.0, &none), [0.0, 2300.0, 0.0]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Scheduled register groups of the poll cycle (see `driver::scheduler`)
+
+use crate::driver::scheduler::PollGroup;
+use tokio::time::{Duration, Instant};
+
+impl crate::driver::AlfenDriver {
+    pub(super) async fn refresh_groups_before_write(&mut self) {
+        let now = Instant::now();
+        let alfen = self.backend.alfen_extensions();
+        if self.poll_scheduler.is_due(PollGroup::Status, now) {
+            if alfen {
+                self.refresh_availability().await;
+            }
+            self.poll_scheduler.mark_run(PollGroup::Status, now);
+        }
+        if self.poll_scheduler.is_due(PollGroup::Validity, now) {
+            // The socket validity and readback (1206..) do not apply to SCN
+            // setpoints, which are checked against the SCN max currents
+            if alfen && self.config.registers.uses_scn() {
+                self.refresh_scn_status().await;
+            } else if alfen {
+                self.refresh_validity_status().await;
+            }
+            self.poll_scheduler.mark_run(PollGroup::Validity, now);
+        }
+        if alfen {
+            self.maybe_refresh_station_clock().await;
+        }
+    }
+
+    pub(super) async fn refresh_groups_after_write(&mut self, cycle_started: Instant) {
+        let budget = Duration::from_millis(self.config.poll_interval_ms);
+        let after_write = PollGroup::ALL.into_iter().filter(|g| !g.before_write());
+        let forced = self
+            .poll_scheduler
+            .most_overdue(after_write.clone(), Instant::now());
+        for group in after_write {
+            let now = Instant::now();
+            if !self.poll_scheduler.is_due(group, now) {
+                continue;
+            }
+            if cycle_started.elapsed() >= budget && Some(group) != forced {
+                self.logger
+                    .debug(&format!("Poll interval used up; deferring {:?}", group));
+                continue;
+            }
+            match group {
+                PollGroup::StationMax => self.update_station_max_current_from_modbus().await,
+                PollGroup::Temperature if self.backend.alfen_extensions() => {
+                    self.refresh_temperature().await;
+                }
+                PollGroup::Identity => {
+                    let _ = self.refresh_charger_identity().await;
+                }
+                _ => {}
+            }
+            self.poll_scheduler.mark_run(group, now);
+        }
+    }
+
+    /// Read the station max current (1100)
+    pub(super) async fn update_station_max_current_from_modbus(&mut self) {
+        if !self.backend.alfen_extensions() {
+            return;
+        }
+        let station_id = self.config.modbus.station_slave_id;
+        let addr_station_max = self.config.registers.station_max_current;
+        let Some(manager) = self.modbus_manager.as_mut() else {
+            return;
+        };
+        let t0 = Instant::now();
+        let max_c = manager
+            .read_holding_registers(station_id, addr_station_max, 2)
+            .await
+            .ok()
+            .and_then(|regs| crate::modbus::decode_32bit_float_opt(regs.get(..2)?))
+            .filter(|v| *v > 0.0);
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_station_max_ms = Some(t0.elapsed().as_millis() as u64);
+        if let Some(max_c) = max_c {
+            self.station_max_current = max_c;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{MockModbus, regs_from_f32};
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn slow_groups_follow_their_interval() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let station = d.config().modbus.station_slave_id;
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(
+            station,
+            1100,
+            2,
+            regs_from_f32(25.0),
+        )));
+        d.station_max_current = 0.0;
+        d.refresh_groups_after_write(Instant::now()).await;
+        assert!((d.station_max_current - 25.0).abs() < f32::EPSILON);
+        assert!(
+            d.last_poll_steps
+                .as_ref()
+                .unwrap()
+                .read_station_max_ms
+                .is_some()
+        );
+
+        // Not due again until the interval has elapsed
+        d.station_max_current = 0.0;
+        d.last_poll_steps = None;
+        d.refresh_groups_after_write(Instant::now()).await;
+        assert!(d.station_max_current.abs() < f32::EPSILON);
+        assert!(d.last_poll_steps.is_none());
+
+        // A cycle that used up the poll interval runs only the most overdue
+        // slow group and defers the others
+        d.poll_scheduler.reset();
+        let late = Instant::now() - Duration::from_millis(d.config().poll_interval_ms);
+        d.refresh_groups_after_write(late).await;
+        let due = |d: &crate::driver::AlfenDriver, g| d.poll_scheduler.is_due(g, Instant::now());
+        assert!(!due(&d, PollGroup::StationMax));
+        assert!(due(&d, PollGroup::Temperature) && due(&d, PollGroup::Identity));
+        // Persistent overruns still get to the next group
+        d.refresh_groups_after_write(late).await;
+        assert!(!due(&d, PollGroup::Temperature));
+        assert!(due(&d, PollGroup::Identity));
+    }
+}
@@ -0,0 +1,327 @@
+//! SCN (Smart Charging Network) per-phase current control
+
+use crate::driver::types::ScnStatus;
+
+/// Decode three consecutive FLOAT32 values (L1, L2, L3), non-finite as 0
+fn decode_phases(regs: &[u16]) -> Option<[f32; 3]> {
+    if regs.len() < 6 {
+        return None;
+    }
+    let mut out = [0.0f32; 3];
+    for (i, v) in out.iter_mut().enumerate() {
+        let x = crate::modbus::decode_32bit_float(&regs[i * 2..i * 2 + 2]).unwrap_or(0.0);
+        *v = if x.is_finite() { x } else { 0.0 };
+    }
+    Some(out)
+}
+
+impl crate::driver::AlfenDriver {
+    /// Address range `[start, end)` covering all SCN registers we read;
+    /// `None` when a configured address runs past the register space
+    fn scn_read_range(&self) -> Option<(u16, u16)> {
+        let r = &self.config.registers;
+        let start = r
+            .scn_consumption
+            .min(r.scn_actual_max_current)
+            .min(r.scn_max_current)
+            .min(r.scn_max_current_enable);
+        let end = r
+            .scn_consumption
+            .checked_add(6)?
+            .max(r.scn_actual_max_current.checked_add(6)?)
+            .max(r.scn_max_current.checked_add(6)?)
+            .max(r.scn_max_current_enable.checked_add(1)?);
+        Some((start, end))
+    }
+
+    pub(super) fn decode_scn_status(&self, start: u16, regs: &[u16]) -> Option<ScnStatus> {
+        let r = &self.config.registers;
+        let slice = |addr: u16, count: usize| -> Option<&[u16]> {
+            let off = addr.checked_sub(start)? as usize;
+            regs.get(off..off + count)
+        };
+        Some(ScnStatus {
+            max_current_enabled: slice(r.scn_max_current_enable, 1)?[0] == 1,
+            total_consumption: decode_phases(slice(r.scn_consumption, 6)?)?,
+            actual_max_current: decode_phases(slice(r.scn_actual_max_current, 6)?)?,
+            max_current: decode_phases(slice(r.scn_max_current, 6)?)?,
+            limit_accepted: None,
+        })
+    }
+
+    /// Read the SCN registers in one request and cache them for the snapshot
+    pub(super) async fn refresh_scn_status(&mut self) {
+        let station_id = self.config.modbus.station_slave_id;
+        let Some((start, end)) = self.scn_read_range() else {
+            self.logger
+                .warn("SCN register addresses exceed the register space; not reading SCN");
+            return;
+        };
+        let t0 = tokio::time::Instant::now();
+        let regs = match self.modbus_manager.as_mut() {
+            Some(m) => m
+                .read_holding_registers(station_id, start, end.saturating_sub(start))
+                .await
+                .ok(),
+            None => None,
+        };
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_scn_ms = Some(t0.elapsed().as_millis() as u64);
+        let Some(mut status) = regs.and_then(|r| self.decode_scn_status(start, &r)) else {
+            self.logger.debug("SCN registers unavailable this cycle");
+            return;
+        };
+        let was_enabled = self.scn_status.as_ref().map(|s| s.max_current_enabled);
+        if was_enabled != Some(status.max_current_enabled) {
+            if status.max_current_enabled {
+                self.logger.info("SCN Modbus max current enabled");
+            } else {
+                self.logger.warn(
+                    "SCN Modbus max current is disabled on the station; setpoints will not be written (enable it in ACE Service Installer)",
+                );
+            }
+        }
+        self.verify_scn_limit(&mut status);
+        self.scn_status = Some(status);
+    }
+
+    /// Compare the SCN max currents read back with the values written last
+    fn verify_scn_limit(&self, status: &mut ScnStatus) {
+        let Some(written) = self.scn_limit_written else {
+            return;
+        };
+        let tolerance = self.config.controls.current_tolerance.max(0.0);
+        let accepted = status
+            .max_current
+            .iter()
+            .zip(written)
+            .all(|(a, w)| (a - w).abs() <= tolerance);
+        let was = self.scn_status.as_ref().and_then(|s| s.limit_accepted);
+        if !accepted && was != Some(false) {
+            self.logger.error(&format!(
+                "SCN setpoint not accepted: wrote {:?} A, station holds {:?} A",
+                written, status.max_current
+            ));
+        } else if accepted && was == Some(false) {
+            self.logger
+                .info("SCN setpoint accepted by the station again");
+        }
+        status.limit_accepted = Some(accepted);
+    }
+
+    /// Whether SCN control is selected but this driver must not write: the
+    /// SCN is one entity driven by socket 1, and the station ignores the
+    /// values while SCN Modbus max current is disabled
+    pub(super) fn scn_writes_blocked(&self) -> bool {
+        self.config.registers.uses_scn()
+            && (self.socket != 1
+                || self
+                    .scn_status
+                    .as_ref()
+                    .is_some_and(|s| !s.max_current_enabled))
+    }
+
+    /// Cap the setpoint by the SCN actual max current of the phases in use
+    /// where the station applies less than the SCN max current it holds (an
+    /// installer limit or the SCN safe current)
+    pub(super) fn apply_scn_limit(&self, effective: &mut f32) {
+        if !self.config.registers.uses_scn() {
+            return;
+        }
+        let Some(s) = self.scn_status.as_ref() else {
+            return;
+        };
+        let tolerance = self.config.controls.current_tolerance.max(0.0);
+        for i in self.phases_in_use() {
+            if s.actual_max_current[i] < s.max_current[i] - tolerance {
+                *effective = effective.min(s.actual_max_current[i]);
+            }
+        }
+    }
+
+    /// Write the setpoint to the SCN max currents of the installation phases
+    /// in use (the SCN registers are not rotated); the other phases get 0
+    pub(super) async fn write_scn_setpoint(&mut self, effective: f32) -> bool {
+        let mut per_phase = [0.0f32; 3];
+        for i in self.phases_in_use() {
+            per_phase[i] = effective;
+        }
+        if !self.write_scn_max_currents(per_phase).await {
+            return false;
+        }
+        self.logger.debug(&format!(
+            "Wrote SCN setpoint {:?} A ({})",
+            per_phase,
+            self.current_mode_reason()
+        ));
+        self.scn_limit_written = Some(per_phase);
+        true
+    }
+
+    /// Write the SCN max current for L1, L2 and L3 (station slave)
+    async fn write_scn_max_currents(&mut self, per_phase: [f32; 3]) -> bool {
+        let station_id = self.config.modbus.station_slave_id;
+        let addr = self.config.registers.scn_max_current;
+        let regs: Vec<u16> = per_phase
+            .iter()
+            .flat_map(|a| crate::modbus::encode_32bit_float(*a))
+            .collect();
+        match self.modbus_manager.as_mut() {
+            Some(m) => m
+                .write_multiple_registers(station_id, addr, &regs)
+                .await
+                .is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{MockModbus, regs_from_f32};
+    use tokio::sync::mpsc;
+
+    fn scn_regs(enabled: u16) -> Vec<u16> {
+        let mut regs: Vec<u16> = [5.0f32, 6.0, 7.0, 16.0, 16.0, 16.0, 20.0, 20.0, 20.0]
+            .into_iter()
+            .flat_map(regs_from_f32)
+            .collect();
+        // 1423..1430: remaining validity and safe current (not decoded)
+        regs.extend([0u16; 8]);
+        regs.push(enabled);
+        regs
+    }
+
+    async fn scn_driver() -> crate::driver::AlfenDriver {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.registers.current_control = "scn".to_string();
+        d.update_config(cfg).unwrap();
+        d
+    }
+
+    #[tokio::test]
+    async fn refresh_scn_status_decodes_and_respects_enable() {
+        let mut d = scn_driver().await;
+        let station = d.config().modbus.station_slave_id;
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(
+            station,
+            1405,
+            27,
+            scn_regs(0),
+        )));
+        d.refresh_scn_status().await;
+        let s = d.scn_status.clone().expect("scn status");
+        assert!(!s.max_current_enabled);
+        assert_eq!(s.total_consumption, [5.0, 6.0, 7.0]);
+        assert_eq!(s.actual_max_current, [16.0; 3]);
+        assert_eq!(s.max_current, [20.0; 3]);
+
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(
+            station,
+            1405,
+            27,
+            scn_regs(1),
+        )));
+        d.refresh_scn_status().await;
+        assert!(d.scn_status.as_ref().unwrap().max_current_enabled);
+    }
+
+    fn last_write(d: &mut crate::driver::AlfenDriver) -> Option<(u8, u16, Vec<u16>)> {
+        d.modbus_manager
+            .as_mut()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<MockModbus>()
+            .unwrap()
+            .last_write
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn scn_setpoint_replaces_socket_setpoint_per_phase() {
+        let mut d = scn_driver().await;
+        let station = d.config().modbus.station_slave_id;
+        let enabled = || MockModbus::new().with_read(200, 1405, 27, scn_regs(1));
+        let f32s = |v: [f32; 3]| v.into_iter().flat_map(regs_from_f32).collect::<Vec<_>>();
+
+        d.modbus_manager = Some(Box::new(enabled()));
+        d.refresh_scn_status().await;
+        assert!(!d.scn_writes_blocked());
+        assert!(d.write_effective_current(10.0).await);
+        assert!(d.setpoint_verify.is_none());
+        let (slave, addr, vals) = last_write(&mut d).expect("SCN write");
+        assert_eq!((slave, addr), (station, 1417));
+        assert_eq!(vals, f32s([10.0; 3]));
+
+        // Single phase on charger L1, wired to installation L2
+        let mut cfg = d.config().clone();
+        cfg.controls.phase_rotation = "L2L3L1".to_string();
+        d.update_config(cfg).unwrap();
+        d.applied_phases = 1;
+        d.modbus_manager = Some(Box::new(enabled()));
+        assert!(d.write_effective_current(8.0).await);
+        let (_, _, vals) = last_write(&mut d).unwrap();
+        assert_eq!(vals, f32s([0.0, 8.0, 0.0]));
+
+        // The station holds 20 A, not the values written
+        d.modbus_manager = Some(Box::new(enabled()));
+        d.refresh_scn_status().await;
+        assert_eq!(d.scn_status.as_ref().unwrap().limit_accepted, Some(false));
+
+        // Socket 2 shares the SCN driven by socket 1
+        d.socket = 2;
+        assert!(d.scn_writes_blocked());
+    }
+
+    #[tokio::test]
+    async fn scn_actual_max_caps_setpoint_below_held_value() {
+        let mut d = scn_driver().await;
+        let mut effective = 25.0;
+        d.apply_scn_limit(&mut effective);
+        assert_eq!(effective, 25.0);
+
+        // Actual max 16 A while the station holds 20 A
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(
+            200,
+            1405,
+            27,
+            scn_regs(1),
+        )));
+        d.refresh_scn_status().await;
+        d.apply_scn_limit(&mut effective);
+        assert_eq!(effective, 16.0);
+
+        // Actual max equal to the held value is our own limit: no cap
+        d.scn_status.as_mut().unwrap().max_current = [16.0; 3];
+        let mut effective = 25.0;
+        d.apply_scn_limit(&mut effective);
+        assert_eq!(effective, 25.0);
+    }
+
+    #[tokio::test]
+    async fn disabled_scn_blocks_writes() {
+        let mut d = scn_driver().await;
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(
+            200,
+            1405,
+            27,
+            scn_regs(0),
+        )));
+        d.refresh_scn_status().await;
+        assert!(d.scn_writes_blocked());
+        assert_eq!(d.maybe_write_current(10.0, 0.0).await, None);
+        assert!(last_write(&mut d).is_none());
+    }
+
+    #[tokio::test]
+    async fn scn_read_range_does_not_overflow() {
+        let mut d = scn_driver().await;
+        let mut cfg = d.config().clone();
+        cfg.registers.scn_max_current = u16::MAX - 2;
+        d.update_config(cfg).unwrap();
+        assert!(d.scn_read_range().is_none());
+    }
+}
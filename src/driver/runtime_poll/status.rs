@@ -2,15 +2,26 @@ impl crate::driver::AlfenDriver {
     /// Derive Victron-esque status from base hardware status and current context.
     ///
     /// Rule order (highest precedence first):
+    /// - Station inoperative (1200) -> 10 (Error)
+    /// - Paused for overheating -> 14 (Overheating)
     /// - StartStop=Stopped -> 6 (Wait start)
     /// - Scheduled mode with inactive window -> 6 (Wait start)
     /// - Auto or Scheduled with Low SoC -> 7 (Low SOC)
     /// - Auto with near-zero current -> 4 (Wait sun)
-    /// - Fallback to base (0/1/2)
+    /// - Minimum + PV never waits for sun or SoC; it charges like Manual
+    /// - Fallback to the Mode 3 state: 5 (B1, waiting for authorization),
+    ///   3 (B2 after charging, Charged), 10/8 (E/F errors), else base (0/1/2)
     pub(super) fn derive_status(&self, status_base: i32, soc_below_min: Option<bool>) -> i32 {
+        if self.station_inoperative() {
+            return super::availability::STATUS_INOPERATIVE;
+        }
         let connected = status_base == 1 || status_base == 2;
         if !connected {
-            return status_base;
+            return self.mode3_status_code(status_base).unwrap_or(status_base);
+        }
+
+        if let Some(code) = self.thermal_status_override() {
+            return code;
         }
 
         // Wait start due to explicit stop
@@ -40,6 +51,6 @@ impl crate::driver::AlfenDriver {
             return 4;
         }
 
-        status_base
+        self.mode3_status_code(status_base).unwrap_or(status_base)
     }
 }
@@ -0,0 +1,162 @@
+// Full socket meter telemetry (registers 300..425)
+//
+// The 126-register meter block exceeds the 125-register Modbus read limit,
+// so it is fetched in two requests: instantaneous values (300..361) and the
+// FLOAT64 energy counters (362..425). The concatenated block also serves the
+// voltages/currents/power/energy the control loop needs.
+
+use crate::driver::types::MeterTelemetry;
+
+/// Number of registers in the meter block (300..425)
+pub(super) const METER_BLOCK_LEN: u16 = 126;
+/// Offset of the first energy counter (362) within the meter block
+const ENERGY_OFFSET: u16 = 62;
+
+/// Decode the extended values from a full meter block starting at register 300
+pub(super) fn decode_meter_telemetry(regs: &[u16]) -> MeterTelemetry {
+    let f32_at = |off: usize| -> Option<f64> {
+        regs.get(off..off + 2)
+            .and_then(crate::modbus::decode_32bit_float_opt)
+            .map(f64::from)
+    };
+    // Energy counters are in Wh/VAh/varh; expose them in k-units
+    let energy_at = |off: usize| -> Option<f64> {
+        regs.get(off..off + 4)
+            .and_then(crate::modbus::decode_64bit_float_opt)
+            .map(|v| v / 1000.0)
+    };
+    let f32_phases = |off: usize| [f32_at(off), f32_at(off + 2), f32_at(off + 4)];
+    let energy_phases = |off: usize| [energy_at(off), energy_at(off + 4), energy_at(off + 8)];
+    MeterTelemetry {
+        voltage_ll: f32_phases(12),
+        current_n: f32_at(18),
+        current_sum: f32_at(26),
+        power_factor: f32_phases(28),
+        power_factor_total: f32_at(34),
+        frequency: f32_at(36),
+        apparent_power: f32_phases(46),
+        apparent_power_total: f32_at(52),
+        reactive_power: f32_phases(54),
+        reactive_power_total: f32_at(60),
+        energy_delivered_kwh: energy_phases(62),
+        energy_delivered_total_kwh: energy_at(74),
+        energy_consumed_kwh: energy_phases(78),
+        energy_consumed_total_kwh: energy_at(90),
+        apparent_energy_kvah: energy_phases(94),
+        apparent_energy_total_kvah: energy_at(106),
+        reactive_energy_kvarh: energy_phases(110),
+        reactive_energy_total_kvarh: energy_at(122),
+    }
+}
+
+impl crate::driver::AlfenDriver {
+    /// Read the whole meter block in two requests; `None` unless both
+    /// requests return complete data
+    pub(super) async fn read_meter_block(&mut self) -> Option<Vec<u16>> {
+        let slave = self.config.modbus.socket_slave_id;
+        let base = self.config.registers.meter_block;
+        let manager = self.modbus_manager.as_mut()?;
+        let t0 = tokio::time::Instant::now();
+        let mut regs = Vec::with_capacity(METER_BLOCK_LEN as usize);
+        for (off, count) in [
+            (0, ENERGY_OFFSET),
+            (ENERGY_OFFSET, METER_BLOCK_LEN - ENERGY_OFFSET),
+        ] {
+            match manager
+                .read_holding_registers(slave, base + off, count)
+                .await
+            {
+                Ok(chunk) if chunk.len() >= count as usize => {
+                    regs.extend_from_slice(&chunk[..count as usize])
+                }
+                _ => {
+                    regs.clear();
+                    break;
+                }
+            }
+        }
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_meter_ms = Some(t0.elapsed().as_millis() as u64);
+        if regs.is_empty() {
+            self.logger
+                .debug("Full meter block unavailable; using the control register range");
+            return None;
+        }
+        Some(regs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{MockModbus, regs_from_f32, regs_from_f64};
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn meter_block() -> Vec<u16> {
+        let mut regs = vec![0u16; 6]; // 300..305 meter state, age and type
+        let f32s = [
+            230.0f32, 231.0, 232.0, // L-N voltages
+            400.0, 401.0, 402.0, // L-L voltages
+            0.5,   // neutral current
+            10.0, 11.0, 12.0, 33.0, // currents and sum
+            0.99, 0.98, 0.97, 0.98,  // power factors
+            50.01, // frequency
+            2300.0, 2530.0, 2780.0, 7610.0, // real power
+            2400.0, 2600.0, 2800.0, 7800.0, // apparent power
+            100.0, 110.0, 120.0, 330.0, // reactive power
+        ];
+        regs.extend(f32s.into_iter().flat_map(regs_from_f32));
+        for base in [1000.0, 2000.0, 3000.0, 4000.0] {
+            for v in [base, base * 2.0, base * 3.0, base * 6.0] {
+                regs.extend(regs_from_f64(v));
+            }
+        }
+        regs
+    }
+
+    #[test]
+    fn decodes_full_meter_block() {
+        let regs = meter_block();
+        assert_eq!(regs.len(), METER_BLOCK_LEN as usize);
+        let m = decode_meter_telemetry(&regs);
+        assert_eq!(m.voltage_ll, [Some(400.0), Some(401.0), Some(402.0)]);
+        assert_eq!(m.current_n, Some(0.5));
+        assert_eq!(m.current_sum, Some(33.0));
+        assert!((m.frequency.unwrap() - 50.01).abs() < 1e-3);
+        assert!((m.power_factor[0].unwrap() - 0.99).abs() < 1e-6);
+        assert_eq!(m.apparent_power_total, Some(7800.0));
+        assert_eq!(m.reactive_power, [Some(100.0), Some(110.0), Some(120.0)]);
+        assert_eq!(m.energy_delivered_kwh, [Some(1.0), Some(2.0), Some(3.0)]);
+        assert_eq!(m.energy_consumed_total_kwh, Some(12.0));
+        assert_eq!(m.reactive_energy_kvarh[2], Some(12.0));
+
+        // Unavailable registers (0xFFFF) decode to None
+        let mut regs = regs;
+        regs[36..38].copy_from_slice(&[0xFFFF, 0xFFFF]);
+        assert_eq!(decode_meter_telemetry(&regs).frequency, None);
+    }
+
+    #[tokio::test]
+    async fn full_telemetry_replaces_control_bulk_read() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.registers.full_meter_telemetry = true;
+        d.update_config(cfg).unwrap();
+        let slave = d.config().modbus.socket_slave_id;
+        let regs = meter_block();
+        d.modbus_manager = Some(Box::new(
+            MockModbus::new()
+                .with_read(slave, 300, 62, regs[..62].to_vec())
+                .with_read(slave, 362, 64, regs[62..].to_vec()),
+        ));
+        let m = d.read_realtime_values().await;
+        assert_eq!(m.voltages.l2, Some(231.0));
+        assert_eq!(m.currents.l3, Some(12.0));
+        assert_eq!(m.total_power, Some(7610.0));
+        assert_eq!(m.energy_kwh, Some(6.0));
+        let telemetry = d.meter_telemetry.clone().expect("meter telemetry");
+        assert!((telemetry.frequency.unwrap() - 50.01).abs() < 1e-3);
+    }
+}
@@ -5,10 +5,16 @@ use tokio::sync::mpsc;
 #[test]
 fn decode_triplet_handles_none_and_short() {
     let t = crate::driver::AlfenDriver::decode_triplet(&None);
-    assert_eq!((t.l1, t.l2, t.l3), (0.0, 0.0, 0.0));
+    assert_eq!((t.l1, t.l2, t.l3), (None, None, None));
     let regs = Some(vec![0u16; 4]);
     let t2 = crate::driver::AlfenDriver::decode_triplet(&regs);
-    assert_eq!((t2.l1, t2.l2, t2.l3), (0.0, 0.0, 0.0));
+    assert_eq!((t2.l1, t2.l2, t2.l3), (None, None, None));
+    // Reserved/unavailable registers read as 0xFFFF (NaN)
+    let mut regs = vec![0xFFFFu16; 2];
+    regs.extend(regs_from_f32(0.0));
+    regs.extend(regs_from_f32(231.0));
+    let t3 = crate::driver::AlfenDriver::decode_triplet(&Some(regs));
+    assert_eq!((t3.l1, t3.l2, t3.l3), (None, Some(0.0), Some(231.0)));
 }
 
 #[test]
@@ -25,17 +31,21 @@ fn decode_triplet_parses_values() {
         ((c[2] as u16) << 8) | c[3] as u16,
     ];
     let t = crate::driver::AlfenDriver::decode_triplet(&Some(regs));
-    assert!((t.l1 - 230.0).abs() < 0.01);
-    assert!((t.l2 - 231.5).abs() < 0.01);
-    assert!((t.l3 - 229.4).abs() < 0.01);
+    assert!((t.l1.unwrap() - 230.0).abs() < 0.01);
+    assert!((t.l2.unwrap() - 231.5).abs() < 0.01);
+    assert!((t.l3.unwrap() - 229.4).abs() < 0.01);
 }
 
 #[test]
 fn decode_energy_kwh_handles_inputs() {
-    assert_eq!(crate::driver::AlfenDriver::decode_energy_kwh(&None), 0.0);
+    assert_eq!(crate::driver::AlfenDriver::decode_energy_kwh(&None), None);
     assert_eq!(
         crate::driver::AlfenDriver::decode_energy_kwh(&Some(vec![0u16; 2])),
-        0.0
+        None
+    );
+    assert_eq!(
+        crate::driver::AlfenDriver::decode_energy_kwh(&Some(vec![0xFFFFu16; 4])),
+        None
     );
     let val: f64 = 1234.0;
     let be = val.to_be_bytes();
@@ -45,7 +55,7 @@ fn decode_energy_kwh_handles_inputs() {
         ((be[4] as u16) << 8) | be[5] as u16,
         ((be[6] as u16) << 8) | be[7] as u16,
     ];
-    let kwh = crate::driver::AlfenDriver::decode_energy_kwh(&Some(regs));
+    let kwh = crate::driver::AlfenDriver::decode_energy_kwh(&Some(regs)).unwrap();
     assert!((kwh - 1.234).abs() < 1e-9);
 }
 
@@ -53,38 +63,48 @@ fn decode_energy_kwh_handles_inputs() {
 fn decode_powers_approximates_when_small() {
     let p_regs = Some(vec![0u16; 8]);
     let voltages = LineTriplet {
-        l1: 230.0,
-        l2: 231.0,
-        l3: 229.0,
+        l1: Some(230.0),
+        l2: Some(231.0),
+        l3: Some(229.0),
     };
     let currents = LineTriplet {
-        l1: 5.0,
-        l2: 6.0,
-        l3: 7.0,
+        l1: Some(5.0),
+        l2: Some(6.0),
+        l3: Some(7.0),
     };
     let (p_triplet, total) =
         crate::driver::AlfenDriver::decode_powers(&p_regs, &voltages, &currents);
-    assert_eq!(p_triplet.l1, (230.0_f64 * 5.0_f64).round());
-    assert_eq!(p_triplet.l2, (231.0_f64 * 6.0_f64).round());
-    assert_eq!(p_triplet.l3, (229.0_f64 * 7.0_f64).round());
-    assert_eq!(total, p_triplet.l1 + p_triplet.l2 + p_triplet.l3);
-}
-
-#[test]
-fn compute_status_from_regs_maps_strings() {
-    let regs = vec![0x4332, 0x0000, 0x0000, 0x0000, 0x0000];
-    let s = crate::driver::AlfenDriver::compute_status_from_regs(&Some(regs));
-    assert_eq!(s, 2);
-    let regs_b1 = vec![0x4231, 0, 0, 0, 0];
+    assert_eq!(p_triplet.l1, Some((230.0_f64 * 5.0_f64).round()));
+    assert_eq!(p_triplet.l2, Some((231.0_f64 * 6.0_f64).round()));
+    assert_eq!(p_triplet.l3, Some((229.0_f64 * 7.0_f64).round()));
     assert_eq!(
-        crate::driver::AlfenDriver::compute_status_from_regs(&Some(regs_b1)),
-        1
-    );
-    let regs_xx = vec![0x5858, 0, 0, 0, 0];
-    assert_eq!(
-        crate::driver::AlfenDriver::compute_status_from_regs(&Some(regs_xx)),
-        0
+        total,
+        Some(p_triplet.l1.unwrap() + p_triplet.l2.unwrap() + p_triplet.l3.unwrap())
     );
+
+    // Unavailable power without voltage/current stays unavailable
+    let none = LineTriplet {
+        l1: None,
+        l2: None,
+        l3: None,
+    };
+    let (p_triplet, total) =
+        crate::driver::AlfenDriver::decode_powers(&Some(vec![0xFFFFu16; 8]), &none, &currents);
+    assert_eq!((p_triplet.l1, total), (None, None));
+}
+
+#[tokio::test]
+async fn alfen_backend_maps_status_strings() {
+    use crate::backend::ChargerBackend;
+    let backend = crate::backend::AlfenBackend::new(&crate::config::Config::default());
+    for (regs, code) in [
+        (vec![0x4332, 0, 0, 0, 0], 2),
+        (vec![0x4231, 0, 0, 0, 0], 1),
+        (vec![0x5858, 0, 0, 0, 0], 0),
+    ] {
+        let mut modbus = MockModbus::new().with_read(1, 1201, 5, regs);
+        assert_eq!(backend.read_status(&mut modbus).await.unwrap().code, code);
+    }
 }
 
 #[tokio::test]
@@ -106,6 +126,10 @@ async fn derive_status_variants() {
 
     d.current_mode = crate::controls::ChargingMode::Scheduled;
     assert_eq!(d.derive_status(1, Some(true)), 7);
+
+    // Minimum + PV charges regardless of sun and SoC
+    d.current_mode = crate::controls::ChargingMode::MinPv;
+    assert_eq!(d.derive_status(2, Some(true)), 2);
 }
 
 #[tokio::test]
@@ -114,11 +138,11 @@ async fn ev_power_for_subtract_and_should_send_update() {
     let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
 
     d.last_sent_current = 10.0;
-    d.last_set_current_monotonic = std::time::Instant::now();
-    let ev_sub = d.ev_power_for_subtract(1234.0);
+    d.last_set_current_monotonic = tokio::time::Instant::now();
+    let ev_sub = d.ev_power_for_subtract(Some(1234.0));
     assert!(ev_sub >= 10.0 * 230.0 * 3.0 - 1.0);
 
-    d.last_current_set_time = std::time::Instant::now()
+    d.last_current_set_time = tokio::time::Instant::now()
         - std::time::Duration::from_millis(d.config.controls.current_update_interval as u64 + 10);
     d.last_sent_current = 10.0;
     let (should, need_change, _) = d.should_send_update(10.3);
@@ -138,23 +162,25 @@ async fn current_mode_reason_strings() {
     assert_eq!(d.current_mode_reason(), "pv_auto");
     d.current_mode = crate::controls::ChargingMode::Scheduled;
     assert_eq!(d.current_mode_reason(), "scheduled");
+    d.current_mode = crate::controls::ChargingMode::MinPv;
+    assert_eq!(d.current_mode_reason(), "min_pv");
 }
 
-struct MockModbus {
+pub(super) struct MockModbus {
     reads: HashMap<(u8, u16, u16), Vec<u16>>,
     write_ok: bool,
-    last_write: Option<(u8, u16, Vec<u16>)>,
+    pub(super) last_write: Option<(u8, u16, Vec<u16>)>,
 }
 
 impl MockModbus {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         Self {
             reads: HashMap::new(),
             write_ok: true,
             last_write: None,
         }
     }
-    fn with_read(mut self, slave: u8, addr: u16, count: u16, data: Vec<u16>) -> Self {
+    pub(super) fn with_read(mut self, slave: u8, addr: u16, count: u16, data: Vec<u16>) -> Self {
         self.reads.insert((slave, addr, count), data);
         self
     }
@@ -193,11 +219,11 @@ impl crate::driver::modbus_like::ModbusLike for MockModbus {
     }
 }
 
-fn regs_from_f32(v: f32) -> Vec<u16> {
+pub(super) fn regs_from_f32(v: f32) -> Vec<u16> {
     crate::modbus::encode_32bit_float(v).to_vec()
 }
 
-fn regs_from_f64(v: f64) -> Vec<u16> {
+pub(super) fn regs_from_f64(v: f64) -> Vec<u16> {
     let be = v.to_be_bytes();
     vec![
         ((be[0] as u16) << 8) | be[1] as u16,
@@ -276,11 +302,11 @@ async fn read_realtime_values_decodes_all_fields() {
         );
     d.modbus_manager = Some(Box::new(mock));
     let m = d.read_realtime_values().await;
-    assert!((m.voltages.l1 - 230.0).abs() < 0.01);
-    assert!((m.currents.l3 - 8.0).abs() < 0.01);
-    assert_eq!(m.powers.l2.round() as i64, 1300);
-    assert_eq!(m.total_power.round() as i64, 3900);
-    assert!((m.energy_kwh - 1.234).abs() < 1e-9);
+    assert!((m.voltages.l1.unwrap() - 230.0).abs() < 0.01);
+    assert!((m.currents.l3.unwrap() - 8.0).abs() < 0.01);
+    assert_eq!(m.powers.l2.unwrap().round() as i64, 1300);
+    assert_eq!(m.total_power.unwrap().round() as i64, 3900);
+    assert!((m.energy_kwh.unwrap() - 1.234).abs() < 1e-9);
     assert_eq!(m.status, 2);
 }
 
@@ -374,6 +400,32 @@ async fn poll_cycle_with_manual_mode_writes_current() {
     assert!((d.last_sent_current - 6.0).abs() < f32::EPSILON);
 }
 
+#[tokio::test]
+async fn poll_cycle_fails_fast_and_publishes_snapshot_while_disconnected() {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let mcfg = crate::config::ModbusConfig {
+        ip: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    d.modbus_manager = Some(Box::new(crate::modbus::ModbusConnectionManager::new(
+        &mcfg,
+        std::time::Duration::from_secs(5),
+    )));
+    let mut snapshots = d.status_snapshot_tx.subscribe();
+    let started = tokio::time::Instant::now();
+    for _ in 0..3 {
+        d.poll_cycle().await.unwrap();
+    }
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    assert!(snapshots.has_changed().unwrap());
+    assert_eq!(snapshots.borrow_and_update().modbus_connected, Some(false));
+}
+
 #[tokio::test]
 async fn insufficient_solar_grace_timer_starts_and_expires() {
     let (tx, rx) = mpsc::unbounded_channel();
@@ -391,7 +443,7 @@ async fn insufficient_solar_grace_timer_starts_and_expires() {
 
     // Simulate that we were charging at >= min current
     d.last_sent_current = 6.0;
-    d.last_set_current_monotonic = std::time::Instant::now();
+    d.last_set_current_monotonic = tokio::time::Instant::now();
 
     // No PV available -> base effective would be 0.0
     let (eff1, _soc) = d.compute_effective_current_with_soc(0.0, 0.0, 0.0).await;
@@ -401,7 +453,7 @@ async fn insufficient_solar_grace_timer_starts_and_expires() {
 
     // Force timer expiry
     d.min_charge_timer_deadline =
-        Some(std::time::Instant::now() - std::time::Duration::from_secs(1));
+        Some(tokio::time::Instant::now() - std::time::Duration::from_secs(1));
 
     // Recompute under same insufficient PV conditions
     let (eff2, _soc2) = d.compute_effective_current_with_soc(0.0, 0.0, 0.0).await;
@@ -447,7 +499,7 @@ async fn grace_timer_does_not_restart_without_pv_improvement_after_expiry() {
 
     // Expire the timer
     d.min_charge_timer_deadline =
-        Some(std::time::Instant::now() - std::time::Duration::from_secs(1));
+        Some(tokio::time::Instant::now() - std::time::Duration::from_secs(1));
 
     // Recompute with still no PV -> should allow stop and clear timer
     let (eff2, _soc2) = d.compute_effective_current_with_soc(0.0, 0.0, 0.0).await;
@@ -459,7 +511,7 @@ async fn grace_timer_does_not_restart_without_pv_improvement_after_expiry() {
 
     // Simulate immediate write to 0 A (as poll_cycle would do), which updates monotonic timestamp
     d.last_sent_current = 0.0;
-    d.last_set_current_monotonic = std::time::Instant::now();
+    d.last_set_current_monotonic = tokio::time::Instant::now();
 
     // Still no PV improvement: the timer must NOT restart; effective stays 0.0
     let (eff3, _soc3) = d.compute_effective_current_with_soc(0.0, 0.0, 0.0).await;
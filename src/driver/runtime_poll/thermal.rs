@@ -0,0 +1,152 @@
+//! Board temperature monitoring and thermal derating
+
+use crate::driver::types::ThermalStatus;
+
+/// Victron evcharger status "Overheating detected"
+const STATUS_OVERHEATING: i32 = 14;
+
+impl crate::driver::AlfenDriver {
+    /// Read the board temperature and update the thermal state
+    pub(super) async fn refresh_temperature(&mut self) {
+        let station_id = self.config.modbus.station_slave_id;
+        let addr = self.config.registers.temperature;
+        let t0 = tokio::time::Instant::now();
+        let temperature = match self.modbus_manager.as_mut() {
+            Some(m) => m
+                .read_holding_registers(station_id, addr, 2)
+                .await
+                .ok()
+                .and_then(|regs| crate::modbus::decode_32bit_float_opt(&regs)),
+            None => None,
+        };
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_temperature_ms = Some(t0.elapsed().as_millis() as u64);
+        self.update_thermal_state(temperature);
+    }
+
+    pub(super) fn update_thermal_state(&mut self, temperature: Option<f32>) {
+        let c = &self.config.controls;
+        let (warn, derate, stop) = (
+            c.temperature_warning_c,
+            c.temperature_derate_c,
+            c.temperature_stop_c,
+        );
+        let was_stopped = self.thermal_stopped();
+        let (state, factor) = match temperature {
+            // Unknown temperature keeps a latched stop, otherwise no derating
+            None if was_stopped => ("stopped", 0.0),
+            None => ("normal", 1.0),
+            Some(t) if t >= stop || (was_stopped && t >= derate) => ("stopped", 0.0),
+            Some(t) if t > derate => ("derating", ((stop - t) / (stop - derate)).clamp(0.0, 1.0)),
+            Some(t) if t >= warn => ("warning", 1.0),
+            Some(_) => ("normal", 1.0),
+        };
+        let prev_state = self.thermal.as_ref().map(|s| s.state.clone());
+        if prev_state.as_deref().unwrap_or("normal") != state {
+            self.log_thermal_transition(state, temperature);
+        }
+        self.thermal = Some(ThermalStatus {
+            temperature_c: temperature,
+            state: state.to_string(),
+            derate_factor: factor,
+        });
+    }
+
+    fn log_thermal_transition(&self, state: &str, temperature: Option<f32>) {
+        let t = temperature
+            .map(|t| format!("{:.1} °C", t))
+            .unwrap_or_else(|| "unknown".to_string());
+        match state {
+            "stopped" => self.logger.error(&format!(
+                "Station overheating ({}); pausing charging until below {:.1} °C",
+                t, self.config.controls.temperature_derate_c
+            )),
+            "derating" => self.logger.warn(&format!(
+                "Station temperature {} above {:.1} °C; derating charge current",
+                t, self.config.controls.temperature_derate_c
+            )),
+            "warning" => self.logger.warn(&format!(
+                "Station temperature {} above warning threshold {:.1} °C",
+                t, self.config.controls.temperature_warning_c
+            )),
+            _ => self
+                .logger
+                .info(&format!("Station temperature back to normal ({})", t)),
+        }
+    }
+
+    pub(super) fn thermal_stopped(&self) -> bool {
+        self.thermal.as_ref().is_some_and(|s| s.state == "stopped")
+    }
+
+    /// Victron status override while charging is paused for overheating
+    pub(super) fn thermal_status_override(&self) -> Option<i32> {
+        self.thermal_stopped().then_some(STATUS_OVERHEATING)
+    }
+
+    /// Scale the effective current by the thermal derating factor. A derated
+    /// current never drops below the minimum settable current; only the stop
+    /// temperature pauses charging.
+    pub(super) fn apply_thermal_derating(&self, effective: &mut f32) {
+        let Some(factor) = self.thermal.as_ref().map(|s| s.derate_factor) else {
+            return;
+        };
+        if factor >= 1.0 || *effective <= 0.0 {
+            return;
+        }
+        *effective = if factor <= 0.0 {
+            0.0
+        } else {
+            (*effective * factor).max(self.config.controls.min_set_current)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn derates_linearly_and_latches_stop_until_cooled() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.controls.temperature_warning_c = 60.0;
+        cfg.controls.temperature_derate_c = 70.0;
+        cfg.controls.temperature_stop_c = 80.0;
+        cfg.controls.min_set_current = 6.0;
+        d.update_config(cfg).unwrap();
+
+        d.update_thermal_state(Some(65.0));
+        assert_eq!(d.thermal.as_ref().unwrap().state, "warning");
+        let mut eff = 16.0;
+        d.apply_thermal_derating(&mut eff);
+        assert_eq!(eff, 16.0);
+
+        d.update_thermal_state(Some(75.0));
+        let mut eff = 16.0;
+        d.apply_thermal_derating(&mut eff);
+        assert_eq!(eff, 8.0);
+        d.update_thermal_state(Some(79.0));
+        let mut eff = 16.0;
+        d.apply_thermal_derating(&mut eff);
+        assert_eq!(eff, 6.0);
+
+        d.update_thermal_state(Some(80.0));
+        assert_eq!(d.thermal_status_override(), Some(14));
+        let mut eff = 16.0;
+        d.apply_thermal_derating(&mut eff);
+        assert_eq!(eff, 0.0);
+        assert_eq!(d.derive_status(2, None), 14);
+
+        // Stays paused while still above the derate temperature
+        d.update_thermal_state(Some(72.0));
+        assert!(d.thermal_stopped());
+        d.update_thermal_state(None);
+        assert!(d.thermal_stopped());
+        d.update_thermal_state(Some(69.0));
+        assert_eq!(d.thermal.as_ref().unwrap().state, "warning");
+        assert_eq!(d.thermal_status_override(), None);
+    }
+}
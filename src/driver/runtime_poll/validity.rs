@@ -0,0 +1,183 @@
+//! Setpoint validity watchdog and safe-current fallback detection
+
+use crate::driver::types::ValidityStatus;
+
+/// Values decoded from the socket's 1206..1214 register block
+struct ValidityRead {
+    applied: Option<f32>,
+    remaining_s: u32,
+    safe_current: Option<f32>,
+    accounted_for: bool,
+}
+
+impl crate::driver::AlfenDriver {
+    async fn read_validity_block(&mut self) -> Option<ValidityRead> {
+        let slave = self.config.modbus.socket_slave_id;
+        let r = &self.config.registers;
+        let (start, end) = r.validity_block()?;
+        let (applied_at, valid_at, safe_at, flag_at) = (
+            r.actual_applied_current,
+            r.max_current_valid_time,
+            r.safe_current,
+            r.setpoint_accounted_for,
+        );
+        let regs = self
+            .modbus_manager
+            .as_mut()?
+            .read_holding_registers(slave, start, end - start)
+            .await
+            .ok()?;
+        let at = |addr: u16, count: usize| {
+            let off = (addr - start) as usize;
+            regs.get(off..off + count)
+        };
+        let float = |addr: u16| {
+            at(addr, 2)
+                .and_then(|w| crate::modbus::decode_32bit_float(w).ok())
+                .filter(|v| v.is_finite())
+        };
+        let remaining = at(valid_at, 2)?;
+        Some(ValidityRead {
+            applied: float(applied_at),
+            remaining_s: (u32::from(remaining[0]) << 16) | u32::from(remaining[1]),
+            safe_current: float(safe_at),
+            accounted_for: at(flag_at, 1)?[0] == 1,
+        })
+    }
+
+    /// Read remaining validity and safe current and detect safe-current fallbacks
+    pub(super) async fn refresh_validity_status(&mut self) {
+        let t0 = tokio::time::Instant::now();
+        let read = self.read_validity_block().await;
+        self.last_poll_steps
+            .get_or_insert_with(Default::default)
+            .read_validity_ms = Some(t0.elapsed().as_millis() as u64);
+
+        self.verify_setpoint(read.as_ref().map_or_else(Default::default, |r| {
+            crate::controls::SetpointReadback {
+                applied: r.applied,
+                accounted_for: Some(r.accounted_for),
+            }
+        }));
+        let prev_active = self.validity.as_ref().map(|v| v.fallback_active);
+        let mut v = self.validity.take().unwrap_or_default();
+        match read {
+            Some(read) => {
+                if read.applied.is_some() {
+                    self.actual_applied_current = read.applied;
+                }
+                v.remaining_s = Some(read.remaining_s);
+                v.validity_period_s = v.validity_period_s.max(read.remaining_s);
+                v.safe_current = read.safe_current;
+                // Validity expired and the station no longer uses our setpoint
+                let fallback = read.remaining_s == 0 && !read.accounted_for;
+                self.note_fallback_transition(&mut v, prev_active, fallback);
+                v.fallback_active = fallback;
+                v.disabled = read.remaining_s == 0 && read.accounted_for;
+            }
+            None => v.remaining_s = None,
+        }
+        self.validity = Some(v);
+    }
+
+    fn note_fallback_transition(
+        &self,
+        v: &mut ValidityStatus,
+        prev_active: Option<bool>,
+        fallback: bool,
+    ) {
+        if fallback && prev_active == Some(false) {
+            v.fallback_events += 1;
+            v.last_fallback_at = Some(self.wall_clock.now().to_rfc3339());
+            self.logger.warn(&format!(
+                "Station fell back to safe current ({} A): setpoint validity expired; reasserting",
+                v.safe_current
+                    .map(|a| format!("{:.1}", a))
+                    .unwrap_or_else(|| "?".to_string())
+            ));
+        } else if !fallback && prev_active == Some(true) {
+            self.logger
+                .info("Station uses the Modbus setpoint again (safe-current fallback ended)");
+        }
+    }
+
+    /// Whether the setpoint must be reasserted according to the station's
+    /// remaining validity (from the cycle's validity read); `None` when the
+    /// validity is unknown or disabled, leaving it to the watchdog interval
+    pub(super) fn validity_reassert_due(&self) -> Option<bool> {
+        let v = self.validity.as_ref().filter(|v| !v.disabled)?;
+        let remaining = v.remaining_s?;
+        // Never wait past half the station's validity period
+        let margin = self
+            .config
+            .controls
+            .validity_reassert_margin_seconds
+            .min(v.validity_period_s / 2);
+        Some(v.fallback_active || remaining <= margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{MockModbus, regs_from_f32};
+    use tokio::sync::mpsc;
+
+    fn block(applied: f32, remaining: u32, accounted: u16) -> Vec<u16> {
+        let mut regs = regs_from_f32(applied);
+        regs.extend([(remaining >> 16) as u16, remaining as u16]);
+        regs.extend(regs_from_f32(16.0)); // 1210 modbus max current
+        regs.extend(regs_from_f32(6.0)); // 1212 safe current
+        regs.push(accounted);
+        regs
+    }
+
+    async fn driver_reading(d: &mut crate::driver::AlfenDriver, regs: Vec<u16>) {
+        let slave = d.config().modbus.socket_slave_id;
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(slave, 1206, 9, regs)));
+        d.refresh_validity_status().await;
+    }
+
+    #[tokio::test]
+    async fn schedules_reassert_from_validity_and_counts_fallbacks() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        assert_eq!(d.validity_reassert_due(), None);
+
+        driver_reading(&mut d, block(16.0, 60, 1)).await;
+        assert_eq!(d.validity_reassert_due(), Some(false));
+        let v = d.validity.clone().unwrap();
+        assert_eq!(v.safe_current, Some(6.0));
+        assert_eq!(v.validity_period_s, 60);
+
+        driver_reading(&mut d, block(16.0, 20, 1)).await;
+        assert_eq!(d.validity_reassert_due(), Some(true));
+
+        driver_reading(&mut d, block(6.0, 0, 0)).await;
+        let v = d.validity.clone().unwrap();
+        assert!(v.fallback_active);
+        assert_eq!(v.fallback_events, 1);
+        assert!(v.last_fallback_at.is_some());
+        assert_eq!(d.actual_applied_current, Some(6.0));
+
+        // Still in fallback: no new event; unreadable validity falls back to the fixed interval
+        driver_reading(&mut d, block(6.0, 0, 0)).await;
+        assert_eq!(d.validity.as_ref().unwrap().fallback_events, 1);
+        driver_reading(&mut d, vec![]).await;
+        assert_eq!(d.validity_reassert_due(), None);
+    }
+
+    #[tokio::test]
+    async fn disabled_validity_leaves_reassert_to_watchdog() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        // Remaining 0 while the setpoint is used: validity time disabled
+        driver_reading(&mut d, block(16.0, 0, 1)).await;
+        let v = d.validity.clone().unwrap();
+        assert!(v.disabled);
+        assert!(!v.fallback_active);
+        assert_eq!(d.validity_reassert_due(), None);
+
+        driver_reading(&mut d, block(16.0, 60, 1)).await;
+        assert_eq!(d.validity_reassert_due(), Some(false));
+    }
+}
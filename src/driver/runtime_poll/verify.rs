@@ -0,0 +1,213 @@
+// Closed-loop setpoint verification
+//
+// After each socket setpoint write the station's Actual Applied Max Current
+// (1206) and "setpoint accounted for" flag (1214) are checked on the first
+// validity read at least `controls.verification_delay` later, so the poll
+// cycle never sleeps while holding the driver. A rejected setpoint is
+// re-written `controls.retry_delay` after its check, and each re-write is
+// verified before the next one; after
+// `controls.max_retries` consecutive rejections (the target may change in
+// between, as it does in Auto mode) the driver reports "setpoint not
+// accepted" (e.g. Modbus writing not allowed in ACE).
+
+use crate::controls::SetpointReadback;
+use crate::driver::types::SetpointRetry;
+
+impl crate::driver::AlfenDriver {
+    /// Whether a scheduled retry for a rejected setpoint is due
+    pub(super) fn setpoint_retry_due(&self) -> bool {
+        self.setpoint_retry
+            .and_then(|r| r.next_at)
+            .is_some_and(|at| tokio::time::Instant::now() >= at)
+    }
+
+    /// Check the setpoint just written once the verification delay has passed
+    pub(super) fn schedule_setpoint_verification(&mut self, target: f32) {
+        let delay =
+            std::time::Duration::from_secs_f64(self.config.controls.verification_delay.max(0.0));
+        self.setpoint_verify = Some((target, tokio::time::Instant::now() + delay));
+        // A pending retry waits for this write's readback
+        if let Some(retry) = self.setpoint_retry.as_mut() {
+            retry.next_at = None;
+        }
+    }
+
+    /// Feed this cycle's 1206/1214 readback to a pending verification
+    pub(super) fn verify_setpoint(&mut self, readback: SetpointReadback) {
+        if let Some((target, due_at)) = self.setpoint_verify
+            && tokio::time::Instant::now() >= due_at
+        {
+            self.setpoint_verify = None;
+            self.record_setpoint_readback(target, readback);
+        }
+    }
+
+    pub(super) fn record_setpoint_readback(&mut self, target: f32, readback: SetpointReadback) {
+        self.actual_applied_current = readback.applied;
+        match readback.accepted(target, self.config.controls.current_tolerance) {
+            None => {
+                self.logger
+                    .debug("Setpoint readback unavailable; skipping verification");
+                self.setpoint_retry = None;
+            }
+            Some(true) => {
+                if self.setpoint_accepted == Some(false) {
+                    self.logger.info(&format!(
+                        "Setpoint {:.2} A accepted by the station again",
+                        target
+                    ));
+                }
+                self.setpoint_accepted = Some(true);
+                self.setpoint_retry = None;
+            }
+            Some(false) => self.handle_rejected_setpoint(target, readback),
+        }
+    }
+
+    fn handle_rejected_setpoint(&mut self, target: f32, readback: SetpointReadback) {
+        // Already reported as not accepted: keep verifying on regular updates
+        // without another burst of retries
+        if self.setpoint_accepted == Some(false) {
+            self.setpoint_retry = None;
+            return;
+        }
+        let attempts = self.setpoint_retry.map_or(1, |r| r.attempts + 1);
+        let max_retries = self.config.controls.max_retries;
+        if attempts <= max_retries {
+            self.logger.warn(&format!(
+                "Setpoint {:.2} A not accepted (applied={:?} A, accounted_for={:?}); retry {}/{}",
+                target, readback.applied, readback.accounted_for, attempts, max_retries
+            ));
+            let delay =
+                std::time::Duration::from_secs_f64(self.config.controls.retry_delay.max(0.0));
+            self.setpoint_retry = Some(SetpointRetry {
+                attempts,
+                next_at: Some(tokio::time::Instant::now() + delay),
+            });
+        } else {
+            self.logger.error(&format!(
+                "Setpoint not accepted: station ignored {:.2} A after {} retries (applied={:?} A, accounted_for={:?}); check that Modbus slave max current is enabled in ACE",
+                target, max_retries, readback.applied, readback.accounted_for
+            ));
+            self.setpoint_accepted = Some(false);
+            self.setpoint_retry = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn rejected() -> SetpointReadback {
+        SetpointReadback {
+            applied: Some(0.0),
+            accounted_for: Some(false),
+        }
+    }
+
+    #[test]
+    fn readback_acceptance_rules() {
+        let ok = SetpointReadback {
+            applied: Some(13.0),
+            accounted_for: Some(true),
+        };
+        // Lower applied current (other limits) is still accepted
+        assert_eq!(ok.accepted(16.0, 0.5), Some(true));
+        assert_eq!(ok.accepted(12.0, 0.5), Some(false));
+        assert_eq!(rejected().accepted(16.0, 0.5), Some(false));
+        assert_eq!(SetpointReadback::default().accepted(16.0, 0.5), None);
+    }
+
+    #[tokio::test]
+    async fn rejected_setpoint_retries_then_reports_not_accepted() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.controls.max_retries = 2;
+        cfg.controls.retry_delay = 0.0;
+        d.update_config(cfg).unwrap();
+
+        d.record_setpoint_readback(10.0, rejected());
+        assert!(d.setpoint_retry_due());
+        assert_eq!(d.setpoint_accepted, None);
+        // A new target (Auto mode) still counts as a consecutive rejection
+        d.record_setpoint_readback(11.5, rejected());
+        assert_eq!(d.setpoint_retry.map(|r| r.attempts), Some(2));
+        d.record_setpoint_readback(9.0, rejected());
+        assert_eq!(d.setpoint_accepted, Some(false));
+        assert!(!d.setpoint_retry_due());
+        assert_eq!(d.actual_applied_current, Some(0.0));
+
+        // No new retry burst while reported; recovery clears the condition
+        d.record_setpoint_readback(10.0, rejected());
+        assert!(d.setpoint_retry.is_none());
+        d.record_setpoint_readback(
+            10.0,
+            SetpointReadback {
+                applied: Some(10.0),
+                accounted_for: Some(true),
+            },
+        );
+        assert_eq!(d.setpoint_accepted, Some(true));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_waits_for_a_verification_longer_than_a_poll() {
+        use super::super::tests::MockModbus;
+        use std::time::Duration;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.controls.verification_delay = 2.5;
+        cfg.controls.retry_delay = 0.0;
+        cfg.controls.max_retries = 2;
+        d.update_config(cfg).unwrap();
+        let poll = Duration::from_millis(d.config().poll_interval_ms);
+        d.modbus_manager = Some(Box::new(MockModbus::new()));
+
+        assert!(d.maybe_write_current(10.0, 0.0).await.is_some());
+        tokio::time::advance(3 * poll).await;
+        d.verify_setpoint(rejected());
+        for attempt in 1..=2 {
+            assert_eq!(d.setpoint_retry.map(|r| r.attempts), Some(attempt));
+            assert!(d.maybe_write_current(10.0, 0.0).await.is_some());
+            // No re-write while the retry's readback is not due yet
+            tokio::time::advance(poll).await;
+            d.verify_setpoint(rejected());
+            assert!(d.maybe_write_current(10.0, 0.0).await.is_none());
+            tokio::time::advance(2 * poll).await;
+            d.verify_setpoint(rejected());
+        }
+        assert_eq!(d.setpoint_accepted, Some(false));
+        assert!(d.maybe_write_current(10.0, 0.0).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn verifies_on_a_later_validity_read() {
+        use super::super::tests::{MockModbus, regs_from_f32};
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = d.config().clone();
+        cfg.controls.verification_delay = 0.0;
+        d.update_config(cfg).unwrap();
+        let slave = d.config().modbus.socket_slave_id;
+
+        d.modbus_manager = Some(Box::new(MockModbus::new()));
+        assert!(d.write_effective_current(10.0).await);
+        assert!(d.setpoint_verify.is_some());
+        assert_eq!(d.setpoint_accepted, None);
+
+        // 1206 applied, 1208 validity, 1210 max current, 1212 safe, 1214 flag
+        let mut regs = regs_from_f32(10.0);
+        regs.extend([0, 60]);
+        regs.extend(regs_from_f32(10.0));
+        regs.extend(regs_from_f32(6.0));
+        regs.push(1);
+        d.modbus_manager = Some(Box::new(MockModbus::new().with_read(slave, 1206, 9, regs)));
+        d.refresh_validity_status().await;
+        assert!(d.setpoint_verify.is_none());
+        assert_eq!(d.setpoint_accepted, Some(true));
+    }
+}
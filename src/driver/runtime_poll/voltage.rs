@@ -0,0 +1,124 @@
+//! Smoothed phase voltages for watts <-> amps conversion
+
+use super::meas::LineTriplet;
+use super::rotation::line_pair_index;
+
+/// Measurements outside this range are treated as unavailable (V)
+const PLAUSIBLE_VOLTAGE: std::ops::RangeInclusive<f32> = 50.0..=500.0;
+
+/// Smoothed voltages in installation phase order
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VoltageEma {
+    /// Line-to-neutral L1, L2, L3
+    ln: [Option<f32>; 3],
+    /// Line-to-line L1-L2, L2-L3, L3-L1
+    ll: [Option<f32>; 3],
+}
+
+fn smooth(prev: Option<f32>, sample: Option<f64>, alpha: f32) -> Option<f32> {
+    let sample = sample
+        .map(|v| v as f32)
+        .filter(|v| PLAUSIBLE_VOLTAGE.contains(v))?;
+    Some(match prev {
+        Some(p) if alpha > 0.0 => alpha * sample + (1.0 - alpha) * p,
+        _ => sample,
+    })
+}
+
+impl VoltageEma {
+    fn update(&mut self, ln: [Option<f64>; 3], ll: [Option<f64>; 3], alpha: f32) {
+        for i in 0..3 {
+            self.ln[i] = smooth(self.ln[i], ln[i], alpha);
+            self.ll[i] = smooth(self.ll[i], ll[i], alpha);
+        }
+    }
+}
+
+impl crate::driver::AlfenDriver {
+    /// Fold this cycle's (rotated) voltages into the smoothed values
+    pub(super) fn update_voltage_ema(&mut self, voltages: &LineTriplet) {
+        let ln = [voltages.l1, voltages.l2, voltages.l3];
+        let ll = self
+            .meter_telemetry
+            .as_ref()
+            .map(|t| t.voltage_ll)
+            .unwrap_or_default();
+        let alpha = self.config.controls.voltage_ema_alpha.clamp(0.0, 1.0);
+        self.voltage_ema.update(ln, ll, alpha);
+    }
+
+    /// Line-to-neutral voltage of installation phase `phase` (0..=2)
+    pub(crate) fn phase_voltage(&self, phase: usize) -> f32 {
+        let c = &self.config.controls;
+        let fallback = if c.line_to_line {
+            c.nominal_voltage / 3f32.sqrt()
+        } else {
+            c.nominal_voltage
+        };
+        self.voltage_ema.ln[phase]
+            .filter(|_| !self.meter_stale())
+            .unwrap_or(fallback)
+    }
+
+    /// Voltage of each phase in use when charging on `phases` phases
+    pub(crate) fn charging_voltages(&self, phases: u8) -> Vec<f32> {
+        let c = &self.config.controls;
+        if phases >= 3 {
+            // Three-phase charging is line-to-neutral on every system
+            return (0..3).map(|i| self.phase_voltage(i)).collect();
+        }
+        let map = self.phase_map();
+        if !c.line_to_line {
+            return vec![self.phase_voltage(map[0])];
+        }
+        let v = self.voltage_ema.ll[line_pair_index(map[0], map[1])];
+        vec![
+            v.filter(|_| !self.meter_stale())
+                .unwrap_or(c.nominal_voltage),
+        ]
+    }
+
+    /// Watts per ampere of setpoint when charging on `phases` phases
+    pub(crate) fn watts_per_amp(&self, phases: u8) -> f32 {
+        self.charging_voltages(phases).iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn meas(l1: f64, l2: f64, l3: f64) -> LineTriplet {
+        LineTriplet {
+            l1: Some(l1),
+            l2: Some(l2),
+            l3: Some(l3),
+        }
+    }
+
+    #[tokio::test]
+    async fn uses_smoothed_measured_voltages_with_nominal_fallback() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
+        // Nothing measured yet
+        assert_eq!(d.watts_per_amp(3), 3.0 * 230.0);
+
+        d.update_voltage_ema(&meas(215.0, 216.0, 214.0));
+        assert_eq!(d.charging_voltages(3), vec![215.0, 216.0, 214.0]);
+        assert_eq!(d.watts_per_amp(1), 215.0);
+        // EMA with alpha 0.2: 215 -> 216 after a 220 V sample
+        d.update_voltage_ema(&meas(220.0, 216.0, 0.0));
+        assert!((d.charging_voltages(1)[0] - 216.0).abs() < 1e-3);
+        // Implausible L3 falls back to nominal
+        assert_eq!(d.charging_voltages(3)[2], 230.0);
+
+        // 120/208 V: single phase between two legs, no L-L measurement
+        let mut cfg = d.config().clone();
+        cfg.controls.nominal_voltage = 208.0;
+        cfg.controls.line_to_line = true;
+        d.update_config(cfg).unwrap();
+        assert_eq!(d.watts_per_amp(1), 208.0);
+        assert!((d.charging_voltages(3)[2] - 120.09).abs() < 0.01);
+    }
+}
@@ -0,0 +1,282 @@
+//! Modbus poll scheduling and read coalescing
+
+use crate::config::SchedulerConfig;
+use crate::driver::modbus_like::ModbusLike;
+use tokio::time::{Duration, Instant};
+
+/// Largest register count of a single read request
+pub const MAX_READ_REGISTERS: u16 = 125;
+
+/// Register groups in priority order (highest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PollGroup {
+    /// Voltages, currents, power, energy and the meter header
+    Measurements,
+    /// Mode 3 status and socket availability
+    Status,
+    /// Setpoint validity timers or SCN status
+    Validity,
+    /// Station max current
+    StationMax,
+    /// Board temperature
+    Temperature,
+    /// Manufacturer, firmware and serial
+    Identity,
+}
+
+impl PollGroup {
+    pub const ALL: [Self; 6] = [
+        Self::Measurements,
+        Self::Status,
+        Self::Validity,
+        Self::StationMax,
+        Self::Temperature,
+        Self::Identity,
+    ];
+
+    /// Whether the group feeds the setpoint decision and runs before the write
+    pub const fn before_write(self) -> bool {
+        matches!(self, Self::Measurements | Self::Status | Self::Validity)
+    }
+}
+
+/// Tracks when each register group was last read
+#[derive(Debug, Clone)]
+pub struct PollScheduler {
+    intervals: [Duration; 6],
+    last_run: [Option<Instant>; 6],
+}
+
+impl PollScheduler {
+    pub fn new(config: &SchedulerConfig) -> Self {
+        let mut s = Self {
+            intervals: [Duration::ZERO; 6],
+            last_run: [None; 6],
+        };
+        s.set_config(config);
+        s
+    }
+
+    /// Apply new intervals, keeping the last run times
+    pub fn set_config(&mut self, config: &SchedulerConfig) {
+        let ms = Duration::from_millis;
+        self.intervals = [
+            Duration::ZERO,
+            ms(config.status_interval_ms),
+            ms(config.validity_interval_ms),
+            ms(config.station_max_interval_ms),
+            ms(config.temperature_interval_ms),
+            ms(config.identity_interval_ms),
+        ];
+    }
+
+    pub fn is_due(&self, group: PollGroup, now: Instant) -> bool {
+        let i = group as usize;
+        self.last_run[i].is_none_or(|t| now.saturating_duration_since(t) >= self.intervals[i])
+    }
+
+    pub fn mark_run(&mut self, group: PollGroup, now: Instant) {
+        self.last_run[group as usize] = Some(now);
+    }
+
+    /// How long past its interval a due group is; `None` when not due and
+    /// `Duration::MAX` when it never ran
+    pub fn overdue(&self, group: PollGroup, now: Instant) -> Option<Duration> {
+        let i = group as usize;
+        match self.last_run[i] {
+            None => Some(Duration::MAX),
+            Some(t) => now
+                .saturating_duration_since(t)
+                .checked_sub(self.intervals[i]),
+        }
+    }
+
+    /// The due group among `groups` that is furthest past its interval
+    pub fn most_overdue(
+        &self,
+        groups: impl IntoIterator<Item = PollGroup>,
+        now: Instant,
+    ) -> Option<PollGroup> {
+        groups
+            .into_iter()
+            .filter_map(|g| Some((self.overdue(g, now)?, g)))
+            .max_by_key(|(overdue, g)| (*overdue, std::cmp::Reverse(*g)))
+            .map(|(_, g)| g)
+    }
+
+    /// Due groups in priority order
+    pub fn due(&self, now: Instant) -> Vec<PollGroup> {
+        PollGroup::ALL
+            .into_iter()
+            .filter(|g| self.is_due(*g, now))
+            .collect()
+    }
+
+    /// Make every group due, e.g. after a reconnect
+    pub fn reset(&mut self) {
+        self.last_run = [None; 6];
+    }
+}
+
+/// A register range to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadRange {
+    pub slave: u8,
+    pub address: u16,
+    pub count: u16,
+}
+
+impl ReadRange {
+    pub const fn new(slave: u8, address: u16, count: u16) -> Self {
+        Self {
+            slave,
+            address,
+            count,
+        }
+    }
+
+    const fn end(&self) -> u32 {
+        self.address as u32 + self.count as u32
+    }
+}
+
+/// Merge ranges of the same slave separated by at most `max_gap` registers
+/// into requests of at most [`MAX_READ_REGISTERS`]; each request comes with
+/// the indices of the ranges it covers
+pub fn coalesce(ranges: &[ReadRange], max_gap: u16) -> Vec<(ReadRange, Vec<usize>)> {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by_key(|&i| (ranges[i].slave, ranges[i].address));
+    let mut out: Vec<(ReadRange, Vec<usize>)> = Vec::new();
+    for i in order {
+        let r = ranges[i];
+        if let Some((req, members)) = out.last_mut() {
+            let end = req.end().max(r.end());
+            if req.slave == r.slave
+                && u32::from(r.address) <= req.end() + u32::from(max_gap)
+                && end - u32::from(req.address) <= u32::from(MAX_READ_REGISTERS)
+            {
+                req.count = (end - u32::from(req.address)) as u16;
+                members.push(i);
+                continue;
+            }
+        }
+        out.push((r, vec![i]));
+    }
+    out
+}
+
+/// Read `ranges` with as few requests as possible; `None` for a range that
+/// could not be read in full
+pub async fn read_coalesced(
+    modbus: &mut dyn ModbusLike,
+    ranges: &[ReadRange],
+    max_gap: u16,
+) -> Vec<Option<Vec<u16>>> {
+    let mut out = vec![None; ranges.len()];
+    for (req, members) in coalesce(ranges, max_gap) {
+        let combined = if members.len() > 1 {
+            read_full(modbus, req).await
+        } else {
+            None
+        };
+        for i in members {
+            let r = ranges[i];
+            out[i] = match &combined {
+                Some(regs) => {
+                    let off = usize::from(r.address - req.address);
+                    Some(regs[off..off + usize::from(r.count)].to_vec())
+                }
+                None => read_full(modbus, r).await,
+            };
+        }
+    }
+    out
+}
+
+async fn read_full(modbus: &mut dyn ModbusLike, r: ReadRange) -> Option<Vec<u16>> {
+    modbus
+        .read_holding_registers(r.slave, r.address, r.count)
+        .await
+        .ok()
+        .filter(|regs| regs.len() >= usize::from(r.count))
+        .map(|mut regs| {
+            regs.truncate(usize::from(r.count));
+            regs
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesces_close_ranges_per_slave() {
+        let ranges = [
+            ReadRange::new(1, 306, 6),
+            ReadRange::new(1, 300, 6),
+            ReadRange::new(1, 320, 6),
+            ReadRange::new(1, 338, 8),
+            ReadRange::new(1, 374, 4),
+            ReadRange::new(1, 1201, 5),
+            ReadRange::new(200, 1100, 2),
+        ];
+        let reqs = coalesce(&ranges, 32);
+        assert_eq!(reqs.len(), 3);
+        assert_eq!(reqs[0], (ReadRange::new(1, 300, 78), vec![1, 0, 2, 3, 4]));
+        assert_eq!(reqs[1].0, ReadRange::new(1, 1201, 5));
+        assert_eq!(reqs[2].0, ReadRange::new(200, 1100, 2));
+        // A small gap limit splits the block (gaps of 0, 8, 12 and 28)
+        assert_eq!(coalesce(&ranges, 8).len(), 5);
+        // Requests never exceed the Modbus limit
+        let wide = [ReadRange::new(1, 0, 100), ReadRange::new(1, 100, 50)];
+        assert_eq!(coalesce(&wide, 0).len(), 2);
+    }
+
+    #[test]
+    fn schedules_groups_by_interval() {
+        let cfg = SchedulerConfig {
+            temperature_interval_ms: 10_000,
+            ..SchedulerConfig::default()
+        };
+        let mut s = PollScheduler::new(&cfg);
+        let t0 = Instant::now();
+        assert_eq!(s.due(t0), PollGroup::ALL.to_vec());
+        for g in PollGroup::ALL {
+            s.mark_run(g, t0);
+        }
+        let t1 = t0 + Duration::from_secs(1);
+        assert!(s.is_due(PollGroup::Measurements, t1));
+        assert!(s.is_due(PollGroup::Status, t1));
+        assert!(!s.is_due(PollGroup::Temperature, t1));
+        assert!(s.is_due(PollGroup::Temperature, t0 + Duration::from_secs(10)));
+        s.reset();
+        assert!(s.is_due(PollGroup::Identity, t1));
+    }
+
+    #[test]
+    fn picks_most_overdue_group() {
+        let cfg = SchedulerConfig {
+            station_max_interval_ms: 1_000,
+            temperature_interval_ms: 10_000,
+            identity_interval_ms: 60_000,
+            ..SchedulerConfig::default()
+        };
+        let mut s = PollScheduler::new(&cfg);
+        let t0 = Instant::now();
+        let slow = [
+            PollGroup::StationMax,
+            PollGroup::Temperature,
+            PollGroup::Identity,
+        ];
+        // Never run: the highest priority group first
+        assert_eq!(s.most_overdue(slow, t0), Some(PollGroup::StationMax));
+        for g in slow {
+            s.mark_run(g, t0);
+        }
+        assert_eq!(s.most_overdue(slow, t0), None);
+        // Station max keeps running; temperature falls further behind
+        let t1 = t0 + Duration::from_secs(30);
+        s.mark_run(PollGroup::StationMax, t1 - Duration::from_secs(2));
+        assert_eq!(s.most_overdue(slow, t1), Some(PollGroup::Temperature));
+    }
+}
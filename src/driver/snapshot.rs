@@ -5,9 +5,15 @@ impl super::AlfenDriver {
         if self.applied_phases >= 3 { 3 } else { 1 }
     }
 
-    fn compute_ac_current_for_snapshot(&self) -> f64 {
-        self.last_l1_current
-            .max(self.last_l2_current.max(self.last_l3_current))
+    fn compute_ac_current_for_snapshot(&self) -> Option<f64> {
+        [
+            self.last_l1_current,
+            self.last_l2_current,
+            self.last_l3_current,
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(f64::max)
     }
 
     fn compute_pricing_currency_for_snapshot(&self) -> Option<String> {
@@ -91,6 +97,7 @@ impl super::AlfenDriver {
             applied_current: self.last_sent_current,
             station_max_current: self.get_station_max_current(),
             device_instance: self.config().device_instance,
+            socket: self.socket,
             product_name: self.product_name.clone(),
             firmware: self.firmware_version.clone(),
             serial: self.serial.clone(),
@@ -127,6 +134,25 @@ impl super::AlfenDriver {
                 super::types::DriverState::ShuttingDown => "ShuttingDown".to_string(),
             },
             poll_steps_ms: self.last_poll_steps.clone(),
+            scn: self.scn_status.clone(),
+            setpoint_accepted: self.setpoint_accepted,
+            actual_applied_current: self.actual_applied_current,
+            validity: self.validity.clone(),
+            meter: self.meter_telemetry.clone(),
+            meter_ok: self.meter_ok(),
+            meter_age_ms: self.meter_age_ms,
+            meter_health: self.meter_health.clone(),
+            thermal: self.thermal.clone(),
+            station_operative: self.station_operative,
+            backoffice_connected: self.backoffice_connected,
+            conditions: self.active_conditions(),
+            mode3: self.mode3.clone(),
+            clock: self.clock.clone(),
+            modbus_stats: self
+                .modbus_manager
+                .as_ref()
+                .and_then(|m| m.connection_stats()),
+            main_fuse: self.main_fuse.clone(),
         }
     }
 }
@@ -141,17 +167,17 @@ mod tests {
         let mut d = crate::driver::AlfenDriver::new(rx, tx).await.unwrap();
 
         // Seed some last measurements
-        d.last_l1_voltage = 230.0;
-        d.last_l2_voltage = 231.0;
-        d.last_l3_voltage = 229.0;
-        d.last_l1_current = 5.0;
-        d.last_l2_current = 6.0;
-        d.last_l3_current = 7.0;
-        d.last_l1_power = 1100.0;
-        d.last_l2_power = 1200.0;
-        d.last_l3_power = 1300.0;
-        d.last_total_power = 3600.0;
-        d.last_energy_kwh = 12.345;
+        d.last_l1_voltage = Some(230.0);
+        d.last_l2_voltage = Some(231.0);
+        d.last_l3_voltage = Some(229.0);
+        d.last_l1_current = Some(5.0);
+        d.last_l2_current = Some(6.0);
+        d.last_l3_current = Some(7.0);
+        d.last_l1_power = Some(1100.0);
+        d.last_l2_power = Some(1200.0);
+        d.last_l3_power = Some(1300.0);
+        d.last_total_power = Some(3600.0);
+        d.last_energy_kwh = Some(12.345);
         d.last_sent_current = 6.5;
         d.product_name = Some("Alfen EV Charger".to_string());
         d.firmware_version = Some("1.2.3".to_string());
@@ -160,7 +186,8 @@ mod tests {
         let snap = d.build_typed_snapshot(Some(10));
         assert_eq!(snap.device_instance, d.config().device_instance);
         assert_eq!(snap.station_max_current, d.get_station_max_current());
-        assert!((snap.ac_power - 3600.0).abs() < 0.001);
+        assert_eq!(snap.ac_power, Some(3600.0));
+        assert_eq!(snap.ac_current, Some(7.0));
         assert!(snap.active_phases >= 1);
         assert_eq!(snap.poll_duration_ms, Some(10));
         assert_eq!(snap.product_name, Some("Alfen EV Charger".to_string()));
@@ -0,0 +1,185 @@
+//! Multi-socket (twin) station support
+//!
+//! Alfen twin stations expose each socket as its own Modbus slave. Phaeton
+//! runs one driver per socket, each with its own control loop, session
+//! manager, persistence file and D-Bus service, while all drivers share a
+//! single Modbus TCP connection to the station.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::modbus_like::{ModbusLike, SharedModbus};
+use crate::config::Config;
+use crate::error::Result;
+
+/// Upper bound on sockets supported by Alfen NG9xx stations
+pub const MAX_SOCKETS: u8 = 2;
+
+/// Persistence file for a socket; socket 1 keeps the historical path
+pub fn state_file_for_socket(socket: u8) -> String {
+    if socket <= 1 {
+        "/data/phaeton_state.json".to_string()
+    } else {
+        format!("/data/phaeton_state_socket{}.json", socket)
+    }
+}
+
+/// Derive the configuration of socket `socket` (1-based) from the base config.
+///
+/// The base config describes socket 1; socket N uses Modbus slave
+/// `socket_slave_id + N - 1` and D-Bus device instance `device_instance + N - 1`.
+pub fn config_for_socket(base: &Config, socket: u8) -> Config {
+    let offset = socket.saturating_sub(1);
+    let mut cfg = base.clone();
+    cfg.modbus.socket_slave_id = base.modbus.socket_slave_id.saturating_add(offset);
+    cfg.device_instance = base.device_instance.saturating_add(u32::from(offset));
+    cfg
+}
+
+/// Read the number of sockets (register 1105) from the station slave.
+///
+/// Falls back to a single socket when the register cannot be read.
+pub async fn detect_socket_count(modbus: &mut dyn ModbusLike, config: &Config) -> u8 {
+    let logger = crate::logging::get_logger("driver");
+    let read = tokio::time::timeout(
+        Duration::from_secs(10),
+        modbus.read_holding_registers(
+            config.modbus.station_slave_id,
+            config.registers.nr_of_sockets,
+            1,
+        ),
+    )
+    .await;
+    match read {
+        Ok(Ok(regs)) if !regs.is_empty() => {
+            let count = regs[0].clamp(1, u16::from(MAX_SOCKETS)) as u8;
+            logger.info(&format!("Station reports {} socket(s)", regs[0]));
+            count
+        }
+        Ok(Ok(_)) => {
+            logger.warn("Empty response reading number of sockets; assuming 1");
+            1
+        }
+        Ok(Err(e)) => {
+            logger.warn(&format!(
+                "Failed to read number of sockets: {}; assuming 1",
+                e
+            ));
+            1
+        }
+        Err(_) => {
+            logger.warn("Timed out reading number of sockets; assuming 1");
+            1
+        }
+    }
+}
+
+/// Build one driver per socket sharing a single Modbus connection.
+///
+/// `modbus.sockets` in the config forces the socket count; 0 detects it from
+/// the station. The returned drivers are ordered by socket number.
+pub async fn build_socket_drivers(
+    config_path_override: Option<PathBuf>,
+) -> Result<Vec<super::AlfenDriver>> {
+    let base = Config::load_with_override(config_path_override.as_deref()).map_err(|e| {
+        eprintln!("Failed to load configuration: {}", e);
+        e
+    })?;
+
+    // Socket 1 first: its constructor initializes logging
+    let (tx, rx) = mpsc::unbounded_channel();
+    let first = super::AlfenDriver::new_for_socket(rx, tx, base.clone(), 1).await?;
+
+    let manager = crate::modbus::ModbusConnectionManager::new(
+        &base.modbus,
+        Duration::from_secs_f64(base.controls.retry_delay),
+    );
+    let sink = super::capture::CaptureSink::maybe_open(&base.modbus.capture_file)?;
+    let manager = super::capture::maybe_record(Box::new(manager), sink.as_ref());
+    let mut shared = SharedModbus::new(manager);
+
+    let count = if base.modbus.sockets > 0 {
+        base.modbus.sockets.min(MAX_SOCKETS)
+    } else if !base.charger.backend.eq_ignore_ascii_case("alfen") {
+        // Register 1105 is Alfen-specific
+        1
+    } else {
+        detect_socket_count(&mut shared, &base).await
+    };
+
+    let mut drivers = vec![first];
+    for socket in 2..=count {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let cfg = config_for_socket(&base, socket);
+        drivers.push(super::AlfenDriver::new_for_socket(rx, tx, cfg, socket).await?);
+    }
+    for driver in drivers.iter_mut() {
+        driver.attach_modbus(Box::new(shared.clone()));
+        driver.dbus_capture = sink.clone().map(super::capture::DbusCapture::Record);
+    }
+    Ok(drivers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedModbus(Option<u16>);
+
+    #[async_trait::async_trait]
+    impl ModbusLike for FixedModbus {
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+        async fn read_holding_registers(
+            &mut self,
+            slave_id: u8,
+            address: u16,
+            _count: u16,
+        ) -> Result<Vec<u16>> {
+            assert_eq!((slave_id, address), (200, 1105));
+            self.0
+                .map(|v| vec![v])
+                .ok_or_else(|| crate::error::PhaetonError::modbus("no response"))
+        }
+        async fn write_multiple_registers(
+            &mut self,
+            _slave_id: u8,
+            _address: u16,
+            _values: &[u16],
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn socket_config_offsets_slave_and_instance() {
+        let base = Config::default();
+        let cfg = config_for_socket(&base, 2);
+        assert_eq!(cfg.modbus.socket_slave_id, base.modbus.socket_slave_id + 1);
+        assert_eq!(cfg.device_instance, base.device_instance + 1);
+        assert_eq!(cfg.modbus.station_slave_id, base.modbus.station_slave_id);
+        assert_eq!(state_file_for_socket(1), "/data/phaeton_state.json");
+        assert_eq!(state_file_for_socket(2), "/data/phaeton_state_socket2.json");
+    }
+
+    #[tokio::test]
+    async fn detects_socket_count_with_fallback() {
+        let cfg = Config::default();
+        assert_eq!(
+            detect_socket_count(&mut FixedModbus(Some(2)), &cfg).await,
+            2
+        );
+        assert_eq!(
+            detect_socket_count(&mut FixedModbus(Some(7)), &cfg).await,
+            2
+        );
+        assert_eq!(
+            detect_socket_count(&mut FixedModbus(Some(0)), &cfg).await,
+            1
+        );
+        assert_eq!(detect_socket_count(&mut FixedModbus(None), &cfg).await, 1);
+    }
+}
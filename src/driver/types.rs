@@ -28,6 +28,21 @@ pub struct PollStepDurations {
     pub read_status_ms: Option<u64>,
     /// Modbus read: station max current
     pub read_station_max_ms: Option<u64>,
+    /// Modbus read: SCN registers (SCN current control only)
+    #[serde(default)]
+    pub read_scn_ms: Option<u64>,
+    /// Modbus read: setpoint validity and safe current
+    #[serde(default)]
+    pub read_validity_ms: Option<u64>,
+    /// Modbus read: full meter block (300..425) in two requests
+    #[serde(default)]
+    pub read_meter_ms: Option<u64>,
+    /// Modbus read: board temperature (station slave)
+    #[serde(default)]
+    pub read_temperature_ms: Option<u64>,
+    /// Modbus read: availability and OCPP state
+    #[serde(default)]
+    pub read_availability_ms: Option<u64>,
     /// D-Bus: compute PV excess (multiple reads under the hood)
     pub pv_excess_ms: Option<u64>,
     /// Compute effective current including SoC checks and grace logic
@@ -40,6 +55,140 @@ pub struct PollStepDurations {
     pub snapshot_build_ms: Option<u64>,
 }
 
+/// Smart Charging Network state read from the station slave (L1, L2, L3)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScnStatus {
+    /// Whether the station accepts the Modbus SCN max currents (register 1431)
+    pub max_current_enabled: bool,
+    /// Total consumption of the SCN per phase (A)
+    pub total_consumption: [f32; 3],
+    /// Max current per phase the SCN currently applies (A)
+    pub actual_max_current: [f32; 3],
+    /// Max current per phase as last written via Modbus (A)
+    pub max_current: [f32; 3],
+    /// Whether the station holds the SCN setpoint written last; None
+    /// before the first write
+    #[serde(default)]
+    pub limit_accepted: Option<bool>,
+}
+
+/// Extended socket meter values from the 312..425 register block.
+/// Per-phase arrays are ordered L1, L2, L3; energies are in kWh/kVAh/kvarh.
+/// Values the station reports as unavailable are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeterTelemetry {
+    /// Line-to-line voltages L1-L2, L2-L3, L3-L1 (V)
+    pub voltage_ll: [Option<f64>; 3],
+    /// Neutral current (A)
+    pub current_n: Option<f64>,
+    /// Sum of the phase currents (A)
+    pub current_sum: Option<f64>,
+    pub power_factor: [Option<f64>; 3],
+    pub power_factor_total: Option<f64>,
+    /// Grid frequency (Hz)
+    pub frequency: Option<f64>,
+    /// Apparent power (VA)
+    pub apparent_power: [Option<f64>; 3],
+    pub apparent_power_total: Option<f64>,
+    /// Reactive power (var)
+    pub reactive_power: [Option<f64>; 3],
+    pub reactive_power_total: Option<f64>,
+    pub energy_delivered_kwh: [Option<f64>; 3],
+    pub energy_delivered_total_kwh: Option<f64>,
+    pub energy_consumed_kwh: [Option<f64>; 3],
+    pub energy_consumed_total_kwh: Option<f64>,
+    pub apparent_energy_kvah: [Option<f64>; 3],
+    pub apparent_energy_total_kvah: Option<f64>,
+    pub reactive_energy_kvarh: [Option<f64>; 3],
+    pub reactive_energy_total_kvarh: Option<f64>,
+}
+
+/// Station board temperature and thermal derating state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermalStatus {
+    /// Board temperature (register 1102, °C); None when unavailable
+    pub temperature_c: Option<f32>,
+    /// "normal", "warning", "derating" or "stopped"
+    pub state: String,
+    /// Factor applied to the effective current (1.0 = no derating)
+    pub derate_factor: f32,
+}
+
+/// Main fuse protection state (`controls.main_fuse_a`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MainFuseStatus {
+    /// Grid current per installation phase (A, positive = import); None
+    /// when unavailable
+    pub grid_current_a: [Option<f32>; 3],
+    /// Highest EV current the fuse allows on the phases in use (A); None
+    /// without grid readings
+    pub ev_limit_a: Option<f32>,
+    /// Whether the fuse limit reduced the setpoint this cycle
+    pub limiting: bool,
+    /// Whether charging is stopped until the headroom has recovered for
+    /// `controls.main_fuse_hold_s`
+    #[serde(default)]
+    pub stopped: bool,
+}
+
+/// Station clock, uptime and timezone checks (registers 168..178)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StationClock {
+    /// Station local time as reported (RFC 3339 with the station's offset)
+    pub station_time: Option<String>,
+    /// Station uptime in seconds
+    pub uptime_s: Option<u64>,
+    /// Station clock minus host clock (seconds)
+    pub drift_s: Option<i64>,
+    /// Timezone offset configured on the station (minutes)
+    pub timezone_offset_minutes: Option<i16>,
+    /// Offset of the configured `timezone` at the time of the check (minutes)
+    pub expected_offset_minutes: Option<i32>,
+    /// Whether the station offset differs from the configured timezone
+    pub timezone_mismatch: bool,
+    /// Number of station reboots detected since the driver started
+    pub reboots: u32,
+    /// Time of the last detected reboot (RFC 3339)
+    pub last_reboot_at: Option<String>,
+    /// Time of the last successful clock read (RFC 3339)
+    pub checked_at: Option<String>,
+}
+
+/// IEC 61851 Mode 3 state (register 1201) and its recent transitions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mode3Status {
+    /// Raw state as reported by the station, e.g. "B1" or "C2"
+    pub state: String,
+    /// Human-readable meaning of the state
+    pub description: String,
+    /// Whether the vehicle charged since it was plugged in
+    pub charged: bool,
+    /// When the current state was entered (RFC 3339)
+    pub since: Option<String>,
+    /// Most recent transitions, oldest first
+    pub transitions: Vec<Mode3Transition>,
+}
+
+/// A single Mode 3 state change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mode3Transition {
+    pub from: String,
+    pub to: String,
+    /// RFC 3339 timestamp of the change
+    pub at: String,
+}
+
+/// Socket meter health decoded from registers 300 and 305
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeterHealth {
+    /// Raw meter state bitmask (Initialised 0x01, Updated 0x02, Warning 0x04, Error 0x08)
+    pub state: u16,
+    /// Meter connection type: "rtu", "tcp", "udp", "p1" or "other"
+    pub meter_type: String,
+    /// Summary: "ok", "warning", "error" or "stale"
+    pub status: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DriverSnapshot {
     pub timestamp: String,
@@ -49,23 +198,27 @@ pub struct DriverSnapshot {
     pub applied_current: f32,
     pub station_max_current: f32,
     pub device_instance: u32,
+    /// Socket number (1-based) on the station
+    #[serde(default)]
+    pub socket: u8,
     pub product_name: Option<String>,
     pub firmware: Option<String>,
     pub serial: Option<String>,
     pub status: u32,
     pub active_phases: u8,
-    pub ac_power: f64,
-    pub ac_current: f64,
-    pub l1_voltage: f64,
-    pub l2_voltage: f64,
-    pub l3_voltage: f64,
-    pub l1_current: f64,
-    pub l2_current: f64,
-    pub l3_current: f64,
-    pub l1_power: f64,
-    pub l2_power: f64,
-    pub l3_power: f64,
-    pub total_energy_kwh: f64,
+    /// Measurements are `null` when the station reports them as unavailable
+    pub ac_power: Option<f64>,
+    pub ac_current: Option<f64>,
+    pub l1_voltage: Option<f64>,
+    pub l2_voltage: Option<f64>,
+    pub l3_voltage: Option<f64>,
+    pub l1_current: Option<f64>,
+    pub l2_current: Option<f64>,
+    pub l3_current: Option<f64>,
+    pub l1_power: Option<f64>,
+    pub l2_power: Option<f64>,
+    pub l3_power: Option<f64>,
+    pub total_energy_kwh: Option<f64>,
     pub pricing_currency: Option<String>,
     pub energy_rate: Option<f64>,
     pub session: serde_json::Value,
@@ -80,6 +233,86 @@ pub struct DriverSnapshot {
     pub driver_state: String,
     /// Optional per-step timings of the last poll cycle
     pub poll_steps_ms: Option<PollStepDurations>,
+    /// SCN state when current control uses the SCN registers
+    #[serde(default)]
+    pub scn: Option<ScnStatus>,
+    /// Whether the station accepted the last setpoint (None until verified).
+    /// `false` means "setpoint not accepted" after exhausting retries.
+    #[serde(default)]
+    pub setpoint_accepted: Option<bool>,
+    /// Actual Applied Max Current read back after the last write (A)
+    #[serde(default)]
+    pub actual_applied_current: Option<f32>,
+    /// Setpoint validity and safe-current fallback state (socket control only)
+    #[serde(default)]
+    pub validity: Option<ValidityStatus>,
+    /// Full meter telemetry (only with `registers.full_meter_telemetry`)
+    #[serde(default)]
+    pub meter: Option<MeterTelemetry>,
+    /// Whether the socket meter delivers fresh, error-free values (None if unknown)
+    #[serde(default)]
+    pub meter_ok: Option<bool>,
+    /// Milliseconds since the meter's last received measurement
+    #[serde(default)]
+    pub meter_age_ms: Option<u64>,
+    #[serde(default)]
+    pub meter_health: Option<MeterHealth>,
+    /// Board temperature and thermal derating state
+    #[serde(default)]
+    pub thermal: Option<ThermalStatus>,
+    /// Socket availability (register 1200); None when unknown
+    #[serde(default)]
+    pub station_operative: Option<bool>,
+    /// OCPP back office connection (register 1104); None when unknown
+    #[serde(default)]
+    pub backoffice_connected: Option<bool>,
+    /// Active abnormal conditions, e.g. "station_inoperative",
+    /// "backoffice_disconnected"
+    #[serde(default)]
+    pub conditions: Vec<String>,
+    /// Mode 3 state machine with transition history
+    #[serde(default)]
+    pub mode3: Option<Mode3Status>,
+    /// Station clock, uptime and drift
+    #[serde(default)]
+    pub clock: Option<StationClock>,
+    /// Modbus connection statistics (reconnects, last error, time connected)
+    #[serde(default)]
+    pub modbus_stats: Option<crate::modbus::ConnectionStats>,
+    /// Main fuse protection; None when disabled
+    #[serde(default)]
+    pub main_fuse: Option<MainFuseStatus>,
+}
+
+/// Setpoint validity and safe-current state of the socket (registers 1208/1212)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ValidityStatus {
+    /// Remaining validity of the Modbus max current (s); None when unreadable
+    pub remaining_s: Option<u32>,
+    /// Longest remaining validity observed, i.e. the configured validity time (s)
+    pub validity_period_s: u32,
+    /// Safe current the station falls back to when validity expires (A)
+    pub safe_current: Option<f32>,
+    /// Whether the station currently runs on the safe current
+    pub fallback_active: bool,
+    /// No validity countdown (remaining 0 while the setpoint is accounted
+    /// for): the station's validity time is disabled
+    #[serde(default)]
+    pub disabled: bool,
+    /// Number of fallbacks to safe current observed since start
+    pub fallback_events: u64,
+    /// Time of the last fallback (RFC 3339)
+    pub last_fallback_at: Option<String>,
+}
+
+/// Pending re-write of a setpoint the station did not accept
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SetpointRetry {
+    /// Consecutive rejected writes, whatever their target
+    pub attempts: u32,
+    /// Earliest time for the next attempt; `None` once it was written,
+    /// until its readback is verified
+    pub next_at: Option<tokio::time::Instant>,
 }
 
 /// Commands accepted by the driver from external components (web, etc.)
@@ -0,0 +1,52 @@
+//! Wall clock of the control loop
+//!
+//! Control timers use the Tokio clock (`tokio::time::Instant`). Decisions
+//! depending on the time of day (schedules, the station clock check) read
+//! [`WallClock::now`] instead of `Utc::now`, so a capture replay with the
+//! Tokio clock paused sees the wall time of the original run.
+
+use chrono::{DateTime, Utc};
+use tokio::time::Instant;
+
+/// System time, or a fixed start advancing with the Tokio clock
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct WallClock {
+    origin: Option<(DateTime<Utc>, Instant)>,
+}
+
+impl WallClock {
+    /// Clock reading `start` now
+    pub(crate) fn starting_at(start: DateTime<Utc>) -> Self {
+        Self {
+            origin: Some((start, Instant::now())),
+        }
+    }
+
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        match self.origin {
+            Some((start, at)) => {
+                start + chrono::Duration::from_std(at.elapsed()).unwrap_or_default()
+            }
+            None => Utc::now(),
+        }
+    }
+
+    /// Seconds since the Unix epoch
+    pub(crate) fn unix_secs(&self) -> f64 {
+        self.now().timestamp_millis() as f64 / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn advances_with_the_tokio_clock() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = WallClock::starting_at(start);
+        tokio::time::advance(std::time::Duration::from_secs(90)).await;
+        assert_eq!(clock.now(), start + chrono::Duration::seconds(90));
+        assert_eq!(clock.unix_secs(), 1_700_000_090.0);
+    }
+}
@@ -24,25 +24,33 @@
 //! - `config`: Configuration management and validation
 //! - `logging`: Structured logging and tracing
 //! - `modbus`: Modbus TCP client for charger communication
+//! - `backend`: Charger register backends (Alfen, YAML profiles)
+//! - `discovery`: mDNS discovery of stations on the LAN
 //! - `driver`: Core driver logic and state management
 //! - `dbus`: D-Bus integration for Venus OS
 //! - `web`: HTTP server and REST API
 //! - `persistence`: State persistence and recovery
 //! - `session`: Charging session management
+//! - `simulator`: Alfen Modbus TCP station simulator (`phaeton-sim`)
 //! - `controls`: Charging control algorithms
 //! - `tibber`: Dynamic pricing integration
 //! - `vehicle`: Vehicle API integrations
 //! - `updater`: Self-update functionality
+//! - `cli`: `phaeton modbus` register inspector subcommands
 
+pub mod backend;
+pub mod cli;
 pub mod config;
 pub mod controls;
 pub mod dbus;
+pub mod discovery;
 pub mod driver;
 pub mod error;
 pub mod logging;
 pub mod modbus;
 pub mod persistence;
 pub mod session;
+pub mod simulator;
 pub mod tibber;
 pub mod updater;
 pub mod vehicle;
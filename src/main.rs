@@ -1,9 +1,9 @@
 use anyhow::Result;
-use phaeton::driver::{AlfenDriver, DriverCommand};
+use phaeton::driver::AlfenDriver;
 use phaeton::web;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::Mutex;
 use tracing::warn;
 use tracing::{error, info};
 
@@ -15,7 +15,7 @@ async fn main() -> Result<()> {
     while let Some(arg) = args.next() {
         if arg == "--help" || arg == "-h" {
             println!(
-                "Usage: phaeton [--config <path>]\n\n  --config, -c <path>  Path to YAML config file (no fallback)\n  --help, -h           Show this help"
+                "Usage: phaeton [--config <path>] [--discover]\n       phaeton [--config <path>] modbus read|write ...\n\n  --config, -c <path>  Path to YAML config file (no fallback)\n  --discover           List Alfen stations found via mDNS and exit\n  modbus               Read or write registers through the running instance\n                       (see `phaeton modbus --help`)\n  --help, -h           Show this help"
             );
             return Ok(());
         } else if arg == "--config" || arg == "-c" {
@@ -25,6 +25,10 @@ async fn main() -> Result<()> {
                 eprintln!("Error: --config requires a file path\nTry --help for usage.");
                 std::process::exit(2);
             }
+        } else if arg == "--discover" {
+            return discover_stations().await;
+        } else if arg == "modbus" {
+            return modbus_command(args.collect(), config_path_override).await;
         } else if let Some(v) = arg.strip_prefix("--config=") {
             config_path_override = Some(PathBuf::from(v));
         } else {
@@ -32,25 +36,41 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Create driver command channel
-    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel::<DriverCommand>();
+    // One driver per socket, sharing a single Modbus connection
+    let drivers = phaeton::driver::sockets::build_socket_drivers(config_path_override)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create driver: {}", e))?;
 
-    // Initialize the driver with optional config override
-    let driver =
-        AlfenDriver::new_with_config_override(cmd_rx, cmd_tx.clone(), config_path_override)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to create driver: {}", e))?;
+    info!(
+        "Phaeton EV Charger Driver starting up ({} socket(s))",
+        drivers.len()
+    );
 
-    info!("Phaeton EV Charger Driver starting up");
+    // Capture web bind settings before placing drivers behind a Mutex
+    let (web_host, web_port) = {
+        let cfg = drivers[0].config();
+        (cfg.web.host.clone(), cfg.web.port)
+    };
 
-    // Capture web bind settings before placing driver behind a Mutex
-    let (web_host, web_port) = (driver.config().web.host.clone(), driver.config().web.port);
+    // Share drivers with web server
+    let driver_arcs: Vec<Arc<Mutex<AlfenDriver>>> = drivers
+        .into_iter()
+        .map(|d| Arc::new(Mutex::new(d)))
+        .collect();
 
-    // Share driver with web server
-    let driver_arc = Arc::new(Mutex::new(driver));
+    // Shut down on SIGINT/SIGTERM so the Modbus connection is closed and the
+    // station frees its master slot
+    let signal_drivers = driver_arcs.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!("Shutdown requested");
+        for d in &signal_drivers {
+            d.lock().await.request_shutdown();
+        }
+    });
 
     // Spawn Axum server (API + OpenAPI)
-    let axum_driver = driver_arc.clone();
+    let axum_drivers = driver_arcs.clone();
     let axum_task = tokio::spawn(async move {
         // Log the selected host/port before starting web server
         {
@@ -61,23 +81,101 @@ async fn main() -> Result<()> {
             );
             logger.info(&msg);
         }
-        if let Err(e) = web::serve(axum_driver.clone(), &web_host, web_port).await {
+        if let Err(e) = web::serve(axum_drivers, &web_host, web_port).await {
             error!("Axum server error: {}", e);
         }
     });
 
-    // Run the driver loop without holding the mutex for the entire duration
-    match phaeton::driver::AlfenDriver::run_on_arc(driver_arc.clone()).await {
-        Ok(_) => {
+    // Run every socket's driver loop without holding the mutex for the entire duration.
+    // The loops are not `Send`, so they run as local tasks on this thread.
+    let local = tokio::task::LocalSet::new();
+    let results = local
+        .run_until(async {
+            let loops: Vec<_> = driver_arcs
+                .iter()
+                .map(|d| {
+                    tokio::task::spawn_local(phaeton::driver::AlfenDriver::run_on_arc(d.clone()))
+                })
+                .collect();
+            let mut results = Vec::with_capacity(loops.len());
+            for handle in loops {
+                results.push(
+                    handle.await.unwrap_or_else(|e| {
+                        Err(phaeton::error::PhaetonError::generic(e.to_string()))
+                    }),
+                );
+            }
+            results
+        })
+        .await;
+    axum_task.abort();
+    match results.into_iter().find_map(|r| r.err()) {
+        None => {
             info!("Driver shutdown complete");
-            // Ensure web server task ends (it runs until process stops)
-            axum_task.abort();
             Ok(())
         }
-        Err(e) => {
+        Some(e) => {
             error!("Driver failed with error: {}", e);
-            axum_task.abort();
             Err(anyhow::anyhow!("Driver error: {}", e))
         }
     }
 }
+
+/// Wait for SIGINT or SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Run `phaeton modbus read|write` against the running instance
+async fn modbus_command(args: Vec<String>, config_path: Option<PathBuf>) -> Result<()> {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", phaeton::cli::MODBUS_USAGE);
+        return Ok(());
+    }
+    let config = phaeton::Config::load_with_override(config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
+    match phaeton::cli::run_modbus(&args, &config).await {
+        Ok(body) => {
+            println!("{}", body.trim());
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print the stations found via mDNS (`--discover`)
+async fn discover_stations() -> Result<()> {
+    let stations = phaeton::discovery::discover(phaeton::discovery::DEFAULT_BROWSE).await?;
+    if stations.is_empty() {
+        println!("No stations found ({})", phaeton::discovery::SERVICE_TYPE);
+    }
+    for s in stations {
+        println!(
+            "{}\t{}\t{}\t{}",
+            s.ip,
+            s.hostname,
+            s.serial.as_deref().unwrap_or("-"),
+            s.name
+        );
+    }
+    Ok(())
+}
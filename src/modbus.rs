@@ -9,10 +9,18 @@ use crate::error::{PhaetonError, Result};
 use crate::logging::get_logger;
 use std::time::Duration;
 use std::{future::Future, pin::Pin};
-use tokio::time::{sleep, timeout};
+use tokio::time::timeout;
 use tokio_modbus::client::tcp;
 use tokio_modbus::prelude::*;
 
+pub mod inspect;
+mod keepalive;
+mod reconnect;
+mod stats;
+pub use keepalive::KEEPALIVE_REGISTER;
+pub use reconnect::{Backoff, CONNECT_GRACE, LinkState};
+pub use stats::{ConnectionStats, ConnectionTracker, SLOTS_FULL_HINT};
+
 /// Modbus TCP client for EV charger communication
 pub struct ModbusClient {
     /// Modbus TCP client connection
@@ -27,6 +35,9 @@ pub struct ModbusClient {
     /// Operation timeout
     operation_timeout: Duration,
 
+    /// Address `config.ip` resolved to on the last connect
+    resolved: Option<std::net::SocketAddr>,
+
     /// Logger
     logger: crate::logging::StructuredLogger,
 }
@@ -40,49 +51,72 @@ impl ModbusClient {
             config: config.clone(),
             connection_timeout: Duration::from_secs(5),
             operation_timeout: Duration::from_secs(2),
+            resolved: None,
             logger,
         }
     }
 
     /// Connect to the Modbus server
     pub async fn connect(&mut self) -> Result<()> {
-        let address = format!("{}:{}", self.config.ip, self.config.port);
+        let (client, socket_addr) = Self::open(&self.config, self.connection_timeout).await?;
+        self.attach(client, socket_addr);
+        Ok(())
+    }
 
-        self.logger
-            .info(&format!("Connecting to Modbus server at {}", address));
+    /// Resolve `config.ip` and open a TCP connection without touching any
+    /// client, so the connect can run as a background task
+    pub async fn open(
+        config: &ModbusConfig,
+        connection_timeout: Duration,
+    ) -> Result<(tokio_modbus::client::Context, std::net::SocketAddr)> {
+        let logger = get_logger("modbus");
+        logger.info(&format!(
+            "Connecting to Modbus server at {}:{}",
+            config.ip, config.port
+        ));
 
-        let socket_addr: std::net::SocketAddr = address
-            .parse()
-            .map_err(|e| PhaetonError::modbus(format!("Invalid socket address: {}", e)))?;
+        // Hostnames and auto:<serial> are re-resolved on every (re)connect
+        let socket_addr = crate::discovery::resolve_modbus_address(&config.ip, config.port).await?;
 
-        match timeout(self.connection_timeout, tcp::connect(socket_addr)).await {
-            Ok(Ok(client)) => {
-                self.client = Some(client);
-                self.logger.info("Successfully connected to Modbus server");
-                Ok(())
-            }
+        match timeout(connection_timeout, tcp::connect(socket_addr)).await {
+            Ok(Ok(client)) => Ok((client, socket_addr)),
             Ok(Err(e)) => {
                 let error_msg = format!("Failed to connect to Modbus server: {}", e);
-                self.logger.error(&error_msg);
+                logger.error(&error_msg);
                 Err(PhaetonError::modbus(error_msg))
             }
             Err(_) => {
                 let error_msg = "Connection timeout".to_string();
-                self.logger.error(&error_msg);
+                logger.error(&error_msg);
                 Err(PhaetonError::timeout(error_msg))
             }
         }
     }
 
+    /// Use a connection opened with [`ModbusClient::open`]
+    pub fn attach(
+        &mut self,
+        client: tokio_modbus::client::Context,
+        socket_addr: std::net::SocketAddr,
+    ) {
+        if self.resolved != Some(socket_addr) && socket_addr.ip().to_string() != self.config.ip {
+            self.logger
+                .info(&format!("Resolved {} to {}", self.config.ip, socket_addr));
+        }
+        self.resolved = Some(socket_addr);
+        self.client = Some(client);
+        self.logger.info("Successfully connected to Modbus server");
+    }
+
     /// Disconnect from the Modbus server
     pub async fn disconnect(&mut self) -> Result<()> {
-        if let Some(_client) = self.client.take() {
+        if let Some(mut client) = self.client.take() {
             self.logger.info("Disconnecting from Modbus server");
-            // The client will be dropped automatically
-            Ok(())
-        } else {
-            Ok(())
+            // Close the TCP connection explicitly so the station frees the
+            // master slot right away; a broken connection is simply dropped
+            let _ = timeout(self.operation_timeout, client.disconnect()).await;
         }
+        Ok(())
     }
 
     /// Check if connected
@@ -287,6 +321,17 @@ pub fn decode_64bit_float(registers: &[u16]) -> Result<f64> {
     Ok(value)
 }
 
+/// Decode a 32-bit float, or `None` when the station reports the value as
+/// unavailable (reserved registers read as 0xFFFF, i.e. NaN) or it is not finite
+pub fn decode_32bit_float_opt(registers: &[u16]) -> Option<f32> {
+    decode_32bit_float(registers).ok().filter(|v| v.is_finite())
+}
+
+/// Decode a 64-bit float, or `None` when unavailable (see [`decode_32bit_float_opt`])
+pub fn decode_64bit_float_opt(registers: &[u16]) -> Option<f64> {
+    decode_64bit_float(registers).ok().filter(|v| v.is_finite())
+}
+
 /// Decode string from registers
 pub fn decode_string(registers: &[u16], max_length: Option<usize>) -> Result<String> {
     let mut bytes = Vec::new();
@@ -326,109 +371,78 @@ pub fn encode_32bit_float(value: f32) -> [u16; 2] {
     ]
 }
 
-/// Connection manager with automatic reconnection
+/// Encode 64-bit float to four 16-bit registers (big-endian)
+pub fn encode_64bit_float(value: f64) -> [u16; 4] {
+    let bytes = value.to_be_bytes();
+    [
+        ((bytes[0] as u16) << 8) | (bytes[1] as u16),
+        ((bytes[2] as u16) << 8) | (bytes[3] as u16),
+        ((bytes[4] as u16) << 8) | (bytes[5] as u16),
+        ((bytes[6] as u16) << 8) | (bytes[7] as u16),
+    ]
+}
+
+/// Connection manager with automatic, non-blocking reconnection
 pub struct ModbusConnectionManager {
     client: ModbusClient,
-    #[allow(dead_code)]
     config: ModbusConfig,
-    max_retry_attempts: u32,
-    retry_delay: Duration,
+    backoff: Backoff,
+    /// Connect attempt running in the background
+    pending: Option<tokio::task::JoinHandle<reconnect::ConnectResult>>,
+    /// Whether the current connection has answered a request
+    link_confirmed: bool,
+    tracker: ConnectionTracker,
     logger: crate::logging::StructuredLogger,
 }
 
 impl ModbusConnectionManager {
-    /// Create a new connection manager
-    pub fn new(config: &ModbusConfig, max_retry_attempts: u32, retry_delay: Duration) -> Self {
+    /// Create a new connection manager. Reconnect attempts start
+    /// `retry_delay` apart and back off exponentially; after
+    /// `config.open_circuit_after` failures (0 = never) the circuit opens.
+    pub fn new(config: &ModbusConfig, retry_delay: Duration) -> Self {
         let logger = get_logger("modbus_manager");
         Self {
             client: ModbusClient::new(config),
             config: config.clone(),
-            max_retry_attempts,
-            retry_delay,
+            backoff: Backoff::new(
+                retry_delay,
+                Duration::from_secs_f64(config.backoff_max_s.max(0.0)),
+                config.open_circuit_after,
+                Duration::from_secs_f64(config.open_circuit_s.max(0.0)),
+            ),
+            pending: None,
+            link_confirmed: false,
+            tracker: ConnectionTracker::default(),
             logger,
         }
     }
 
-    /// Execute a Modbus operation with automatic reconnection
+    /// Execute a Modbus operation, failing fast while the connection is down.
+    /// A connection error drops the connection; the next request starts a
+    /// reconnect.
     pub async fn execute_with_reconnect<F, T>(&mut self, mut operation: F) -> Result<T>
     where
         for<'a> F:
             FnMut(&'a mut ModbusClient) -> Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>,
     {
-        let mut attempts = 0;
-
-        loop {
-            // Ensure we're connected
-            if !self.client.is_connected()
-                && let Err(e) = self.client.connect().await
-            {
-                attempts += 1;
-                // If max_retry_attempts == 0, retry indefinitely
-                if self.max_retry_attempts > 0 && attempts >= self.max_retry_attempts {
-                    self.logger.error(&format!(
-                        "Modbus connect failed after {} attempts: {} (giving up)",
-                        attempts, e
-                    ));
-                    return Err(e);
-                }
-                // Escalate every 10th attempt to error for visibility
-                if attempts % 10 == 0 {
-                    self.logger.error(&format!(
-                        "Modbus connect attempt {} failed: {} (will retry in {:.1}s)",
-                        attempts,
-                        e,
-                        self.retry_delay.as_secs_f64()
-                    ));
-                } else {
-                    self.logger.warn(&format!(
-                        "Modbus connect attempt {} failed: {} (will retry in {:.1}s)",
-                        attempts,
-                        e,
-                        self.retry_delay.as_secs_f64()
-                    ));
-                }
-                sleep(self.retry_delay).await;
-                continue;
+        if !self.ensure_connected().await {
+            return Err(PhaetonError::modbus(format!(
+                "Not connected to Modbus server ({})",
+                self.link_state().as_str()
+            )));
+        }
+        match operation(&mut self.client).await {
+            Err(e) if Self::is_connection_error(&e) => {
+                self.logger
+                    .warn(&format!("Modbus connection lost: {} (reconnecting)", e));
+                self.on_connection_lost(&e);
+                self.client.disconnect().await.ok();
+                Err(e)
             }
-
-            // Execute the operation
-            match operation(&mut self.client).await {
-                Ok(result) => return Ok(result),
-                Err(e) => {
-                    // Check if it's a connection error that requires reconnection
-                    if Self::is_connection_error(&e) {
-                        attempts += 1;
-                        if self.max_retry_attempts > 0 && attempts >= self.max_retry_attempts {
-                            self.logger.error(&format!(
-                                "Operation failed due to connection error after {} attempts: {} (giving up)",
-                                attempts, e
-                            ));
-                            // Ensure we don't leave a stale client around so the next call reconnects
-                            self.client.disconnect().await.ok();
-                            return Err(e);
-                        }
-                        if attempts % 10 == 0 {
-                            self.logger.error(&format!(
-                                "Operation failed due to connection error (attempt {}): {} (retrying in {:.1}s)",
-                                attempts,
-                                e,
-                                self.retry_delay.as_secs_f64()
-                            ));
-                        } else {
-                            self.logger.warn(&format!(
-                                "Operation failed due to connection error (attempt {}): {} (retrying in {:.1}s)",
-                                attempts,
-                                e,
-                                self.retry_delay.as_secs_f64()
-                            ));
-                        }
-                        self.client.disconnect().await.ok(); // Ignore disconnect errors
-                        sleep(self.retry_delay).await;
-                        continue;
-                    } else {
-                        return Err(e);
-                    }
-                }
+            // An exception response still proves the connection alive
+            res => {
+                self.on_response();
+                res
             }
         }
     }
@@ -448,6 +462,10 @@ impl ModbusConnectionManager {
                     || m.contains("connection aborted")
                     || m.contains("not connected")
                     || m.contains("eof")
+                    // Transport (I/O) failures of a request; Modbus
+                    // exceptions are reported as "Modbus exception ..."
+                    || m.starts_with("failed to read")
+                    || m.starts_with("failed to write")
             }
             PhaetonError::Timeout { message: _ } => true,
             _ => false,
@@ -460,9 +478,21 @@ impl crate::driver::modbus_like::ModbusLike for ModbusConnectionManager {
     fn connection_status(&self) -> Option<bool> {
         Some(self.client.is_connected())
     }
+    fn connection_stats(&self) -> Option<ConnectionStats> {
+        Some(self.stats())
+    }
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
+    async fn keepalive(&mut self) -> Result<bool> {
+        ModbusConnectionManager::keepalive(self).await
+    }
+    async fn close(&mut self) {
+        ModbusConnectionManager::close(self).await
+    }
+    async fn ensure_connected(&mut self) -> bool {
+        ModbusConnectionManager::ensure_connected(self).await
+    }
     async fn read_holding_registers(
         &mut self,
         slave_id: u8,
@@ -0,0 +1,302 @@
+//! Raw register inspector for diagnostics
+//!
+//! Reads any holding registers and decodes them as one of the
+//! [`RegisterType`]s; writes are limited to the registers Phaeton itself
+//! writes ([`writable_ranges`]) so a typo cannot reconfigure the station.
+//! Used by `/api/modbus/read`, `/api/modbus/write` and `phaeton modbus`.
+
+use super::{decode_32bit_float, decode_64bit_float, decode_string, encode_32bit_float};
+use crate::config::Config;
+use crate::driver::modbus_like::ModbusLike;
+use crate::error::{PhaetonError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Largest register count of one inspector read
+pub const MAX_INSPECT_REGISTERS: u16 = 125;
+
+/// How registers are decoded or encoded (big-endian word order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RegisterType {
+    #[default]
+    U16,
+    I16,
+    U32,
+    U64,
+    F32,
+    F64,
+    String,
+}
+
+impl RegisterType {
+    /// Registers per value (0 for strings, which span the whole read)
+    pub const fn width(self) -> usize {
+        match self {
+            Self::U16 | Self::I16 => 1,
+            Self::U32 | Self::F32 => 2,
+            Self::U64 | Self::F64 => 4,
+            Self::String => 0,
+        }
+    }
+}
+
+impl std::str::FromStr for RegisterType {
+    type Err = PhaetonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_value(Value::String(s.to_ascii_lowercase())).map_err(|_| {
+            PhaetonError::validation(
+                "type",
+                "Must be one of u16, i16, u32, u64, f32, f64, string",
+            )
+        })
+    }
+}
+
+fn words_to_u64(words: &[u16]) -> u64 {
+    words.iter().fold(0, |acc, w| (acc << 16) | u64::from(*w))
+}
+
+/// Decode registers; numeric types yield one value per `width()` registers
+pub fn decode(regs: &[u16], ty: RegisterType) -> Result<Value> {
+    if ty == RegisterType::String {
+        return Ok(Value::String(decode_string(regs, None)?));
+    }
+    let width = ty.width();
+    if regs.is_empty() || !regs.len().is_multiple_of(width) {
+        return Err(PhaetonError::validation(
+            "count",
+            &format!("Must be a multiple of {} for {:?}", width, ty),
+        ));
+    }
+    regs.chunks(width)
+        .map(|w| {
+            Ok(match ty {
+                RegisterType::U16 => Value::from(w[0]),
+                RegisterType::I16 => Value::from(w[0] as i16),
+                RegisterType::U32 | RegisterType::U64 => Value::from(words_to_u64(w)),
+                RegisterType::F32 => Value::from(f64::from(decode_32bit_float(w)?)),
+                RegisterType::F64 => Value::from(decode_64bit_float(w)?),
+                RegisterType::String => unreachable!(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Value::Array)
+}
+
+/// Encode one value, or an array of values, as registers
+pub fn encode(value: &Value, ty: RegisterType) -> Result<Vec<u16>> {
+    if let Value::Array(items) = value {
+        return items.iter().try_fold(Vec::new(), |mut regs, v| {
+            regs.extend(encode(v, ty)?);
+            Ok(regs)
+        });
+    }
+    let invalid = || PhaetonError::validation("value", &format!("Not a valid {:?} value", ty));
+    let int = |max: u64| value.as_u64().filter(|v| *v <= max).ok_or_else(invalid);
+    let words = |v: u64, n: usize| (0..n).rev().map(|i| (v >> (16 * i)) as u16).collect();
+    Ok(match ty {
+        RegisterType::U16 => vec![int(u64::from(u16::MAX))? as u16],
+        RegisterType::I16 => {
+            let v = value.as_i64().and_then(|v| i16::try_from(v).ok());
+            vec![v.ok_or_else(invalid)? as u16]
+        }
+        RegisterType::U32 => words(int(u64::from(u32::MAX))?, 2),
+        RegisterType::U64 => words(int(u64::MAX)?, 4),
+        RegisterType::F32 => {
+            encode_32bit_float(value.as_f64().ok_or_else(invalid)? as f32).to_vec()
+        }
+        RegisterType::F64 => {
+            super::encode_64bit_float(value.as_f64().ok_or_else(invalid)?).to_vec()
+        }
+        RegisterType::String => return Err(invalid()),
+    })
+}
+
+/// A writable register range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WritableRange {
+    pub name: &'static str,
+    pub slave: u8,
+    pub address: u16,
+    pub count: u16,
+}
+
+/// Registers the inspector may write: the socket max current and phases of
+/// the socket slave, and the SCN per-phase max currents of the station slave.
+/// The addresses are fixed rather than taken from `config.registers`, which
+/// can be changed through the config API.
+pub fn writable_ranges(config: &Config) -> [WritableRange; 3] {
+    let (socket, station) = (
+        config.modbus.socket_slave_id,
+        config.modbus.station_slave_id,
+    );
+    [
+        WritableRange {
+            name: "max_current",
+            slave: socket,
+            address: 1210,
+            count: 2,
+        },
+        WritableRange {
+            name: "phases",
+            slave: socket,
+            address: 1215,
+            count: 1,
+        },
+        WritableRange {
+            name: "scn_max_current",
+            slave: station,
+            address: 1417,
+            count: 6,
+        },
+    ]
+}
+
+/// Whether writing `count` registers at `address` stays within one allowed range
+pub fn write_allowed(config: &Config, slave: u8, address: u16, count: usize) -> bool {
+    let end = usize::from(address) + count;
+    count > 0
+        && writable_ranges(config).iter().any(|r| {
+            r.slave == slave
+                && address >= r.address
+                && end <= usize::from(r.address) + usize::from(r.count)
+        })
+}
+
+/// Result of an inspector read
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectRead {
+    pub slave: u8,
+    pub address: u16,
+    pub count: u16,
+    #[serde(rename = "type")]
+    pub ty: RegisterType,
+    pub registers: Vec<u16>,
+    pub value: Value,
+}
+
+/// Read and decode registers
+pub async fn read(
+    modbus: &mut dyn ModbusLike,
+    slave: u8,
+    address: u16,
+    count: u16,
+    ty: RegisterType,
+) -> Result<InspectRead> {
+    if count == 0 || count > MAX_INSPECT_REGISTERS {
+        return Err(PhaetonError::validation(
+            "count",
+            &format!("Must be 1..={}", MAX_INSPECT_REGISTERS),
+        ));
+    }
+    let registers = modbus.read_holding_registers(slave, address, count).await?;
+    let value = decode(&registers, ty)?;
+    Ok(InspectRead {
+        slave,
+        address,
+        count,
+        ty,
+        registers,
+        value,
+    })
+}
+
+/// Encode and write `value` if the target is on the allowlist; returns the
+/// registers written
+pub async fn write(
+    modbus: &mut dyn ModbusLike,
+    config: &Config,
+    slave: u8,
+    address: u16,
+    ty: RegisterType,
+    value: &Value,
+) -> Result<Vec<u16>> {
+    let regs = encode(value, ty)?;
+    if !write_allowed(config, slave, address, regs.len()) {
+        return Err(PhaetonError::validation(
+            "address",
+            &format!(
+                "Writing {} register(s) at {} on slave {} is not allowed",
+                regs.len(),
+                address,
+                slave
+            ),
+        ));
+    }
+    crate::logging::get_logger("modbus").warn(&format!(
+        "Inspector write: slave {} address {} values {:?}",
+        slave, address, regs
+    ));
+    modbus
+        .write_multiple_registers(slave, address, &regs)
+        .await?;
+    Ok(regs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn decodes_and_encodes_all_types() {
+        assert_eq!(
+            decode(&[1, 0xFFFF], RegisterType::U16).unwrap(),
+            json!([1, 65535])
+        );
+        assert_eq!(decode(&[0xFFFF], RegisterType::I16).unwrap(), json!([-1]));
+        assert_eq!(decode(&[1, 2], RegisterType::U32).unwrap(), json!([65538]));
+        assert_eq!(
+            decode(&[0, 0, 1, 0], RegisterType::U64).unwrap(),
+            json!([65536])
+        );
+        assert_eq!(
+            decode(&[0x4120, 0], RegisterType::F32).unwrap(),
+            json!([10.0])
+        );
+        assert_eq!(
+            decode(&[0x4142, 0x4300], RegisterType::String).unwrap(),
+            json!("ABC")
+        );
+        assert!(decode(&[1, 2, 3], RegisterType::U32).is_err());
+
+        assert_eq!(
+            encode(&json!(16.0), RegisterType::F32).unwrap(),
+            vec![0x4180, 0]
+        );
+        assert_eq!(
+            encode(&json!([1, 3]), RegisterType::U16).unwrap(),
+            vec![1, 3]
+        );
+        assert_eq!(encode(&json!(-2), RegisterType::I16).unwrap(), vec![0xFFFE]);
+        assert_eq!(
+            encode(&json!(65538), RegisterType::U32).unwrap(),
+            vec![1, 2]
+        );
+        assert!(encode(&json!(70000), RegisterType::U16).is_err());
+        assert_eq!("F64".parse::<RegisterType>().unwrap(), RegisterType::F64);
+        assert!("u8".parse::<RegisterType>().is_err());
+    }
+
+    #[test]
+    fn allows_only_known_writable_registers() {
+        let cfg = Config::default();
+        assert!(write_allowed(&cfg, 1, 1210, 2));
+        assert!(write_allowed(&cfg, 1, 1215, 1));
+        assert!(write_allowed(&cfg, 200, 1417, 6));
+        assert!(write_allowed(&cfg, 200, 1419, 2));
+        assert!(!write_allowed(&cfg, 1, 1210, 3));
+        assert!(!write_allowed(&cfg, 200, 1210, 2));
+        assert!(!write_allowed(&cfg, 200, 1100, 2));
+        assert!(!write_allowed(&cfg, 1, 1215, 0));
+
+        // Remapped registers in the config do not widen the allowlist
+        let mut cfg = Config::default();
+        cfg.registers.amps_config = 1100;
+        assert!(!write_allowed(&cfg, 1, 1100, 2));
+        assert!(write_allowed(&cfg, 1, 1210, 2));
+    }
+}
@@ -0,0 +1,76 @@
+//! Keepalive, graceful close and connection tracking of the connection manager
+
+use super::{ConnectionStats, ModbusConnectionManager, SLOTS_FULL_HINT};
+use crate::error::{PhaetonError, Result};
+use std::time::Duration;
+
+/// Station register read as keepalive (first register of the station name,
+/// 100..116, on the station slave; the manufacturer starts at 117)
+pub const KEEPALIVE_REGISTER: u16 = 100;
+
+impl ModbusConnectionManager {
+    /// Read a station register when the connection has been idle for
+    /// `modbus.keepalive_s`, so the station does not drop it after 60 s of
+    /// silence. Returns whether a keepalive was sent.
+    pub async fn keepalive(&mut self) -> Result<bool> {
+        let interval = Duration::from_secs(self.config.keepalive_s);
+        if interval.is_zero() || !self.client.is_connected() || self.tracker.idle() < interval {
+            return Ok(false);
+        }
+        self.tracker.on_keepalive();
+        let slave = self.config.station_slave_id;
+        match self
+            .client
+            .read_holding_registers(slave, KEEPALIVE_REGISTER, 1)
+            .await
+        {
+            Err(e) if Self::is_connection_error(&e) => {
+                self.on_connection_lost(&e);
+                self.client.disconnect().await.ok();
+                Err(e)
+            }
+            // An exception response also proves the connection alive
+            _ => {
+                self.on_response();
+                Ok(true)
+            }
+        }
+    }
+
+    /// Close the connection, releasing the station's master slot
+    pub async fn close(&mut self) {
+        if let Some(task) = self.pending.take() {
+            task.abort();
+        }
+        if self.client.is_connected() {
+            self.client.disconnect().await.ok();
+            self.tracker.on_disconnected();
+        }
+        self.backoff.reset();
+    }
+
+    pub fn stats(&self) -> ConnectionStats {
+        ConnectionStats {
+            state: self.link_state().as_str().to_string(),
+            next_attempt_in_ms: (!self.client.is_connected())
+                .then(|| self.backoff.next_attempt_in(std::time::Instant::now()))
+                .flatten()
+                .map(|d| d.as_millis() as u64),
+            ..self.tracker.snapshot()
+        }
+    }
+
+    /// A working connection is re-established at once; one dropped before
+    /// its first response counts as a failed attempt
+    pub(super) fn on_connection_lost(&mut self, e: &PhaetonError) {
+        if self.tracker.on_connection_lost(e) {
+            self.logger.error(SLOTS_FULL_HINT);
+        }
+        if self.link_confirmed {
+            self.backoff.reset();
+        } else {
+            self.on_attempt_failed(e);
+        }
+        self.link_confirmed = false;
+    }
+}
@@ -0,0 +1,233 @@
+//! Non-blocking reconnects with backoff and an open circuit
+
+use super::{ModbusClient, ModbusConnectionManager, SLOTS_FULL_HINT};
+use crate::error::{PhaetonError, Result};
+use std::time::{Duration, Instant};
+
+/// How long a request waits for a connect attempt it started; a station on
+/// the LAN answers well within this, a dead one no longer stalls the caller
+pub const CONNECT_GRACE: Duration = Duration::from_millis(250);
+
+/// Connection state reported in the statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkState {
+    Connected,
+    Reconnecting,
+    OpenCircuit,
+}
+
+impl LinkState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Connected => "connected",
+            Self::Reconnecting => "reconnecting",
+            Self::OpenCircuit => "open_circuit",
+        }
+    }
+}
+
+/// Exponential backoff with an open-circuit state
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    pub base: Duration,
+    pub cap: Duration,
+    /// Failed attempts before the circuit opens (0 = never)
+    pub max_failures: u32,
+    pub open_for: Duration,
+    failures: u32,
+    next_at: Option<Instant>,
+    open: bool,
+}
+
+impl Backoff {
+    pub fn new(base: Duration, cap: Duration, max_failures: u32, open_for: Duration) -> Self {
+        Self {
+            base,
+            cap: cap.max(base),
+            max_failures,
+            open_for,
+            failures: 0,
+            next_at: None,
+            open: false,
+        }
+    }
+
+    /// Whether a connect attempt may start now
+    pub fn due(&self, now: Instant) -> bool {
+        self.next_at.is_none_or(|t| now >= t)
+    }
+
+    /// Record a failed attempt; `jitter` in 0..=1 scales the delay between
+    /// 50 % and 100 %. Returns the delay until the next attempt.
+    pub fn on_failure(&mut self, now: Instant, jitter: f64) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        self.open = self.max_failures > 0 && self.failures >= self.max_failures;
+        let delay = if self.open {
+            // One probe per open period (half-open); a failure re-opens it
+            self.failures = self.max_failures;
+            self.open_for
+        } else {
+            let exp = self.failures.saturating_sub(1).min(16);
+            let full = self.base.saturating_mul(1 << exp).min(self.cap);
+            full.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+        };
+        self.next_at = Some(now + delay);
+        delay
+    }
+
+    /// Connection up (or closed on purpose): next attempt may start at once
+    pub fn reset(&mut self) {
+        self.failures = 0;
+        self.next_at = None;
+        self.open = false;
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn state(&self, connected: bool) -> LinkState {
+        match (connected, self.open) {
+            (true, _) => LinkState::Connected,
+            (false, true) => LinkState::OpenCircuit,
+            (false, false) => LinkState::Reconnecting,
+        }
+    }
+
+    /// Time until the next attempt may start
+    pub fn next_attempt_in(&self, now: Instant) -> Option<Duration> {
+        self.next_at.map(|t| t.saturating_duration_since(now))
+    }
+}
+
+/// Pseudo-random jitter in 0..1 from the clock's sub-second nanos
+fn jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    f64::from(nanos % 1000) / 1000.0
+}
+
+pub(super) type ConnectResult = Result<(tokio_modbus::client::Context, std::net::SocketAddr)>;
+
+impl ModbusConnectionManager {
+    /// Make progress towards a connection without blocking: pick up a
+    /// finished connect attempt, or start one when the backoff allows it.
+    /// Returns whether the connection is up.
+    pub async fn ensure_connected(&mut self) -> bool {
+        if self.client.is_connected() {
+            return true;
+        }
+        let started = self.pending.is_none() && self.backoff.due(Instant::now());
+        if started {
+            let config = self.config.clone();
+            let connect_timeout = self.client.connection_timeout;
+            self.pending = Some(tokio::spawn(async move {
+                ModbusClient::open(&config, connect_timeout).await
+            }));
+        }
+        let Some(mut task) = self.pending.take() else {
+            return false;
+        };
+        let finished = if started {
+            tokio::time::timeout(CONNECT_GRACE, &mut task).await.ok()
+        } else if task.is_finished() {
+            Some((&mut task).await)
+        } else {
+            None
+        };
+        match finished {
+            Some(res) => self.on_connect_result(res.unwrap_or_else(|e| {
+                Err(PhaetonError::generic(format!("Connect task failed: {}", e)))
+            })),
+            None => {
+                self.pending = Some(task);
+                false
+            }
+        }
+    }
+
+    fn on_connect_result(&mut self, res: ConnectResult) -> bool {
+        match res {
+            Ok((ctx, addr)) => {
+                self.client.attach(ctx, addr);
+                self.tracker.on_connected();
+                // The backoff is reset by the first response, not here
+                self.link_confirmed = false;
+                true
+            }
+            Err(e) => {
+                if self.tracker.on_connect_failed(&e) {
+                    self.logger.error(SLOTS_FULL_HINT);
+                }
+                self.on_attempt_failed(&e);
+                false
+            }
+        }
+    }
+
+    /// Count a failed connect attempt towards the backoff and open circuit
+    pub(super) fn on_attempt_failed(&mut self, e: &PhaetonError) {
+        let delay = self.backoff.on_failure(Instant::now(), jitter());
+        let attempt = self.backoff.failures();
+        if self.backoff.state(false) == LinkState::OpenCircuit {
+            self.logger.error(&format!(
+                "Modbus connect failed {} times: {} (circuit open, next attempt in {:.0}s)",
+                attempt,
+                e,
+                delay.as_secs_f64()
+            ));
+        } else {
+            self.logger.warn(&format!(
+                "Modbus connect attempt {} failed: {} (next attempt in {:.1}s)",
+                attempt,
+                e,
+                delay.as_secs_f64()
+            ));
+        }
+    }
+
+    /// A response arrived: the first one confirms the connection
+    pub(super) fn on_response(&mut self) {
+        self.tracker.on_request_ok();
+        if !self.link_confirmed {
+            self.link_confirmed = true;
+            self.backoff.reset();
+        }
+    }
+
+    /// Current state of the connection
+    pub fn link_state(&self) -> LinkState {
+        self.backoff.state(self.client.is_connected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially_then_opens_circuit() {
+        let s = Duration::from_secs;
+        let mut b = Backoff::new(s(1), s(8), 6, s(120));
+        let t0 = Instant::now();
+        assert!(b.due(t0));
+        let delays: Vec<Duration> = (0..5).map(|_| b.on_failure(t0, 1.0)).collect();
+        assert_eq!(delays, [s(1), s(2), s(4), s(8), s(8)]);
+        assert_eq!(b.state(false), LinkState::Reconnecting);
+        assert!(!b.due(t0 + s(7)));
+        assert!(b.due(t0 + s(8)));
+
+        // Jitter shortens the delay by up to half
+        assert_eq!(Backoff::new(s(4), s(8), 0, s(1)).on_failure(t0, 0.0), s(2));
+
+        assert_eq!(b.on_failure(t0, 1.0), s(120));
+        assert_eq!(b.state(false), LinkState::OpenCircuit);
+        // A failed probe keeps the circuit open
+        assert_eq!(b.on_failure(t0, 1.0), s(120));
+        b.reset();
+        assert_eq!(b.state(true), LinkState::Connected);
+        assert!(b.due(t0));
+    }
+}
@@ -0,0 +1,171 @@
+//! Modbus connection statistics and slots-full diagnostics
+
+use crate::error::PhaetonError;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Diagnostic logged when the station appears to have no free master slot
+pub const SLOTS_FULL_HINT: &str = "The station refused or dropped the Modbus connection; \
+Alfen stations accept only two Modbus TCP masters at a time. Check whether another EMS, \
+HEMS or Phaeton instance holds a connection to this station";
+
+/// Connection statistics exposed in `/api/metrics`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub connected: bool,
+    /// "connected", "reconnecting" or "open_circuit"
+    pub state: String,
+    /// Time until the next connect attempt while disconnected (ms)
+    pub next_attempt_in_ms: Option<u64>,
+    /// Successful connects since start
+    pub connects: u64,
+    /// Connects after the first one
+    pub reconnect_count: u64,
+    pub connect_failures: u64,
+    /// Whether the last failure looked like "no free master slot"
+    pub slots_full: bool,
+    pub last_error: Option<String>,
+    /// Time of the last error (RFC 3339)
+    pub last_error_at: Option<String>,
+    /// Start of the current connection (RFC 3339)
+    pub connected_since: Option<String>,
+    /// Duration of the current connection (s)
+    pub connected_s: u64,
+    /// Time connected since start (s)
+    pub total_connected_s: u64,
+    /// Keepalive reads sent on an otherwise idle connection
+    pub keepalives: u64,
+}
+
+/// Tracks connection events of one Modbus connection
+#[derive(Debug)]
+pub struct ConnectionTracker {
+    stats: ConnectionStats,
+    connected_at: Option<Instant>,
+    earlier_connected: Duration,
+    /// Successful requests on the current connection
+    requests: u64,
+    last_activity: Instant,
+}
+
+impl Default for ConnectionTracker {
+    fn default() -> Self {
+        Self {
+            stats: ConnectionStats::default(),
+            connected_at: None,
+            earlier_connected: Duration::ZERO,
+            requests: 0,
+            last_activity: Instant::now(),
+        }
+    }
+}
+
+impl ConnectionTracker {
+    pub fn on_connected(&mut self) {
+        if self.stats.connects > 0 {
+            self.stats.reconnect_count += 1;
+        }
+        self.stats.connects += 1;
+        self.stats.connected = true;
+        self.stats.connected_since = Some(chrono::Utc::now().to_rfc3339());
+        self.connected_at = Some(Instant::now());
+        self.requests = 0;
+        self.last_activity = Instant::now();
+    }
+
+    pub fn on_disconnected(&mut self) {
+        if let Some(at) = self.connected_at.take() {
+            self.earlier_connected += at.elapsed();
+        }
+        self.stats.connected = false;
+        self.stats.connected_since = None;
+    }
+
+    pub fn on_request_ok(&mut self) {
+        self.requests += 1;
+        self.stats.slots_full = false;
+        self.last_activity = Instant::now();
+    }
+
+    /// Record a failed connect; returns true when it newly looks like the
+    /// station has no free master slot
+    pub fn on_connect_failed(&mut self, error: &PhaetonError) -> bool {
+        self.stats.connect_failures += 1;
+        self.record_error(error);
+        let refused = error.to_string().to_lowercase().contains("refused");
+        self.set_slots_full(refused)
+    }
+
+    /// Record a connection error of a request; a connection dropped before
+    /// its first response also points at a full station. Returns true when
+    /// that is newly the case.
+    pub fn on_connection_lost(&mut self, error: &PhaetonError) -> bool {
+        self.record_error(error);
+        let dropped_unused = self.connected_at.is_some() && self.requests == 0;
+        self.on_disconnected();
+        dropped_unused && self.set_slots_full(true)
+    }
+
+    pub fn on_keepalive(&mut self) {
+        self.stats.keepalives += 1;
+    }
+
+    /// Time since the last successful request
+    pub fn idle(&self) -> Duration {
+        self.last_activity.elapsed()
+    }
+
+    pub fn snapshot(&self) -> ConnectionStats {
+        let current = self.connected_at.map(|at| at.elapsed()).unwrap_or_default();
+        ConnectionStats {
+            connected_s: current.as_secs(),
+            total_connected_s: (self.earlier_connected + current).as_secs(),
+            ..self.stats.clone()
+        }
+    }
+
+    fn record_error(&mut self, error: &PhaetonError) {
+        self.stats.last_error = Some(error.to_string());
+        self.stats.last_error_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    fn set_slots_full(&mut self, full: bool) -> bool {
+        let newly = full && !self.stats.slots_full;
+        self.stats.slots_full = full;
+        newly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_reconnects_and_full_slots() {
+        let mut t = ConnectionTracker::default();
+        let refused = PhaetonError::modbus("Failed to connect: Connection refused (os error 111)");
+        assert!(t.on_connect_failed(&refused));
+        // Reported once per episode
+        assert!(!t.on_connect_failed(&refused));
+
+        t.on_connected();
+        t.on_request_ok();
+        assert!(!t.snapshot().slots_full);
+        assert!(!t.on_connection_lost(&PhaetonError::modbus("broken pipe")));
+
+        // Accepted, then dropped before the first response
+        t.on_connected();
+        assert!(t.on_connection_lost(&PhaetonError::modbus("unexpected eof")));
+
+        let s = t.snapshot();
+        assert_eq!(
+            (s.connects, s.reconnect_count, s.connect_failures),
+            (2, 1, 2)
+        );
+        assert!(s.slots_full && !s.connected);
+        assert_eq!(
+            s.last_error.as_deref(),
+            Some("Modbus error: unexpected eof")
+        );
+    }
+}
@@ -40,6 +40,23 @@ pub struct ChargingSession {
 
     /// Session status
     pub status: SessionStatus,
+
+    /// Notable events during the session (e.g. a station reboot)
+    #[serde(default)]
+    pub events: Vec<SessionEvent>,
+}
+
+/// Timestamped event in a session's timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEvent {
+    /// Time of the event
+    pub time: DateTime<Utc>,
+
+    /// Event kind, e.g. "station_reboot"
+    pub kind: String,
+
+    /// Human-readable details
+    pub message: String,
 }
 
 /// Session status enumeration
@@ -107,6 +124,7 @@ impl ChargingSessionManager {
             average_power_w: 0.0,
             cost: None,
             status: SessionStatus::Active,
+            events: Vec::new(),
         };
 
         self.logger
@@ -137,14 +155,41 @@ impl ChargingSessionManager {
         Ok(())
     }
 
+    /// Add an event to the active session's timeline; returns false when
+    /// no session is active
+    pub fn record_event(&mut self, kind: &str, message: &str) -> bool {
+        let Some(ref mut session) = self.current_session else {
+            return false;
+        };
+        session.events.push(SessionEvent {
+            time: Utc::now(),
+            kind: kind.to_string(),
+            message: message.to_string(),
+        });
+        true
+    }
+
     /// End the current session
     pub fn end_session(&mut self, end_energy_kwh: f64) -> Result<()> {
+        self.close_session(Some(end_energy_kwh), SessionStatus::Completed)
+    }
+
+    /// End the current session as interrupted (e.g. the station became
+    /// inoperative). Without a meter reading the energy delivered so far is kept.
+    pub fn interrupt_session(&mut self, end_energy_kwh: Option<f64>) -> Result<()> {
+        self.close_session(end_energy_kwh, SessionStatus::Interrupted)
+    }
+
+    fn close_session(&mut self, end_energy_kwh: Option<f64>, status: SessionStatus) -> Result<()> {
         if let Some(mut session) = self.current_session.take() {
+            let end_energy_kwh =
+                end_energy_kwh.unwrap_or(session.start_energy_kwh + session.energy_delivered_kwh);
             session.end_time = Some(Utc::now());
             session.end_energy_kwh = Some(end_energy_kwh);
             let energy_delivered = end_energy_kwh - session.start_energy_kwh;
             session.energy_delivered_kwh = energy_delivered;
-            session.status = SessionStatus::Completed;
+            let interrupted = matches!(status, SessionStatus::Interrupted);
+            session.status = status;
 
             // Move to last session and add to history
             self.last_session = Some(session.clone());
@@ -156,7 +201,8 @@ impl ChargingSessionManager {
             }
 
             self.logger.info(&format!(
-                "Ended charging session, delivered {:.3} kWh",
+                "{} charging session, delivered {:.3} kWh",
+                if interrupted { "Interrupted" } else { "Ended" },
                 energy_delivered
             ));
 
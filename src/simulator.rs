@@ -0,0 +1,412 @@
+//! Alfen NG9xx Modbus TCP simulator
+//!
+//! Serves the register map from the Alfen Modbus slave documentation over
+//! Modbus TCP so the driver can be exercised end to end without a physical
+//! charger. Each socket models a car that follows the written max current
+//! with a configurable lag, and the station falls back to the safe current
+//! when the validity timer of a setpoint expires.
+
+mod car;
+mod registers;
+mod script;
+mod server;
+
+pub use car::{CarState, MIN_OFFERED_CURRENT, SocketModel};
+pub use script::{SimCommand, parse_command, run_script};
+pub use server::serve;
+
+use crate::error::{PhaetonError, Result};
+use crate::modbus::decode_32bit_float;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Slave id of the station (identification, status and SCN registers)
+pub const STATION_SLAVE_ID: u8 = 200;
+
+/// Static simulator settings
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    /// Number of sockets (1 or 2); socket N is served on slave id N
+    pub sockets: u8,
+    /// Station active max current (register 1100)
+    pub station_max_current: f32,
+    /// Active load balancing safe current used when a setpoint expires
+    pub safe_current: f32,
+    /// Validity time of written max currents in seconds
+    pub validity_seconds: u32,
+    /// Maximum current a simulated car accepts
+    pub car_max_current: f32,
+    /// Time constant of the car's response to a new setpoint (seconds)
+    pub car_lag_seconds: f32,
+    /// Phase-to-neutral voltage reported by the meters
+    pub voltage: f32,
+    /// Whether socket max currents (1210) are used ("Enable sockets")
+    pub socket_control: bool,
+    /// Whether SCN max currents (1417..1422) are used ("Enable SCN")
+    pub scn_control: bool,
+    /// Maximum number of simultaneous Modbus TCP masters
+    pub max_connections: usize,
+    /// Station serial number (registers 157..167)
+    pub serial: String,
+    /// Firmware version string (registers 123..139)
+    pub firmware_version: String,
+    /// Time zone offset to UTC in minutes (register 178)
+    pub timezone_offset_minutes: i16,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            sockets: 1,
+            station_max_current: 32.0,
+            safe_current: 6.0,
+            validity_seconds: 60,
+            car_max_current: 16.0,
+            car_lag_seconds: 2.0,
+            voltage: 230.0,
+            socket_control: true,
+            scn_control: false,
+            max_connections: 2,
+            serial: "ACE0000001".to_string(),
+            firmware_version: "6.4.0-4210".to_string(),
+            timezone_offset_minutes: 0,
+        }
+    }
+}
+
+/// Modbus exception codes returned by the simulator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModbusException {
+    IllegalFunction = 0x01,
+    IllegalDataAddress = 0x02,
+    IllegalDataValue = 0x03,
+    GatewayTargetFailed = 0x0B,
+}
+
+/// Mutable state of the simulated station
+pub(crate) struct Station {
+    pub(crate) config: SimulatorConfig,
+    pub(crate) started: Instant,
+    pub(crate) temperature_c: f32,
+    pub(crate) sockets: Vec<SocketModel>,
+    pub(crate) scn_max: [f32; 3],
+    pub(crate) scn_valid_until: [Option<Instant>; 3],
+}
+
+impl Station {
+    fn socket_limit(&self, index: usize, now: Instant) -> Option<f32> {
+        if !self.config.socket_control {
+            return None;
+        }
+        let s = &self.sockets[index];
+        Some(if s.setpoint_valid(now) {
+            s.modbus_max_current
+        } else {
+            self.config.safe_current
+        })
+    }
+
+    pub(crate) fn scn_actual_max(&self, phase: usize, now: Instant) -> f32 {
+        let valid = self.scn_valid_until[phase].is_some_and(|t| t > now);
+        let limit = if valid {
+            self.scn_max[phase]
+        } else {
+            self.config.safe_current
+        };
+        limit.min(self.config.station_max_current)
+    }
+
+    /// Overall max current applied to a socket (register 1206)
+    pub(crate) fn applied_max_current(&self, index: usize, now: Instant) -> f32 {
+        let mut applied = self.config.station_max_current;
+        if let Some(limit) = self.socket_limit(index, now) {
+            applied = applied.min(limit);
+        }
+        if self.config.scn_control {
+            let phases = if self.sockets[index].phases == 1 {
+                1
+            } else {
+                3
+            };
+            for p in 0..phases {
+                applied = applied.min(self.scn_actual_max(p, now));
+            }
+        }
+        applied
+    }
+
+    pub(crate) fn scn_consumption(&self) -> [f32; 3] {
+        let mut total = [0.0f32; 3];
+        for s in &self.sockets {
+            for (t, c) in total.iter_mut().zip(s.phase_currents()) {
+                *t += c;
+            }
+        }
+        total
+    }
+
+    /// Advance all sockets to `now`, splitting at setpoint expiry so the
+    /// fallback to the safe current happens at the right moment.
+    fn advance(&mut self, now: Instant) {
+        let lag = self.config.car_lag_seconds;
+        let voltage = self.config.voltage;
+        for i in 0..self.sockets.len() {
+            let last = self.sockets[i].last_update;
+            if let Some(expiry) = self.sockets[i].valid_until
+                && expiry > last
+                && expiry < now
+            {
+                let applied = self.applied_max_current(i, last);
+                self.sockets[i].advance(expiry, applied, lag, voltage);
+            }
+            let applied = self.applied_max_current(i, self.sockets[i].last_update);
+            self.sockets[i].advance(now, applied, lag, voltage);
+        }
+    }
+
+    fn socket_index(&self, slave_id: u8) -> Option<usize> {
+        let idx = usize::from(slave_id).checked_sub(1)?;
+        (idx < self.sockets.len()).then_some(idx)
+    }
+}
+
+/// Shared handle to a simulated station; cheap to clone
+#[derive(Clone)]
+pub struct Simulator {
+    station: Arc<Mutex<Station>>,
+}
+
+impl Simulator {
+    /// Create a simulator with all sockets idle
+    pub fn new(config: SimulatorConfig) -> Self {
+        let now = Instant::now();
+        let sockets = (0..config.sockets.clamp(1, 2))
+            .map(|_| SocketModel::new(now, config.car_max_current))
+            .collect();
+        Self {
+            station: Arc::new(Mutex::new(Station {
+                config,
+                started: now,
+                temperature_c: 35.0,
+                sockets,
+                scn_max: [0.0; 3],
+                scn_valid_until: [None; 3],
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Station> {
+        self.station
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Maximum number of simultaneous Modbus TCP masters
+    pub fn max_connections(&self) -> usize {
+        self.lock().config.max_connections
+    }
+
+    /// Snapshot of a socket (1-based) after advancing the model to now
+    pub fn socket(&self, socket: u8) -> Option<SocketModel> {
+        let mut st = self.lock();
+        st.advance(Instant::now());
+        let idx = st.socket_index(socket)?;
+        Some(st.sockets[idx].clone())
+    }
+
+    /// Overall max current currently applied to a socket (1-based)
+    pub fn applied_max_current(&self, socket: u8) -> Option<f32> {
+        let st = self.lock();
+        let idx = st.socket_index(socket)?;
+        Some(st.applied_max_current(idx, Instant::now()))
+    }
+
+    /// Apply a scripted event immediately. `Wait` is a no-op here; it is
+    /// interpreted by [`run_script`].
+    pub fn apply(&self, command: &SimCommand) -> Result<()> {
+        let mut st = self.lock();
+        st.advance(Instant::now());
+        let socket_of = |st: &Station, socket: u8| {
+            st.socket_index(socket).ok_or_else(|| {
+                PhaetonError::validation("socket", &format!("No such socket: {}", socket))
+            })
+        };
+        match *command {
+            SimCommand::PlugIn {
+                socket,
+                max_current,
+            } => {
+                let idx = socket_of(&st, socket)?;
+                let s = &mut st.sockets[idx];
+                s.car = CarState::Charging;
+                if let Some(max) = max_current {
+                    s.car_max_current = max;
+                }
+            }
+            SimCommand::Unplug { socket } => {
+                let idx = socket_of(&st, socket)?;
+                let s = &mut st.sockets[idx];
+                s.car = CarState::Disconnected;
+                s.current = 0.0;
+            }
+            SimCommand::CarFull { socket } => {
+                let idx = socket_of(&st, socket)?;
+                st.sockets[idx].car = CarState::Full;
+            }
+            SimCommand::Resume { socket } => {
+                let idx = socket_of(&st, socket)?;
+                st.sockets[idx].car = CarState::Charging;
+            }
+            SimCommand::Fault { socket } => {
+                let idx = socket_of(&st, socket)?;
+                let s = &mut st.sockets[idx];
+                s.car = CarState::Fault;
+                s.current = 0.0;
+            }
+            SimCommand::Temperature(c) => st.temperature_c = c,
+            SimCommand::Wait(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Serve a Modbus "read holding registers" request
+    pub fn read(
+        &self,
+        slave_id: u8,
+        address: u16,
+        count: u16,
+    ) -> std::result::Result<Vec<u16>, ModbusException> {
+        let now = Instant::now();
+        let mut st = self.lock();
+        st.advance(now);
+        let image = if slave_id == STATION_SLAVE_ID {
+            st.station_image(now)
+        } else if let Some(idx) = st.socket_index(slave_id) {
+            st.socket_image(idx, now)
+        } else {
+            return Err(ModbusException::GatewayTargetFailed);
+        };
+        image
+            .read(address, count)
+            .ok_or(ModbusException::IllegalDataAddress)
+    }
+
+    /// Serve a Modbus write request; only the documented R/W registers are writable
+    pub fn write(
+        &self,
+        slave_id: u8,
+        address: u16,
+        values: &[u16],
+    ) -> std::result::Result<(), ModbusException> {
+        let now = Instant::now();
+        let mut st = self.lock();
+        st.advance(now);
+        let validity = Duration::from_secs(u64::from(st.config.validity_seconds));
+        let amps = |regs: &[u16]| -> std::result::Result<f32, ModbusException> {
+            decode_32bit_float(regs)
+                .ok()
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .ok_or(ModbusException::IllegalDataValue)
+        };
+
+        if slave_id == STATION_SLAVE_ID {
+            // SCN max current per phase: 1417/1419/1421, FLOAT32 each
+            if !(1417..=1421).contains(&address) || !(address - 1417).is_multiple_of(2) {
+                return Err(ModbusException::IllegalDataAddress);
+            }
+            let first = usize::from((address - 1417) / 2);
+            if !values.len().is_multiple_of(2) || first + values.len() / 2 > 3 {
+                return Err(ModbusException::IllegalDataAddress);
+            }
+            for (i, pair) in values.chunks(2).enumerate() {
+                let v = amps(pair)?;
+                st.scn_max[first + i] = v;
+                st.scn_valid_until[first + i] = Some(now + validity);
+            }
+            return Ok(());
+        }
+
+        let idx = st
+            .socket_index(slave_id)
+            .ok_or(ModbusException::GatewayTargetFailed)?;
+        match (address, values.len()) {
+            (1210, 2) => {
+                let v = amps(values)?;
+                let s = &mut st.sockets[idx];
+                s.modbus_max_current = v;
+                s.valid_until = Some(now + validity);
+                Ok(())
+            }
+            (1215, 1) => match values[0] {
+                1 | 3 => {
+                    st.sockets[idx].phases = values[0] as u8;
+                    Ok(())
+                }
+                _ => Err(ModbusException::IllegalDataValue),
+            },
+            _ => Err(ModbusException::IllegalDataAddress),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::encode_32bit_float;
+
+    fn sim() -> Simulator {
+        Simulator::new(SimulatorConfig {
+            car_lag_seconds: 0.0,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn unknown_slave_and_address_are_rejected() {
+        let s = sim();
+        assert_eq!(s.read(9, 300, 1), Err(ModbusException::GatewayTargetFailed));
+        assert_eq!(
+            s.read(STATION_SLAVE_ID, 1106, 1),
+            Err(ModbusException::IllegalDataAddress)
+        );
+        assert_eq!(
+            s.write(1, 306, &[0, 0]),
+            Err(ModbusException::IllegalDataAddress)
+        );
+        assert_eq!(
+            s.write(1, 1215, &[2]),
+            Err(ModbusException::IllegalDataValue)
+        );
+    }
+
+    #[test]
+    fn written_setpoint_is_applied_until_validity_expires() {
+        let s = sim();
+        s.write(1, 1210, &encode_32bit_float(10.0)).unwrap();
+        assert_eq!(s.applied_max_current(1), Some(10.0));
+        {
+            let mut st = s.lock();
+            st.sockets[0].valid_until = Some(Instant::now() - Duration::from_millis(1));
+        }
+        assert_eq!(s.applied_max_current(1), Some(6.0));
+        let regs = s.read(1, 1214, 1).unwrap();
+        assert_eq!(regs, vec![0]);
+    }
+
+    #[test]
+    fn scn_limits_socket_when_enabled() {
+        let s = Simulator::new(SimulatorConfig {
+            socket_control: false,
+            scn_control: true,
+            ..Default::default()
+        });
+        let mut regs = Vec::new();
+        for v in [12.0f32, 13.0, 14.0] {
+            regs.extend_from_slice(&encode_32bit_float(v));
+        }
+        s.write(STATION_SLAVE_ID, 1417, &regs).unwrap();
+        assert_eq!(s.applied_max_current(1), Some(12.0));
+        let enable = s.read(STATION_SLAVE_ID, 1431, 1).unwrap();
+        assert_eq!(enable, vec![1]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Per-socket car model for the simulator
+
+use std::time::{Duration, Instant};
+
+/// Minimum current (A) for which the station applies a PWM signal (IEC 61851)
+pub const MIN_OFFERED_CURRENT: f32 = 6.0;
+
+/// What the simulated car connected to a socket is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarState {
+    /// No car plugged in (Mode 3 state A)
+    Disconnected,
+    /// Car plugged in and willing to charge
+    Charging,
+    /// Car plugged in but its battery is full (draws no current)
+    Full,
+    /// Error state reported by the station (Mode 3 state F)
+    Fault,
+}
+
+/// Electrical model of a single socket with a car attached
+#[derive(Debug, Clone)]
+pub struct SocketModel {
+    /// Current car state
+    pub car: CarState,
+    /// Maximum current the car accepts per phase (A)
+    pub car_max_current: f32,
+    /// Last value written to the Modbus Slave Max Current register (A)
+    pub modbus_max_current: f32,
+    /// Instant at which the written max current stops being valid
+    pub valid_until: Option<Instant>,
+    /// Number of phases configured through register 1215 (1 or 3)
+    pub phases: u8,
+    /// Actual current drawn by the car on each active phase (A)
+    pub current: f32,
+    /// Delivered energy per phase (Wh)
+    pub energy_wh: [f64; 3],
+    /// Last time the model was advanced
+    pub(crate) last_update: Instant,
+}
+
+impl SocketModel {
+    /// Create an idle socket with no car connected
+    pub fn new(now: Instant, car_max_current: f32) -> Self {
+        Self {
+            car: CarState::Disconnected,
+            car_max_current,
+            modbus_max_current: 0.0,
+            valid_until: None,
+            phases: 3,
+            current: 0.0,
+            energy_wh: [0.0; 3],
+            last_update: now,
+        }
+    }
+
+    /// Remaining validity of the written max current
+    pub fn validity_remaining(&self, now: Instant) -> Duration {
+        self.valid_until
+            .map(|t| t.saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// Whether the written max current is still valid
+    pub fn setpoint_valid(&self, now: Instant) -> bool {
+        !self.validity_remaining(now).is_zero()
+    }
+
+    /// Current the car will settle at for a given applied station maximum
+    pub fn target_current(&self, applied_max: f32) -> f32 {
+        match self.car {
+            CarState::Charging if applied_max >= MIN_OFFERED_CURRENT => {
+                applied_max.min(self.car_max_current)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// IEC 61851 Mode 3 state string as reported in registers 1201..1205
+    pub fn mode3_state(&self, applied_max: f32) -> &'static str {
+        let offered = applied_max >= MIN_OFFERED_CURRENT;
+        match (self.car, offered) {
+            (CarState::Disconnected, _) => "A",
+            (CarState::Fault, _) => "F",
+            (CarState::Full, true) => "B2",
+            (CarState::Full, false) => "B1",
+            (CarState::Charging, true) => "C2",
+            (CarState::Charging, false) => "C1",
+        }
+    }
+
+    /// Per-phase currents according to the configured phase count
+    pub fn phase_currents(&self) -> [f32; 3] {
+        if self.phases == 1 {
+            [self.current, 0.0, 0.0]
+        } else {
+            [self.current; 3]
+        }
+    }
+
+    /// Advance the model to `now` while the station applies `applied_max`.
+    ///
+    /// The car follows the target current with a first-order lag of
+    /// `lag_seconds`; energy is integrated using the current at the start
+    /// of the interval.
+    pub fn advance(&mut self, now: Instant, applied_max: f32, lag_seconds: f32, voltage: f32) {
+        let dt = now
+            .saturating_duration_since(self.last_update)
+            .as_secs_f32();
+        if dt <= 0.0 {
+            return;
+        }
+        let currents = self.phase_currents();
+        for (energy, amps) in self.energy_wh.iter_mut().zip(currents) {
+            *energy += f64::from(voltage * amps * dt) / 3600.0;
+        }
+        let target = self.target_current(applied_max);
+        if lag_seconds <= 0.0 {
+            self.current = target;
+        } else {
+            let alpha = 1.0 - (-dt / lag_seconds).exp();
+            self.current += (target - self.current) * alpha;
+            if (target - self.current).abs() < 0.05 {
+                self.current = target;
+            }
+        }
+        self.last_update = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn car_follows_setpoint_with_lag() {
+        let t0 = Instant::now();
+        let mut s = SocketModel::new(t0, 16.0);
+        s.car = CarState::Charging;
+        s.advance(t0 + Duration::from_secs(1), 10.0, 2.0, 230.0);
+        assert!(s.current > 0.0 && s.current < 10.0);
+        s.advance(t0 + Duration::from_secs(30), 10.0, 2.0, 230.0);
+        assert!((s.current - 10.0).abs() < f32::EPSILON);
+        // Car limit caps the draw
+        s.advance(t0 + Duration::from_secs(60), 32.0, 0.0, 230.0);
+        assert!((s.current - 16.0).abs() < f32::EPSILON);
+        assert!(s.energy_wh.iter().all(|e| *e > 0.0));
+    }
+
+    #[test]
+    fn mode3_states_follow_car_and_offer() {
+        let mut s = SocketModel::new(Instant::now(), 16.0);
+        assert_eq!(s.mode3_state(16.0), "A");
+        s.car = CarState::Charging;
+        assert_eq!(s.mode3_state(16.0), "C2");
+        assert_eq!(s.mode3_state(0.0), "C1");
+        s.car = CarState::Full;
+        assert_eq!(s.mode3_state(16.0), "B2");
+        assert_eq!(s.mode3_state(5.0), "B1");
+        s.car = CarState::Fault;
+        assert_eq!(s.mode3_state(16.0), "F");
+        assert!(s.target_current(16.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn single_phase_draws_on_l1_only() {
+        let mut s = SocketModel::new(Instant::now(), 16.0);
+        s.current = 8.0;
+        s.phases = 1;
+        assert_eq!(s.phase_currents(), [8.0, 0.0, 0.0]);
+    }
+}
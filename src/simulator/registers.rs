@@ -0,0 +1,201 @@
+//! Register images rendered from the simulated station state
+//!
+//! Addresses follow the Alfen NG9xx Modbus slave register table; see
+//! `docs/Implementation_of_Modbus_Slave_TCPIP_for_Alfen_NG9xx_platform.md`.
+
+use super::Station;
+use crate::modbus::{encode_32bit_float, encode_64bit_float};
+use chrono::{Datelike, Timelike};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Sparse holding-register image of one Modbus slave
+#[derive(Debug, Default)]
+pub(crate) struct RegisterImage {
+    regs: HashMap<u16, u16>,
+}
+
+impl RegisterImage {
+    fn put(&mut self, addr: u16, words: &[u16]) {
+        for (i, w) in words.iter().enumerate() {
+            self.regs.insert(addr + i as u16, *w);
+        }
+    }
+
+    fn u16(&mut self, addr: u16, v: u16) {
+        self.put(addr, &[v]);
+    }
+
+    fn i16(&mut self, addr: u16, v: i16) {
+        self.put(addr, &[v as u16]);
+    }
+
+    fn u32(&mut self, addr: u16, v: u32) {
+        self.put(addr, &[(v >> 16) as u16, (v & 0xFFFF) as u16]);
+    }
+
+    fn u64(&mut self, addr: u16, v: u64) {
+        self.put(
+            addr,
+            &[
+                (v >> 48) as u16,
+                (v >> 32) as u16,
+                (v >> 16) as u16,
+                (v & 0xFFFF) as u16,
+            ],
+        );
+    }
+
+    fn f32(&mut self, addr: u16, v: f32) {
+        self.put(addr, &encode_32bit_float(v));
+    }
+
+    fn f64(&mut self, addr: u16, v: f64) {
+        self.put(addr, &encode_64bit_float(v));
+    }
+
+    /// ASCII string, two characters per register, NUL padded to `count` registers
+    fn string(&mut self, addr: u16, count: u16, s: &str) {
+        let mut bytes: Vec<u8> = s.bytes().take(count as usize * 2).collect();
+        bytes.resize(count as usize * 2, 0);
+        let words: Vec<u16> = bytes
+            .chunks(2)
+            .map(|c| (u16::from(c[0]) << 8) | u16::from(c[1]))
+            .collect();
+        self.put(addr, &words);
+    }
+
+    /// Read a contiguous range; `None` if any address is not mapped
+    pub(crate) fn read(&self, addr: u16, count: u16) -> Option<Vec<u16>> {
+        (0..count)
+            .map(|i| self.regs.get(&addr.checked_add(i)?).copied())
+            .collect()
+    }
+}
+
+impl Station {
+    /// Render the station slave (product identification, station status and SCN)
+    pub(crate) fn station_image(&self, now: Instant) -> RegisterImage {
+        let cfg = &self.config;
+        let mut img = RegisterImage::default();
+        img.string(100, 17, "ALF_1000");
+        img.string(117, 5, "Alfen NV");
+        img.i16(122, 1);
+        img.string(123, 17, &cfg.firmware_version);
+        img.string(140, 17, "NG910");
+        img.string(157, 11, &cfg.serial);
+
+        let local =
+            chrono::Utc::now() + chrono::Duration::minutes(i64::from(cfg.timezone_offset_minutes));
+        img.i16(168, local.year() as i16);
+        img.i16(169, local.month() as i16);
+        img.i16(170, local.day() as i16);
+        img.i16(171, local.hour() as i16);
+        img.i16(172, local.minute() as i16);
+        img.i16(173, local.second() as i16);
+        img.u64(174, now.duration_since(self.started).as_millis() as u64);
+        img.i16(178, cfg.timezone_offset_minutes);
+
+        img.f32(1100, cfg.station_max_current);
+        img.f32(1102, self.temperature_c);
+        img.u16(1104, 1);
+        img.u16(1105, u16::from(cfg.sockets));
+
+        img.string(1400, 4, "SCN");
+        img.u16(1404, u16::from(cfg.sockets));
+        let consumption = self.scn_consumption();
+        for phase in 0..3u16 {
+            let i = phase as usize;
+            img.f32(1405 + phase * 2, consumption[i]);
+            img.f32(1411 + phase * 2, self.scn_actual_max(i, now));
+            img.f32(1417 + phase * 2, self.scn_max[i]);
+            let remaining = self.scn_valid_until[i]
+                .map(|t| t.saturating_duration_since(now).as_secs() as u32)
+                .unwrap_or(0);
+            img.u32(1423 + phase * 2, remaining);
+        }
+        img.f32(1429, cfg.safe_current);
+        img.u16(1431, u16::from(cfg.scn_control));
+        img
+    }
+
+    /// Render a socket slave (meter measurements and socket status)
+    pub(crate) fn socket_image(&self, index: usize, now: Instant) -> RegisterImage {
+        let cfg = &self.config;
+        let socket = &self.sockets[index];
+        let applied = self.applied_max_current(index, now);
+        let currents = socket.phase_currents();
+        let v = cfg.voltage;
+        let mut img = RegisterImage::default();
+
+        // Meter state: initialised | updated; last value received just now; RTU meter
+        img.u16(300, 0x03);
+        img.u64(
+            301,
+            now.saturating_duration_since(socket.last_update)
+                .as_millis() as u64,
+        );
+        img.u16(305, 0);
+        for p in 0..3u16 {
+            img.f32(306 + p * 2, v);
+            img.f32(312 + p * 2, v * 3f32.sqrt());
+        }
+        img.f32(318, 0.0);
+        let mut total_power = 0.0f32;
+        for (p, amps) in currents.iter().enumerate() {
+            let p16 = p as u16;
+            let watts = v * amps;
+            total_power += watts;
+            img.f32(320 + p16 * 2, *amps);
+            img.f32(328 + p16 * 2, 1.0);
+            img.f32(338 + p16 * 2, watts);
+            img.f32(346 + p16 * 2, watts);
+            img.f32(354 + p16 * 2, 0.0);
+            img.f64(362 + p16 * 4, socket.energy_wh[p]);
+            img.f64(378 + p16 * 4, 0.0);
+            img.f64(394 + p16 * 4, socket.energy_wh[p]);
+            img.f64(410 + p16 * 4, 0.0);
+        }
+        let total_energy: f64 = socket.energy_wh.iter().sum();
+        img.f32(326, currents.iter().sum());
+        img.f32(334, 1.0);
+        img.f32(336, 50.0);
+        img.f32(344, total_power);
+        img.f32(352, total_power);
+        img.f32(360, 0.0);
+        img.f64(374, total_energy);
+        img.f64(390, 0.0);
+        img.f64(406, total_energy);
+        img.f64(422, 0.0);
+
+        img.u16(1200, 1);
+        img.string(1201, 5, socket.mode3_state(applied));
+        img.f32(1206, applied);
+        img.u32(1208, socket.validity_remaining(now).as_secs() as u32);
+        img.f32(1210, socket.modbus_max_current);
+        img.f32(1212, cfg.safe_current);
+        img.u16(
+            1214,
+            u16::from(cfg.socket_control && socket.setpoint_valid(now)),
+        );
+        img.u16(1215, u16::from(socket.phases));
+        img
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_and_numeric_encoding_round_trip() {
+        let mut img = RegisterImage::default();
+        img.string(10, 3, "ABCD");
+        img.u32(20, 0x0001_0002);
+        img.u64(30, 5);
+        assert_eq!(img.read(10, 3).unwrap(), vec![0x4142, 0x4344, 0x0000]);
+        assert_eq!(img.read(20, 2).unwrap(), vec![1, 2]);
+        assert_eq!(img.read(30, 4).unwrap(), vec![0, 0, 0, 5]);
+        assert!(img.read(12, 2).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+//! Scriptable simulator events
+//!
+//! Commands use a simple line format, one per line, e.g.:
+//!
+//! ```text
+//! # plug a car that accepts 16 A into socket 1, let it charge, then fill it up
+//! plug 1 16
+//! wait 30
+//! full 1
+//! wait 10
+//! unplug 1
+//! ```
+
+use super::Simulator;
+use crate::error::{PhaetonError, Result};
+use std::time::Duration;
+
+/// A single simulator event
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimCommand {
+    /// Plug a car into a socket, optionally overriding its max current
+    PlugIn {
+        socket: u8,
+        max_current: Option<f32>,
+    },
+    /// Unplug the car from a socket
+    Unplug { socket: u8 },
+    /// The car's battery is full; it stops drawing current
+    CarFull { socket: u8 },
+    /// The car wants to charge again (also clears a fault)
+    Resume { socket: u8 },
+    /// Put the socket into Mode 3 error state F
+    Fault { socket: u8 },
+    /// Set the board temperature (°C)
+    Temperature(f32),
+    /// Pause script execution
+    Wait(Duration),
+}
+
+fn invalid(line: &str, why: &str) -> PhaetonError {
+    PhaetonError::validation("script", &format!("{} in line '{}'", why, line))
+}
+
+/// Parse one script line. Blank lines and `#` comments yield `None`.
+pub fn parse_command(line: &str) -> Result<Option<SimCommand>> {
+    let line = line.split('#').next().unwrap_or("").trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let verb = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let args: Vec<&str> = parts.collect();
+    let socket = |i: usize| -> Result<u8> {
+        match args.get(i) {
+            None => Ok(1),
+            Some(v) => v.parse().map_err(|_| invalid(line, "invalid socket")),
+        }
+    };
+    let number = |i: usize| -> Result<f32> {
+        args.get(i)
+            .ok_or_else(|| invalid(line, "missing value"))?
+            .parse()
+            .map_err(|_| invalid(line, "invalid number"))
+    };
+
+    let cmd = match verb.as_str() {
+        "plug" | "plugin" => SimCommand::PlugIn {
+            socket: socket(0)?,
+            max_current: if args.len() > 1 {
+                Some(number(1)?)
+            } else {
+                None
+            },
+        },
+        "unplug" => SimCommand::Unplug { socket: socket(0)? },
+        "full" => SimCommand::CarFull { socket: socket(0)? },
+        "resume" | "clear" => SimCommand::Resume { socket: socket(0)? },
+        "fault" => SimCommand::Fault { socket: socket(0)? },
+        "temp" | "temperature" => SimCommand::Temperature(number(0)?),
+        "wait" | "sleep" => {
+            let secs = number(0)?;
+            if !secs.is_finite() || secs < 0.0 {
+                return Err(invalid(line, "invalid duration"));
+            }
+            SimCommand::Wait(Duration::from_secs_f32(secs))
+        }
+        _ => return Err(invalid(line, "unknown command")),
+    };
+    Ok(Some(cmd))
+}
+
+/// Run a parsed script against the simulator, honouring `wait` steps
+pub async fn run_script(sim: &Simulator, commands: &[SimCommand]) -> Result<()> {
+    let logger = crate::logging::get_logger("simulator");
+    for cmd in commands {
+        match cmd {
+            SimCommand::Wait(d) => tokio::time::sleep(*d).await,
+            other => {
+                logger.info(&format!("Script event: {:?}", other));
+                sim.apply(other)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_comments_and_defaults() {
+        assert_eq!(parse_command("  # comment").unwrap(), None);
+        assert_eq!(
+            parse_command("plug 2 16").unwrap(),
+            Some(SimCommand::PlugIn {
+                socket: 2,
+                max_current: Some(16.0)
+            })
+        );
+        assert_eq!(
+            parse_command("FULL").unwrap(),
+            Some(SimCommand::CarFull { socket: 1 })
+        );
+        assert_eq!(
+            parse_command("wait 1.5").unwrap(),
+            Some(SimCommand::Wait(Duration::from_millis(1500)))
+        );
+        assert!(parse_command("explode 1").is_err());
+        assert!(parse_command("temp").is_err());
+    }
+}
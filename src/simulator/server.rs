@@ -0,0 +1,137 @@
+//! Minimal Modbus TCP server for the simulator
+//!
+//! Supports the functions the Alfen station implements: 0x03 (read holding
+//! registers), 0x06 (write single register) and 0x10 (write multiple
+//! registers). Like the real station it only accepts a limited number of
+//! simultaneous masters; extra connections are closed immediately.
+
+use super::{ModbusException, Simulator};
+use crate::error::{PhaetonError, Result};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Maximum number of registers in a single read request
+const MAX_READ_COUNT: u16 = 125;
+
+/// Accept Modbus TCP masters on `listener` until the task is dropped
+pub async fn serve(listener: TcpListener, sim: Simulator) -> Result<()> {
+    let logger = crate::logging::get_logger("simulator");
+    let active = Arc::new(AtomicUsize::new(0));
+    loop {
+        let (stream, peer) = listener
+            .accept()
+            .await
+            .map_err(|e| PhaetonError::network(format!("Accept failed: {}", e)))?;
+        let max = sim.max_connections();
+        if active.load(Ordering::SeqCst) >= max {
+            logger.warn(&format!(
+                "Rejecting Modbus master {}: all {} connection slots in use",
+                peer, max
+            ));
+            drop(stream);
+            continue;
+        }
+        active.fetch_add(1, Ordering::SeqCst);
+        logger.info(&format!("Modbus master connected: {}", peer));
+        let sim = sim.clone();
+        let active = active.clone();
+        let logger = logger.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &sim).await {
+                logger.debug(&format!("Connection {} closed with error: {}", peer, e));
+            }
+            active.fetch_sub(1, Ordering::SeqCst);
+            logger.info(&format!("Modbus master disconnected: {}", peer));
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, sim: &Simulator) -> std::io::Result<()> {
+    loop {
+        // MBAP header: transaction id, protocol id, length, unit id
+        let mut header = [0u8; 7];
+        match stream.read_exact(&mut header).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        let len = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        if !(2..=254).contains(&len) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid MBAP length {}", len),
+            ));
+        }
+        let mut pdu = vec![0u8; len - 1];
+        stream.read_exact(&mut pdu).await?;
+
+        let response = handle_pdu(sim, header[6], &pdu);
+        let mut frame = Vec::with_capacity(7 + response.len());
+        frame.extend_from_slice(&header[0..4]);
+        frame.extend_from_slice(&((response.len() + 1) as u16).to_be_bytes());
+        frame.push(header[6]);
+        frame.extend_from_slice(&response);
+        stream.write_all(&frame).await?;
+    }
+}
+
+fn word(pdu: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*pdu.get(at)?, *pdu.get(at + 1)?]))
+}
+
+fn handle_pdu(sim: &Simulator, unit: u8, pdu: &[u8]) -> Vec<u8> {
+    let fc = pdu[0];
+    let result = match fc {
+        0x03 => read_holding(sim, unit, pdu),
+        0x06 => match (word(pdu, 1), word(pdu, 3)) {
+            (Some(addr), Some(value)) => sim.write(unit, addr, &[value]).map(|_| pdu[..5].to_vec()),
+            _ => Err(ModbusException::IllegalDataValue),
+        },
+        0x10 => write_multiple(sim, unit, pdu),
+        _ => Err(ModbusException::IllegalFunction),
+    };
+    result.unwrap_or_else(|e| vec![fc | 0x80, e as u8])
+}
+
+fn read_holding(
+    sim: &Simulator,
+    unit: u8,
+    pdu: &[u8],
+) -> std::result::Result<Vec<u8>, ModbusException> {
+    let (Some(addr), Some(count)) = (word(pdu, 1), word(pdu, 3)) else {
+        return Err(ModbusException::IllegalDataValue);
+    };
+    if count == 0 || count > MAX_READ_COUNT {
+        return Err(ModbusException::IllegalDataValue);
+    }
+    let regs = sim.read(unit, addr, count)?;
+    let mut out = Vec::with_capacity(2 + regs.len() * 2);
+    out.push(0x03);
+    out.push((regs.len() * 2) as u8);
+    for r in regs {
+        out.extend_from_slice(&r.to_be_bytes());
+    }
+    Ok(out)
+}
+
+fn write_multiple(
+    sim: &Simulator,
+    unit: u8,
+    pdu: &[u8],
+) -> std::result::Result<Vec<u8>, ModbusException> {
+    let (Some(addr), Some(count)) = (word(pdu, 1), word(pdu, 3)) else {
+        return Err(ModbusException::IllegalDataValue);
+    };
+    let byte_count = usize::from(*pdu.get(5).ok_or(ModbusException::IllegalDataValue)?);
+    if count == 0 || byte_count != usize::from(count) * 2 || pdu.len() < 6 + byte_count {
+        return Err(ModbusException::IllegalDataValue);
+    }
+    let values: Vec<u16> = pdu[6..6 + byte_count]
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect();
+    sim.write(unit, addr, &values)?;
+    Ok(pdu[..5].to_vec())
+}
@@ -153,10 +153,8 @@ pub async fn get_plan_json(cfg: &crate::config::TibberConfig) -> Result<serde_js
     // Determine threshold if applicable
     let mut threshold: Option<f64> = None;
     match cfg.strategy.as_str() {
-        "threshold" => {
-            if cfg.max_price_total > 0.0 {
-                threshold = Some(cfg.max_price_total);
-            }
+        "threshold" if cfg.max_price_total > 0.0 => {
+            threshold = Some(cfg.max_price_total);
         }
         "percentile" => {
             threshold = client.determine_percentile_threshold(cfg.cheap_percentile);
@@ -41,7 +41,7 @@ impl TibberClient {
         #[cfg(not(feature = "tibber"))]
         {
             let _ = (&access_token, &home_id);
-            return Self {};
+            Self {}
         }
     }
 
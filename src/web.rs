@@ -26,18 +26,28 @@ use utoipa::OpenApi;
 #[cfg(feature = "openapi")]
 use utoipa_swagger_ui::SwaggerUi;
 
+mod discovery;
 mod logs;
+mod modbus;
 pub use logs::{logs_download, logs_head, logs_stream, logs_tail};
+mod sockets;
+pub use sockets::SocketHandle;
 
+/// Shared web state. `driver`/`snapshot_rx` refer to socket 1, which the
+/// unprefixed `/api/*` endpoints control; `sockets` lists every socket.
 #[derive(Clone)]
 pub struct AppState {
     pub driver: Arc<Mutex<AlfenDriver>>,
     pub snapshot_rx: watch::Receiver<Arc<DriverSnapshot>>,
+    pub sockets: Vec<SocketHandle>,
+    /// `web.api_token` read at startup; only the config file changes it
+    pub api_token: Arc<str>,
 }
 
 #[derive(Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ModeBody {
+    /// 0 = Manual, 1 = Auto, 2 = Scheduled, 3 = Minimum + PV
     pub mode: u8,
 }
 
@@ -93,6 +103,7 @@ async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
         "modbus_connected": snap.modbus_connected,
         "driver_state": snap.driver_state,
         "poll_steps_ms": snap.poll_steps_ms,
+        "modbus": snap.modbus_stats,
     }))
 }
 
@@ -185,6 +196,10 @@ async fn get_config(State(state): State<AppState>) -> impl IntoResponse {
     if let Some(obj) = json.as_object_mut() {
         obj.remove("vehicles");
     }
+    // The inspector token is never exposed; it is only set in the config file
+    if let Some(token) = json.pointer_mut("/web/api_token") {
+        *token = serde_json::Value::String(String::new());
+    }
     Json(json)
 }
 
@@ -193,7 +208,7 @@ async fn put_config(
     State(state): State<AppState>,
     Json(new_cfg_value): Json<serde_json::Value>,
 ) -> impl IntoResponse {
-    let new_cfg: crate::config::Config = match serde_json::from_value(new_cfg_value) {
+    let mut new_cfg: crate::config::Config = match serde_json::from_value(new_cfg_value) {
         Ok(c) => c,
         Err(_) => {
             return (
@@ -209,14 +224,26 @@ async fn put_config(
         );
     }
 
-    // Apply and persist
+    // Keep the current inspector token; it cannot be read or replaced here
+    new_cfg.web.api_token = state.api_token.to_string();
+
+    // Apply to every socket (or the only driver) and persist
     let cfg_to_save = new_cfg.clone();
-    let mut drv = state.driver.lock().await;
-    if drv.update_config(new_cfg).is_err() {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error":"apply failed"})),
-        );
+    let drivers: Vec<_> = if state.sockets.is_empty() {
+        vec![state.driver.clone()]
+    } else {
+        state.sockets.iter().map(|h| h.driver.clone()).collect()
+    };
+    for (i, driver) in drivers.iter().enumerate() {
+        let socket_cfg = crate::driver::sockets::config_for_socket(&new_cfg, i as u8 + 1);
+        // Load the charger profile before taking the driver lock
+        let Ok(backend) = crate::backend::from_config(&socket_cfg) else {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error":"apply failed"})),
+            );
+        };
+        driver.lock().await.apply_config(socket_cfg, backend);
     }
     // Try to persist to disk (best-effort)
     let mut saved_path: Option<&'static str> = None;
@@ -384,8 +411,14 @@ async fn events(State(state): State<AppState>) -> impl IntoResponse {
         crate::web::logs::logs_stream,
         sessions, dbus_dump, update_status, update_check, update_apply, update_releases,
         events, metrics, tibber_plan,
+        crate::web::sockets::list_sockets, crate::web::sockets::socket_status,
+        crate::web::sockets::socket_set_mode, crate::web::sockets::socket_set_startstop,
+        crate::web::sockets::socket_set_current, crate::web::sockets::socket_set_phases,
+        crate::web::sockets::socket_sessions,
+        crate::web::discovery::discovery,
+        crate::web::modbus::modbus_read, crate::web::modbus::modbus_write,
     ),
-    components(schemas(ModeBody, StartStopBody, SetCurrentBody, crate::web::logs::TailParams)),
+    components(schemas(ModeBody, StartStopBody, SetCurrentBody, crate::web::logs::TailParams, crate::web::modbus::WriteBody, crate::modbus::inspect::RegisterType)),
     tags((name = "phaeton", description = "Phaeton EV Charger API"))
 )]
 pub struct ApiDoc;
@@ -443,6 +476,9 @@ pub fn build_router(state: AppState) -> Router {
         .route("/api/config", get(get_config).put(put_config))
         .route("/api/config/schema", get(get_config_schema))
         .merge(logs::routes())
+        .merge(sockets::routes())
+        .merge(discovery::routes())
+        .merge(modbus::routes())
         .route("/api/sessions", get(sessions))
         .route("/api/dbus", get(dbus_dump))
         .route("/api/update/status", get(update_status))
@@ -462,14 +498,34 @@ pub fn build_router(state: AppState) -> Router {
     router
 }
 
-pub async fn serve(driver: Arc<Mutex<AlfenDriver>>, host: &str, port: u16) -> anyhow::Result<()> {
-    let snapshot_rx = {
-        let drv = driver.lock().await;
-        drv.subscribe_snapshot()
-    };
+/// Serve the API for the given socket drivers (ordered by socket number)
+pub async fn serve(
+    drivers: Vec<Arc<Mutex<AlfenDriver>>>,
+    host: &str,
+    port: u16,
+) -> anyhow::Result<()> {
+    let mut sockets = Vec::with_capacity(drivers.len());
+    for driver in drivers {
+        sockets.push(SocketHandle::new(driver).await);
+    }
+    let first = sockets
+        .first()
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("no socket drivers to serve"))?;
+    let api_token = first
+        .driver
+        .lock()
+        .await
+        .config()
+        .web
+        .api_token
+        .as_str()
+        .into();
     let state = AppState {
-        driver,
-        snapshot_rx,
+        driver: first.driver,
+        snapshot_rx: first.snapshot_rx,
+        sockets,
+        api_token,
     };
     let router = build_router(state);
 
@@ -0,0 +1,41 @@
+//! mDNS discovery endpoint (`/api/discovery`)
+
+use super::AppState;
+use axum::{
+    Json, Router,
+    extract::Query,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+pub struct DiscoveryQuery {
+    /// Browse duration in seconds (1..=10, default 3)
+    pub seconds: Option<u64>,
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(get, path = "/api/discovery", params(DiscoveryQuery), responses((status = 200))))]
+pub async fn discovery(Query(q): Query<DiscoveryQuery>) -> Response {
+    let duration = q.seconds.map_or(crate::discovery::DEFAULT_BROWSE, |s| {
+        std::time::Duration::from_secs(s.clamp(1, 10))
+    });
+    match crate::discovery::discover(duration).await {
+        Ok(stations) => Json(serde_json::json!({
+            "service": crate::discovery::SERVICE_TYPE,
+            "stations": stations,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"error": e.to_string(), "stations": []})),
+        )
+            .into_response(),
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/discovery", get(discovery))
+}
@@ -307,23 +307,24 @@ mod tests {
                     applied_current: 0.0,
                     station_max_current: 0.0,
                     device_instance: 0,
+                    socket: 1,
                     product_name: None,
                     firmware: None,
                     serial: None,
                     status: 0,
                     active_phases: 0,
-                    ac_power: 0.0,
-                    ac_current: 0.0,
-                    l1_voltage: 0.0,
-                    l2_voltage: 0.0,
-                    l3_voltage: 0.0,
-                    l1_current: 0.0,
-                    l2_current: 0.0,
-                    l3_current: 0.0,
-                    l1_power: 0.0,
-                    l2_power: 0.0,
-                    l3_power: 0.0,
-                    total_energy_kwh: 0.0,
+                    ac_power: None,
+                    ac_current: None,
+                    l1_voltage: None,
+                    l2_voltage: None,
+                    l3_voltage: None,
+                    l1_current: None,
+                    l2_current: None,
+                    l3_current: None,
+                    l1_power: None,
+                    l2_power: None,
+                    l3_power: None,
+                    total_energy_kwh: None,
                     pricing_currency: None,
                     energy_rate: None,
                     session: serde_json::json!({}),
@@ -335,9 +336,27 @@ mod tests {
                     modbus_connected: Some(false),
                     driver_state: "".into(),
                     poll_steps_ms: None,
+                    scn: None,
+                    setpoint_accepted: None,
+                    actual_applied_current: None,
+                    validity: None,
+                    meter: None,
+                    meter_ok: None,
+                    meter_age_ms: None,
+                    meter_health: None,
+                    thermal: None,
+                    station_operative: None,
+                    backoffice_connected: None,
+                    conditions: Vec::new(),
+                    mode3: None,
+                    clock: None,
+                    modbus_stats: None,
+                    main_fuse: None,
                 },
             ))
             .1,
+            sockets: vec![],
+            api_token: "".into(),
         };
         let router = axum::Router::new()
             .route("/api/logs/tail", get(logs_tail))
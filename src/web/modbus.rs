@@ -0,0 +1,232 @@
+//! Raw Modbus register inspector (`/api/modbus/read`, `/api/modbus/write`)
+//!
+//! Requests go through the socket driver's own Modbus connection, so no extra
+//! master slot is used. Both endpoints require `Authorization: Bearer
+//! <web.api_token>` and are disabled while the token is empty.
+
+use super::AppState;
+use crate::error::PhaetonError;
+use crate::modbus::inspect::{self, RegisterType};
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+pub struct ReadQuery {
+    /// Socket whose connection and slave IDs are used (default 1)
+    pub socket: Option<usize>,
+    /// Slave ID (default: the socket slave)
+    pub slave: Option<u8>,
+    pub address: u16,
+    /// Register count (default: one value of `type`)
+    pub count: Option<u16>,
+    #[serde(rename = "type", default)]
+    pub ty: RegisterType,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WriteBody {
+    pub socket: Option<usize>,
+    pub slave: Option<u8>,
+    pub address: u16,
+    #[serde(rename = "type", default)]
+    pub ty: RegisterType,
+    /// A number, or an array of numbers written to consecutive registers
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub value: serde_json::Value,
+}
+
+fn error(status: StatusCode, msg: impl std::fmt::Display) -> Response {
+    (status, Json(serde_json::json!({"error": msg.to_string()}))).into_response()
+}
+
+fn error_status(e: &PhaetonError) -> StatusCode {
+    match e {
+        PhaetonError::Validation { .. } => StatusCode::BAD_REQUEST,
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+/// Compare without an early exit on the first differing byte
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// The rejection of a request without the configured token, if any
+fn check_auth(headers: &HeaderMap, token: &str) -> Option<Response> {
+    if token.is_empty() {
+        return Some(error(
+            StatusCode::FORBIDDEN,
+            "Register inspector disabled; set web.api_token to enable it",
+        ));
+    }
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    (!token_matches(given.trim(), token))
+        .then(|| error(StatusCode::UNAUTHORIZED, "Invalid or missing API token"))
+}
+
+fn driver_of(state: &AppState, socket: Option<usize>) -> Option<&super::SocketHandle> {
+    socket
+        .unwrap_or(1)
+        .checked_sub(1)
+        .and_then(|i| state.sockets.get(i))
+}
+
+fn unknown_socket(socket: Option<usize>) -> Response {
+    error(
+        StatusCode::NOT_FOUND,
+        format!("unknown socket {}", socket.unwrap_or(1)),
+    )
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(get, path = "/api/modbus/read", params(ReadQuery), responses((status = 200), (status = 400), (status = 401), (status = 403), (status = 502))))]
+pub async fn modbus_read(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(q): Query<ReadQuery>,
+) -> Response {
+    if let Some(r) = check_auth(&headers, &state.api_token) {
+        return r;
+    }
+    let Some(handle) = driver_of(&state, q.socket) else {
+        return unknown_socket(q.socket);
+    };
+    let mut d = handle.driver.lock().await;
+    let slave = q.slave.unwrap_or(d.config().modbus.socket_slave_id);
+    let count = q.count.unwrap_or(q.ty.width().max(1) as u16);
+    let Some(modbus) = d.modbus_mut() else {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "Modbus not initialized");
+    };
+    match inspect::read(modbus, slave, q.address, count, q.ty).await {
+        Ok(read) => Json(read).into_response(),
+        Err(e) => error(error_status(&e), e),
+    }
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(post, path = "/api/modbus/write", request_body = WriteBody, responses((status = 200), (status = 400), (status = 401), (status = 403), (status = 502))))]
+pub async fn modbus_write(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<WriteBody>,
+) -> Response {
+    if let Some(r) = check_auth(&headers, &state.api_token) {
+        return r;
+    }
+    let Some(handle) = driver_of(&state, body.socket) else {
+        return unknown_socket(body.socket);
+    };
+    let mut d = handle.driver.lock().await;
+    let config = d.config().clone();
+    let slave = body.slave.unwrap_or(config.modbus.socket_slave_id);
+    let Some(modbus) = d.modbus_mut() else {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "Modbus not initialized");
+    };
+    match inspect::write(modbus, &config, slave, body.address, body.ty, &body.value).await {
+        Ok(registers) => Json(serde_json::json!({
+            "ok": true,
+            "slave": slave,
+            "address": body.address,
+            "registers": registers,
+        }))
+        .into_response(),
+        Err(e) => error(error_status(&e), e),
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/modbus/read", get(modbus_read))
+        .route("/api/modbus/write", post(modbus_write))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::AlfenDriver;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use tower::ServiceExt;
+
+    async fn state(token: &str) -> AppState {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut driver = AlfenDriver::new(rx, tx).await.unwrap();
+        let mut cfg = driver.config().clone();
+        cfg.web.api_token = token.to_string();
+        driver.update_config(cfg).unwrap();
+        let driver = Arc::new(Mutex::new(driver));
+        let handle = super::super::SocketHandle::new(driver.clone()).await;
+        AppState {
+            driver,
+            snapshot_rx: handle.snapshot_rx.clone(),
+            sockets: vec![handle],
+            api_token: token.into(),
+        }
+    }
+
+    async fn app(token: &str) -> Router {
+        routes().with_state(state(token).await)
+    }
+
+    async fn status(app: Router, auth: Option<&str>) -> StatusCode {
+        let mut req = Request::get("/api/modbus/read?address=1100&type=f32");
+        if let Some(auth) = auth {
+            req = req.header(header::AUTHORIZATION, auth);
+        }
+        let resp = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+        resp.status()
+    }
+
+    #[tokio::test]
+    async fn requires_configured_bearer_token() {
+        assert_eq!(status(app("").await, None).await, StatusCode::FORBIDDEN);
+        let app = app("s3cret").await;
+        assert_eq!(status(app.clone(), None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(app.clone(), Some("Bearer wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Authorized, but the test driver has no Modbus connection
+        assert_eq!(
+            status(app, Some("Bearer s3cret")).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_before_waiting_for_the_driver() {
+        let state = state("s3cret").await;
+        let _busy = state.driver.clone().lock_owned().await;
+        let app = routes().with_state(state);
+        assert_eq!(status(app, None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn config_api_does_not_expose_token() {
+        let app = super::super::build_router(state("s3cret").await);
+        let req = Request::get("/api/config").body(Body::empty()).unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let cfg: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(cfg["web"]["api_token"], "");
+    }
+}
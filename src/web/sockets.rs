@@ -0,0 +1,221 @@
+//! Per-socket API routes for multi-socket stations
+//!
+//! Sockets are addressed by their 1-based index (`/api/sockets/{socket}/...`).
+//! The unprefixed `/api/*` control endpoints keep addressing socket 1.
+
+use super::{AppState, ModeBody, SetCurrentBody, SetPhasesBody, StartStopBody};
+use crate::driver::{AlfenDriver, DriverSnapshot};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use std::sync::Arc;
+use tokio::sync::{Mutex, watch};
+
+/// Driver and snapshot channel of one socket
+#[derive(Clone)]
+pub struct SocketHandle {
+    pub driver: Arc<Mutex<AlfenDriver>>,
+    pub snapshot_rx: watch::Receiver<Arc<DriverSnapshot>>,
+}
+
+impl SocketHandle {
+    pub async fn new(driver: Arc<Mutex<AlfenDriver>>) -> Self {
+        let snapshot_rx = driver.lock().await.subscribe_snapshot();
+        Self {
+            driver,
+            snapshot_rx,
+        }
+    }
+}
+
+fn socket_handle(state: &AppState, socket: usize) -> Option<&SocketHandle> {
+    socket.checked_sub(1).and_then(|i| state.sockets.get(i))
+}
+
+fn not_found(socket: usize) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": format!("unknown socket {}", socket)})),
+    )
+        .into_response()
+}
+
+fn ok() -> Response {
+    (StatusCode::OK, Json(serde_json::json!({"ok":true}))).into_response()
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(get, path = "/api/sockets", responses((status = 200))))]
+pub async fn list_sockets(State(state): State<AppState>) -> impl IntoResponse {
+    let list: Vec<serde_json::Value> = state
+        .sockets
+        .iter()
+        .map(|h| {
+            let snap = h.snapshot_rx.borrow().clone();
+            serde_json::json!({
+                "socket": snap.socket,
+                "device_instance": snap.device_instance,
+                "mode": snap.mode,
+                "status": snap.status,
+                "ac_power": snap.ac_power,
+                "modbus_connected": snap.modbus_connected,
+            })
+        })
+        .collect();
+    Json(list)
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(get, path = "/api/sockets/{socket}/status", params(("socket" = usize, Path, description = "Socket number (1-based)")), responses((status = 200), (status = 404))))]
+pub async fn socket_status(State(state): State<AppState>, Path(socket): Path<usize>) -> Response {
+    match socket_handle(&state, socket) {
+        Some(h) => Json((*h.snapshot_rx.borrow().clone()).clone()).into_response(),
+        None => not_found(socket),
+    }
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(post, path = "/api/sockets/{socket}/mode", params(("socket" = usize, Path, description = "Socket number (1-based)")), request_body = ModeBody, responses((status = 200), (status = 404))))]
+pub async fn socket_set_mode(
+    State(state): State<AppState>,
+    Path(socket): Path<usize>,
+    Json(body): Json<ModeBody>,
+) -> Response {
+    match socket_handle(&state, socket) {
+        Some(h) => {
+            h.driver.lock().await.set_mode(body.mode).await;
+            ok()
+        }
+        None => not_found(socket),
+    }
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(post, path = "/api/sockets/{socket}/startstop", params(("socket" = usize, Path, description = "Socket number (1-based)")), request_body = StartStopBody, responses((status = 200), (status = 404))))]
+pub async fn socket_set_startstop(
+    State(state): State<AppState>,
+    Path(socket): Path<usize>,
+    Json(body): Json<StartStopBody>,
+) -> Response {
+    match socket_handle(&state, socket) {
+        Some(h) => {
+            let v = body
+                .value
+                .or_else(|| body.enabled.map(|b| if b { 1 } else { 0 }))
+                .unwrap_or(0);
+            h.driver.lock().await.set_start_stop(v).await;
+            ok()
+        }
+        None => not_found(socket),
+    }
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(post, path = "/api/sockets/{socket}/set_current", params(("socket" = usize, Path, description = "Socket number (1-based)")), request_body = SetCurrentBody, responses((status = 200), (status = 404))))]
+pub async fn socket_set_current(
+    State(state): State<AppState>,
+    Path(socket): Path<usize>,
+    Json(body): Json<SetCurrentBody>,
+) -> Response {
+    match socket_handle(&state, socket) {
+        Some(h) => {
+            h.driver.lock().await.set_intended_current(body.amps).await;
+            ok()
+        }
+        None => not_found(socket),
+    }
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(post, path = "/api/sockets/{socket}/phases", params(("socket" = usize, Path, description = "Socket number (1-based)")), request_body = SetPhasesBody, responses((status = 200), (status = 404))))]
+pub async fn socket_set_phases(
+    State(state): State<AppState>,
+    Path(socket): Path<usize>,
+    Json(body): Json<SetPhasesBody>,
+) -> Response {
+    match socket_handle(&state, socket) {
+        Some(h) => {
+            h.driver.lock().await.set_phases(body.phases).await;
+            ok()
+        }
+        None => not_found(socket),
+    }
+}
+
+#[cfg_attr(feature = "openapi", utoipa::path(get, path = "/api/sockets/{socket}/sessions", params(("socket" = usize, Path, description = "Socket number (1-based)")), responses((status = 200), (status = 404))))]
+pub async fn socket_sessions(State(state): State<AppState>, Path(socket): Path<usize>) -> Response {
+    match socket_handle(&state, socket) {
+        Some(h) => Json(h.driver.lock().await.sessions_snapshot()).into_response(),
+        None => not_found(socket),
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/sockets", get(list_sockets))
+        .route("/api/sockets/{socket}/status", get(socket_status))
+        .route("/api/sockets/{socket}/mode", post(socket_set_mode))
+        .route(
+            "/api/sockets/{socket}/startstop",
+            post(socket_set_startstop),
+        )
+        .route(
+            "/api/sockets/{socket}/set_current",
+            post(socket_set_current),
+        )
+        .route("/api/sockets/{socket}/phases", post(socket_set_phases))
+        .route("/api/sockets/{socket}/sessions", get(socket_sessions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn unknown_socket_returns_404_and_list_covers_sockets() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let driver = Arc::new(Mutex::new(AlfenDriver::new(rx, tx).await.unwrap()));
+        let handle = SocketHandle::new(driver.clone()).await;
+        let state = AppState {
+            driver,
+            snapshot_rx: handle.snapshot_rx.clone(),
+            sockets: vec![handle],
+            api_token: "".into(),
+        };
+        let app = routes().with_state(state);
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::get("/api/sockets/2/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::get("/api/sockets/1/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = app
+            .oneshot(Request::get("/api/sockets").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(v.as_array().unwrap().len(), 1);
+        assert_eq!(v[0]["socket"], 1);
+    }
+}
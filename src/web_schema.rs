@@ -10,33 +10,33 @@ pub fn build_ui_schema() -> Value {
     json!({
         "sections": {
             "modbus": {"title": "Modbus", "type": "object", "fields": {
-                "ip": {"type": "string", "format": "ipv4", "title": "Charger IP"},
+                "ip": {"type": "string", "title": "Charger IP, hostname or auto:<serial>"},
                 "port": {"type": "integer", "min": 1, "max": 65535, "title": "Port"},
                 "socket_slave_id": {"type": "integer", "min": 1, "max": 247, "title": "Socket slave ID"},
-                "station_slave_id": {"type": "integer", "min": 1, "max": 247, "title": "Station slave ID"}
+                "station_slave_id": {"type": "integer", "min": 1, "max": 247, "title": "Station slave ID"},
+                "sockets": {"type": "integer", "min": 0, "max": 2, "title": "Number of sockets (0 = detect)"},
+                "capture_file": {"type": "string", "title": "Traffic capture file (empty = off)"},
+                "keepalive_s": {"type": "integer", "min": 0, "max": 55, "title": "Keepalive after idle (s, 0 = off)"},
+                "backoff_max_s": {"type": "number", "min": 0.0, "step": 0.1, "title": "Max reconnect backoff (s)"},
+                "open_circuit_after": {"type": "integer", "min": 0, "title": "Failed connects before pausing (0 = never)"},
+                "open_circuit_s": {"type": "number", "min": 0.0, "step": 1, "title": "Pause after max retries (s)"}
+            }},
+            "charger": {"title": "Charger backend", "type": "object", "fields": {
+                "backend": {"type": "enum", "values": ["alfen","profile"], "title": "Backend"},
+                "profile": {"type": "string", "title": "Register-map profile path"}
             }},
             "defaults": {"title": "Defaults", "type": "object", "fields": {
                 "intended_set_current": {"type": "number", "min": 0.0, "max": 80.0, "step": 0.1, "title": "Intended set current (A)"},
                 "station_max_current": {"type": "number", "min": 0.0, "max": 80.0, "step": 0.1, "title": "Station max current (A)"}
             }},
-            "controls": {"title": "Controls & Safety", "type": "object", "fields": {
-                "current_tolerance": {"type": "number", "min": 0.0, "step": 0.01, "title": "Verification tolerance (A)"},
-                "update_difference_threshold": {"type": "number", "min": 0.0, "step": 0.01, "title": "Update threshold (A)"},
-                "verification_delay": {"type": "number", "min": 0.0, "step": 0.01, "title": "Verification delay (s)"},
-                "retry_delay": {"type": "number", "min": 0.0, "step": 0.01, "title": "Retry delay (s)"},
-                "max_retries": {"type": "integer", "min": 1, "title": "Max retries"},
-                "watchdog_interval_seconds": {"type": "integer", "min": 1, "title": "Watchdog interval (s)"},
-                "max_set_current": {"type": "number", "min": 0.01, "step": 0.1, "title": "Max set current (A)"},
-                "min_set_current": {"type": "number", "min": 0.0, "step": 0.1, "title": "Min set current (A)"},
-                "min_charge_duration_seconds": {"type": "integer", "min": 0, "title": "Min charge duration (s)"},
-                "current_update_interval": {"type": "integer", "min": 0, "title": "Current update interval (ms)"},
-                "verify_delay": {"type": "integer", "min": 0, "title": "Verify delay (ms)"},
-                "ev_reporting_lag_ms": {"type": "integer", "min": 0, "title": "EV reporting lag (ms)"},
-                "pv_excess_ema_alpha": {"type": "number", "min": 0.0, "max": 1.0, "step": 0.01, "title": "PV excess EMA alpha"},
-                "phase_switch_grace_seconds": {"type": "integer", "min": 0, "title": "Phase switch grace (s)"},
-                "phase_switch_settle_seconds": {"type": "integer", "min": 0, "title": "Phase switch settle (s)"},
-                "auto_phase_switch": {"type": "boolean", "title": "Auto 1P/3P switching in Auto mode"},
-                "auto_phase_hysteresis_watts": {"type": "number", "min": 0.0, "step": 1.0, "title": "Auto phase hysteresis (W)"}
+            "controls": controls_section(),
+            "scheduler": {"title": "Poll scheduling", "type": "object", "advanced": true, "fields": {
+                "status_interval_ms": {"type": "integer", "min": 0, "title": "Status interval (ms, 0 = every poll)"},
+                "validity_interval_ms": {"type": "integer", "min": 0, "title": "Validity/SCN interval (ms)"},
+                "station_max_interval_ms": {"type": "integer", "min": 0, "title": "Station max current interval (ms)"},
+                "temperature_interval_ms": {"type": "integer", "min": 0, "title": "Temperature interval (ms)"},
+                "identity_interval_ms": {"type": "integer", "min": 0, "title": "Identity interval (ms)"},
+                "coalesce_max_gap": {"type": "integer", "min": 0, "max": 124, "title": "Max register gap when coalescing reads"}
             }},
             "logging": {"title": "Logging", "type": "object", "fields": {
                 "level": {"type": "enum", "values": ["DEBUG","INFO","WARNING","ERROR","CRITICAL"], "title": "Level"},
@@ -85,25 +85,7 @@ pub fn build_ui_schema() -> Value {
                     "end_time": {"type": "time", "title": "End time"}
                 }}}
             }},
-            "registers": {"title": "Registers", "type": "object", "fields": {
-                "voltages": {"type": "integer", "min": 0, "title": "Voltages base register"},
-                "currents": {"type": "integer", "min": 0, "title": "Currents base register"},
-                "power": {"type": "integer", "min": 0, "title": "Power register"},
-                "energy": {"type": "integer", "min": 0, "title": "Energy register"},
-                "status": {"type": "integer", "min": 0, "title": "Status string register"},
-                "amps_config": {"type": "integer", "min": 0, "title": "Amps config register"},
-                "phases": {"type": "integer", "min": 0, "title": "Phases register"},
-                "firmware_version": {"type": "integer", "min": 0, "title": "Firmware version register"},
-                "firmware_version_count": {"type": "integer", "min": 0, "title": "Firmware version count"},
-                "station_serial": {"type": "integer", "min": 0, "title": "Station serial register"},
-                "station_serial_count": {"type": "integer", "min": 0, "title": "Station serial count"},
-                "manufacturer": {"type": "integer", "min": 0, "title": "Manufacturer register"},
-                "manufacturer_count": {"type": "integer", "min": 0, "title": "Manufacturer count"},
-                "platform_type": {"type": "integer", "min": 0, "title": "Platform type register"},
-                "platform_type_count": {"type": "integer", "min": 0, "title": "Platform type count"},
-                "station_max_current": {"type": "integer", "min": 0, "title": "Station max current (reg 1100)"},
-                "station_status": {"type": "integer", "min": 0, "title": "Station status register"}
-            }},
+            "registers": registers_section(),
             "web": {"title": "Web UI", "type": "object", "fields": {
                 "host": {"type": "string", "title": "Bind address"},
                 "port": {"type": "integer", "min": 1, "max": 65535, "title": "Port"}
@@ -123,3 +105,101 @@ pub fn build_ui_schema() -> Value {
         }
     })
 }
+
+fn controls_section() -> Value {
+    let mut section = json!({"title": "Controls & Safety", "type": "object", "fields": {
+        "current_tolerance": {"type": "number", "min": 0.0, "step": 0.01, "title": "Verification tolerance (A)"},
+        "update_difference_threshold": {"type": "number", "min": 0.0, "step": 0.01, "title": "Update threshold (A)"},
+        "verification_delay": {"type": "number", "min": 0.0, "step": 0.01, "title": "Verification delay (s)"},
+        "retry_delay": {"type": "number", "min": 0.0, "step": 0.01, "title": "Retry delay (s)"},
+        "max_retries": {"type": "integer", "min": 1, "title": "Max retries"},
+        "watchdog_interval_seconds": {"type": "integer", "min": 1, "title": "Watchdog interval (s)"},
+        "validity_reassert_margin_seconds": {"type": "integer", "min": 1, "title": "Reassert when setpoint validity below (s)"},
+        "max_set_current": {"type": "number", "min": 0.01, "step": 0.1, "title": "Max set current (A)"},
+        "min_set_current": {"type": "number", "min": 0.0, "step": 0.1, "title": "Min set current (A)"},
+        "min_charge_duration_seconds": {"type": "integer", "min": 0, "title": "Min charge duration (s)"},
+        "current_update_interval": {"type": "integer", "min": 0, "title": "Current update interval (ms)"},
+        "ev_reporting_lag_ms": {"type": "integer", "min": 0, "title": "EV reporting lag (ms)"},
+        "meter_stale_threshold_ms": {"type": "integer", "min": 0, "title": "Meter stale after (ms)"},
+        "pv_excess_ema_alpha": {"type": "number", "min": 0.0, "max": 1.0, "step": 0.01, "title": "PV excess EMA alpha"},
+        "phase_switch_grace_seconds": {"type": "integer", "min": 0, "title": "Phase switch grace (s)"},
+        "phase_switch_settle_seconds": {"type": "integer", "min": 0, "title": "Phase switch settle (s)"},
+        "auto_phase_switch": {"type": "boolean", "title": "Auto 1P/3P switching in Auto mode"},
+        "auto_phase_hysteresis_watts": {"type": "number", "min": 0.0, "step": 1.0, "title": "Auto phase hysteresis (W)"},
+        "temperature_warning_c": {"type": "number", "step": 0.5, "title": "Temperature warning (°C)"},
+        "temperature_derate_c": {"type": "number", "step": 0.5, "title": "Temperature derating starts (°C)"},
+        "temperature_stop_c": {"type": "number", "step": 0.5, "title": "Temperature stop charging (°C)"},
+        "clock_check_interval_seconds": {"type": "integer", "min": 1, "title": "Station clock check interval (s)"},
+        "clock_drift_warning_seconds": {"type": "integer", "min": 0, "title": "Clock drift warning (s)"},
+        "phase_rotation": {"type": "enum", "values": ["L1L2L3","L2L3L1","L3L1L2","L1L3L2","L3L2L1","L2L1L3"], "title": "Phase rotation (installation phases of charger L1/L2/L3)"}
+    }});
+    // Split off to stay within the json! macro recursion limit
+    if let (Some(fields), Value::Object(extra)) =
+        (section["fields"].as_object_mut(), pv_control_fields())
+    {
+        fields.extend(extra);
+    }
+    section
+}
+
+/// Watts/amps conversion, Auto controller and home battery fields
+fn pv_control_fields() -> Value {
+    json!({
+        "nominal_voltage": {"type": "number", "min": 50.0, "max": 500.0, "step": 1.0, "title": "Nominal phase voltage fallback (V)"},
+        "line_to_line": {"type": "boolean", "title": "Single-phase charging line-to-line (208/240 V)"},
+        "voltage_ema_alpha": {"type": "number", "min": 0.0, "max": 1.0, "step": 0.01, "title": "Voltage EMA alpha"},
+        "auto_controller": {"type": "enum", "values": ["pv_excess","grid_zero"], "title": "Auto mode controller"},
+        "grid_setpoint_w": {"type": "number", "step": 10.0, "title": "Grid-zero setpoint (W, + = import)"},
+        "grid_kp": {"type": "number", "min": 0.0, "step": 0.05, "title": "Grid-zero proportional gain"},
+        "grid_ki": {"type": "number", "min": 0.0, "step": 0.01, "title": "Grid-zero integral gain (1/s)"},
+        "grid_deadband_w": {"type": "number", "min": 0.0, "step": 10.0, "title": "Grid-zero deadband (W)"},
+        "grid_ramp_a_per_s": {"type": "number", "min": 0.0, "step": 0.5, "title": "Grid-zero ramp limit (A/s)"},
+        "grid_start_margin_a": {"type": "number", "min": 0.0, "step": 0.5, "title": "Grid-zero start margin above minimum (A)"},
+        "battery_policy": {"type": "enum", "values": ["ev_first","battery_first","battery_buffer"], "title": "Home battery policy for PV charging"},
+        "battery_priority_soc": {"type": "number", "min": 0.0, "max": 100.0, "step": 1.0, "title": "Battery first below SoC (%)"},
+        "battery_buffer_soc": {"type": "number", "min": 0.0, "max": 100.0, "step": 1.0, "title": "EV may discharge battery above SoC (%)"},
+        "battery_max_discharge_w": {"type": "number", "min": 0.0, "step": 100.0, "title": "EV battery discharge limit (W)"},
+        "main_fuse_a": {"type": "number", "min": 0.0, "step": 1.0, "title": "Main fuse per phase (A, 0 = off)"},
+        "main_fuse_margin_a": {"type": "number", "min": 0.0, "step": 0.5, "title": "Main fuse safety margin (A)"},
+        "main_fuse_resume_a": {"type": "number", "min": 0.0, "step": 0.5, "title": "Main fuse resume headroom above minimum (A)"},
+        "main_fuse_hold_s": {"type": "number", "min": 0.0, "step": 1.0, "title": "Main fuse resume hold time (s)"},
+        "main_fuse_service": {"type": "string", "title": "Grid meter D-Bus service (empty = system)"}
+    })
+}
+
+fn registers_section() -> Value {
+    json!({"title": "Registers", "type": "object", "fields": {
+        "voltages": {"type": "integer", "min": 0, "title": "Voltages base register"},
+        "currents": {"type": "integer", "min": 0, "title": "Currents base register"},
+        "power": {"type": "integer", "min": 0, "title": "Power register"},
+        "energy": {"type": "integer", "min": 0, "title": "Energy register"},
+        "status": {"type": "integer", "min": 0, "title": "Status string register"},
+        "amps_config": {"type": "integer", "min": 0, "title": "Amps config register"},
+        "phases": {"type": "integer", "min": 0, "title": "Phases register"},
+        "firmware_version": {"type": "integer", "min": 0, "title": "Firmware version register"},
+        "firmware_version_count": {"type": "integer", "min": 0, "title": "Firmware version count"},
+        "station_serial": {"type": "integer", "min": 0, "title": "Station serial register"},
+        "station_serial_count": {"type": "integer", "min": 0, "title": "Station serial count"},
+        "manufacturer": {"type": "integer", "min": 0, "title": "Manufacturer register"},
+        "manufacturer_count": {"type": "integer", "min": 0, "title": "Manufacturer count"},
+        "platform_type": {"type": "integer", "min": 0, "title": "Platform type register"},
+        "platform_type_count": {"type": "integer", "min": 0, "title": "Platform type count"},
+        "station_max_current": {"type": "integer", "min": 0, "title": "Station max current (reg 1100)"},
+        "station_status": {"type": "integer", "min": 0, "title": "Station status register"},
+        "temperature": {"type": "integer", "min": 0, "title": "Board temperature register (1102)"},
+        "station_clock": {"type": "integer", "min": 0, "title": "Station clock block start (168)"},
+        "ocpp_state": {"type": "integer", "min": 0, "title": "OCPP back office state register (1104)"},
+        "availability": {"type": "integer", "min": 0, "title": "Availability register (1200)"},
+        "actual_applied_current": {"type": "integer", "min": 0, "title": "Actual applied max current register (1206)"},
+        "setpoint_accounted_for": {"type": "integer", "min": 0, "title": "Setpoint accounted for register (1214)"},
+        "max_current_valid_time": {"type": "integer", "min": 0, "title": "Max current valid time register (1208)"},
+        "safe_current": {"type": "integer", "min": 0, "title": "Safe current register (1212)"},
+        "current_control": {"type": "enum", "values": ["socket","scn"], "title": "Current control (socket or SCN per-phase)"},
+        "scn_consumption": {"type": "integer", "min": 0, "title": "SCN total consumption register (1405)"},
+        "scn_actual_max_current": {"type": "integer", "min": 0, "title": "SCN actual max current register (1411)"},
+        "scn_max_current": {"type": "integer", "min": 0, "title": "SCN max current per phase register (1417)"},
+        "scn_max_current_enable": {"type": "integer", "min": 0, "title": "SCN max current enable register (1431)"},
+        "meter_block": {"type": "integer", "min": 0, "title": "Meter block start register (300)"},
+        "full_meter_telemetry": {"type": "boolean", "title": "Read full meter telemetry (300..425)"}
+    }})
+}
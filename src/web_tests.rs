@@ -23,18 +23,18 @@ async fn test_state_async() -> AppState {
         serial: None,
         status: 0,
         active_phases: 0,
-        ac_power: 0.0,
-        ac_current: 0.0,
-        l1_voltage: 0.0,
-        l2_voltage: 0.0,
-        l3_voltage: 0.0,
-        l1_current: 0.0,
-        l2_current: 0.0,
-        l3_current: 0.0,
-        l1_power: 0.0,
-        l2_power: 0.0,
-        l3_power: 0.0,
-        total_energy_kwh: 0.0,
+        ac_power: None,
+        ac_current: None,
+        l1_voltage: None,
+        l2_voltage: None,
+        l3_voltage: None,
+        l1_current: None,
+        l2_current: None,
+        l3_current: None,
+        l1_power: None,
+        l2_power: None,
+        l3_power: None,
+        total_energy_kwh: None,
         pricing_currency: None,
         energy_rate: None,
         session: serde_json::json!({}),
@@ -47,7 +47,12 @@ async fn test_state_async() -> AppState {
         driver_state: "Initializing".to_string(),
     }));
     let _ = snapshot_tx;
-    AppState { driver: Arc::new(Mutex::new(driver)), snapshot_rx }
+    AppState {
+        driver: Arc::new(Mutex::new(driver)),
+        snapshot_rx,
+        sockets: vec![],
+        api_token: "".into(),
+    }
 }
 
 #[tokio::test]
@@ -0,0 +1,108 @@
+use phaeton::backend::{self, ChargerBackend, ChargerProfile, ProfileBackend};
+use phaeton::config::Config;
+use phaeton::driver::modbus_like::ModbusLike;
+use std::collections::HashMap;
+
+/// Register map backed by a hash map; writes land in the same map
+#[derive(Default)]
+struct MapModbus {
+    regs: HashMap<(u8, u16), u16>,
+}
+
+impl MapModbus {
+    fn set(mut self, slave: u8, addr: u16, values: &[u16]) -> Self {
+        for (i, v) in values.iter().enumerate() {
+            self.regs.insert((slave, addr + i as u16), *v);
+        }
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusLike for MapModbus {
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+    async fn read_holding_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        count: u16,
+    ) -> phaeton::Result<Vec<u16>> {
+        (address..address + count)
+            .map(|a| {
+                self.regs
+                    .get(&(slave_id, a))
+                    .copied()
+                    .ok_or_else(|| phaeton::PhaetonError::modbus("Illegal data address"))
+            })
+            .collect()
+    }
+    async fn write_multiple_registers(
+        &mut self,
+        slave_id: u8,
+        address: u16,
+        values: &[u16],
+    ) -> phaeton::Result<()> {
+        for (i, v) in values.iter().enumerate() {
+            self.regs.insert((slave_id, address + i as u16), *v);
+        }
+        Ok(())
+    }
+}
+
+fn example_profile() -> ProfileBackend {
+    let yaml = std::fs::read_to_string("profiles/example_wallbox.yaml").unwrap();
+    ProfileBackend::new(ChargerProfile::from_yaml(&yaml).unwrap())
+}
+
+#[tokio::test]
+async fn example_profile_reads_and_writes() {
+    let backend = example_profile();
+    assert_eq!(backend.name(), "Example wallbox");
+    assert!(!backend.alfen_extensions());
+
+    let mut modbus = MapModbus::default()
+        .set(1, 100, &[2301, 2302, 2303, 1600, 0, 0, 0, 3680, 0, 12_345])
+        .set(1, 120, &[3])
+        .set(1, 130, &[0x312E, 0x3200, 0, 0, 0, 0, 0, 0])
+        .set(1, 138, &[0x4142, 0x4300, 0, 0, 0, 0, 0, 0, 0, 0])
+        .set(1, 148, &[32]);
+
+    let m = backend.read_measurements(&mut modbus).await.unwrap();
+    assert!((m.voltages[0].unwrap() - 230.1).abs() < 1e-9);
+    assert!((m.currents[0].unwrap() - 16.0).abs() < 1e-9);
+    assert_eq!(m.powers, [None, None, None]);
+    assert_eq!(m.total_power, Some(3680.0));
+    assert!((m.energy_kwh.unwrap() - 12.345).abs() < 1e-9);
+
+    let status = backend.read_status(&mut modbus).await.unwrap();
+    assert_eq!((status.raw.as_str(), status.code), ("3", 2));
+
+    let id = backend.read_identity(&mut modbus).await.unwrap();
+    assert_eq!(id.manufacturer, "Example");
+    assert_eq!(id.firmware, "1.2");
+    assert_eq!(id.serial, "ABC");
+    assert_eq!(id.max_current, Some(32.0));
+
+    backend.write_current(&mut modbus, 10.5).await.unwrap();
+    assert_eq!(modbus.regs[&(1, 200)], 105);
+    backend.write_phases(&mut modbus, 1).await.unwrap();
+    assert_eq!(modbus.regs[&(1, 201)], 1);
+}
+
+#[test]
+fn from_config_selects_backend() {
+    let mut cfg = Config::default();
+    assert_eq!(backend::from_config(&cfg).unwrap().name(), "alfen");
+
+    cfg.charger.backend = "profile".to_string();
+    cfg.charger.profile = "profiles/example_wallbox.yaml".to_string();
+    assert_eq!(
+        backend::from_config(&cfg).unwrap().name(),
+        "Example wallbox"
+    );
+
+    cfg.charger.profile = "profiles/missing.yaml".to_string();
+    assert!(backend::from_config(&cfg).is_err());
+}
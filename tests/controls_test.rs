@@ -22,7 +22,7 @@ async fn manual_mode_respects_limits() {
             0.0,
             Some(0.0),
             &cfg,
-            3,
+            &[230.0; 3],
         )
         .await
         .unwrap();
@@ -42,7 +42,7 @@ async fn stopped_returns_zero() {
             0.0,
             Some(0.0),
             &cfg,
-            3,
+            &[230.0; 3],
         )
         .await
         .unwrap();
@@ -63,7 +63,7 @@ async fn auto_mode_converts_solar_power() {
             0.0,
             Some(6900.0),
             &cfg,
-            3,
+            &[230.0; 3],
         )
         .await
         .unwrap();
@@ -74,7 +74,8 @@ async fn auto_mode_converts_solar_power() {
 async fn scheduled_mode_respects_schedule() {
     let controls = ChargingControls::new();
     let mut cfg = base_config();
-    let weekday = Utc::now().weekday().num_days_from_monday() as u8;
+    let now = Utc::now();
+    let weekday = now.weekday().num_days_from_monday() as u8;
     cfg.schedule.items = vec![ScheduleItem {
         active: true,
         days: vec![weekday],
@@ -92,10 +93,10 @@ async fn scheduled_mode_respects_schedule() {
             StartStopState::Enabled,
             6.0,
             25.0,
-            0.0,
+            now.timestamp() as f64,
             Some(0.0),
             &cfg,
-            3,
+            &[230.0; 3],
         )
         .await
         .unwrap();
@@ -110,10 +111,10 @@ async fn scheduled_mode_respects_schedule() {
             StartStopState::Enabled,
             6.0,
             25.0,
-            0.0,
+            now.timestamp() as f64,
             Some(0.0),
             &cfg,
-            3,
+            &[230.0; 3],
         )
         .await
         .unwrap();
@@ -1,6 +1,7 @@
 use phaeton::config::ModbusConfig;
 use phaeton::modbus::{
-    ModbusClient, decode_32bit_float, decode_64bit_float, decode_string, encode_32bit_float,
+    ModbusClient, decode_32bit_float, decode_32bit_float_opt, decode_64bit_float,
+    decode_64bit_float_opt, decode_string, encode_32bit_float,
 };
 
 #[test]
@@ -22,6 +23,15 @@ fn decode_64bit_float_happy_path() {
     assert!((decode_64bit_float(&regs).unwrap() - 1.0).abs() < f64::EPSILON);
 }
 
+#[test]
+fn unavailable_registers_decode_to_none() {
+    assert_eq!(decode_32bit_float_opt(&[0xFFFF, 0xFFFF]), None);
+    assert_eq!(decode_64bit_float_opt(&[0xFFFF; 4]), None);
+    assert_eq!(decode_32bit_float_opt(&[0x3F80]), None);
+    assert_eq!(decode_32bit_float_opt(&[0, 0]), Some(0.0));
+    assert_eq!(decode_64bit_float_opt(&[0x3FF0, 0, 0, 0]), Some(1.0));
+}
+
 #[test]
 fn encode_32bit_float_happy_path() {
     assert_eq!(encode_32bit_float(1.0), [0x3F80, 0x0000]);
@@ -1,4 +1,4 @@
-use phaeton::session::ChargingSessionManager;
+use phaeton::session::{ChargingSessionManager, SessionStatus};
 
 #[test]
 fn start_update_end_session() {
@@ -24,3 +24,37 @@ fn start_update_end_session() {
         .unwrap();
     assert!((energy - 2.0).abs() < 1e-6);
 }
+
+#[test]
+fn interrupt_session_keeps_energy_without_reading() {
+    let mut mgr = ChargingSessionManager::default();
+    mgr.start_session(10.0).unwrap();
+    mgr.update(7000.0, 11.5).unwrap();
+    assert!(mgr.interrupt_session(None).is_ok());
+    let last = mgr.last_session.as_ref().unwrap();
+    assert!(matches!(last.status, SessionStatus::Interrupted));
+    assert!((last.energy_delivered_kwh - 1.5).abs() < 1e-6);
+    assert!(mgr.interrupt_session(None).is_err());
+}
+
+#[test]
+fn events_are_recorded_on_the_active_session() {
+    let mut mgr = ChargingSessionManager::default();
+    assert!(!mgr.record_event("station_reboot", "uptime 12 s"));
+    mgr.start_session(10.0).unwrap();
+    assert!(mgr.record_event("station_reboot", "uptime 12 s"));
+    mgr.end_session(11.0).unwrap();
+    let last = mgr.last_session.as_ref().unwrap();
+    assert_eq!(last.events.len(), 1);
+    assert_eq!(last.events[0].kind, "station_reboot");
+
+    // Sessions persisted before events existed still restore
+    let mut state = mgr.get_state();
+    state["last_session"]
+        .as_object_mut()
+        .unwrap()
+        .remove("events");
+    let mut restored = ChargingSessionManager::default();
+    restored.restore_state(state).unwrap();
+    assert!(restored.last_session.unwrap().events.is_empty());
+}
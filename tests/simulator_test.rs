@@ -0,0 +1,208 @@
+use phaeton::config::ModbusConfig;
+use phaeton::driver::modbus_like::ModbusLike;
+use phaeton::modbus::{
+    ModbusConnectionManager, decode_32bit_float, decode_string, encode_32bit_float,
+};
+use phaeton::simulator::{self, SimCommand, Simulator, SimulatorConfig};
+use std::time::Duration;
+
+async fn start(cfg: SimulatorConfig) -> (Simulator, ModbusConnectionManager) {
+    let sim = Simulator::new(cfg);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(simulator::serve(listener, sim.clone()));
+    let mcfg = ModbusConfig {
+        ip: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    let manager = ModbusConnectionManager::new(&mcfg, Duration::from_millis(50));
+    (sim, manager)
+}
+
+#[tokio::test]
+async fn identity_and_station_registers_over_tcp() {
+    let (_sim, mut m) = start(SimulatorConfig::default()).await;
+    let manufacturer = m.read_holding_registers(200, 117, 5).await.unwrap();
+    assert_eq!(decode_string(&manufacturer, None).unwrap(), "Alfen NV");
+    let max = m.read_holding_registers(200, 1100, 2).await.unwrap();
+    assert!((decode_32bit_float(&max).unwrap() - 32.0).abs() < f32::EPSILON);
+    let sockets = m.read_holding_registers(200, 1105, 1).await.unwrap();
+    assert_eq!(sockets, vec![1]);
+    // Unmapped registers are rejected with a Modbus exception
+    assert!(m.read_holding_registers(200, 1106, 1).await.is_err());
+}
+
+#[tokio::test]
+async fn car_follows_written_setpoint_and_reports_mode3() {
+    let (sim, mut m) = start(SimulatorConfig {
+        car_lag_seconds: 0.0,
+        ..Default::default()
+    })
+    .await;
+    sim.apply(&SimCommand::PlugIn {
+        socket: 1,
+        max_current: Some(16.0),
+    })
+    .unwrap();
+    m.write_multiple_registers(1, 1210, &encode_32bit_float(10.0))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let state = m.read_holding_registers(1, 1201, 5).await.unwrap();
+    assert_eq!(decode_string(&state, None).unwrap(), "C2");
+    let currents = m.read_holding_registers(1, 320, 6).await.unwrap();
+    assert!((decode_32bit_float(&currents[0..2]).unwrap() - 10.0).abs() < 0.01);
+    let applied = m.read_holding_registers(1, 1206, 2).await.unwrap();
+    assert!((decode_32bit_float(&applied).unwrap() - 10.0).abs() < f32::EPSILON);
+
+    sim.apply(&SimCommand::CarFull { socket: 1 }).unwrap();
+    let state = m.read_holding_registers(1, 1201, 5).await.unwrap();
+    assert_eq!(decode_string(&state, None).unwrap(), "B2");
+    sim.apply(&SimCommand::Fault { socket: 1 }).unwrap();
+    let state = m.read_holding_registers(1, 1201, 5).await.unwrap();
+    assert_eq!(decode_string(&state, None).unwrap(), "F");
+}
+
+#[tokio::test]
+async fn falls_back_to_safe_current_after_validity_expires() {
+    let (sim, mut m) = start(SimulatorConfig {
+        validity_seconds: 1,
+        safe_current: 8.0,
+        car_lag_seconds: 0.0,
+        ..Default::default()
+    })
+    .await;
+    sim.apply(&SimCommand::PlugIn {
+        socket: 1,
+        max_current: None,
+    })
+    .unwrap();
+    m.write_multiple_registers(1, 1210, &encode_32bit_float(16.0))
+        .await
+        .unwrap();
+    assert_eq!(sim.applied_max_current(1), Some(16.0));
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let applied = m.read_holding_registers(1, 1206, 2).await.unwrap();
+    assert!((decode_32bit_float(&applied).unwrap() - 8.0).abs() < f32::EPSILON);
+    let socket = sim.socket(1).unwrap();
+    assert!((socket.current - 8.0).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn third_master_is_refused() {
+    let sim = Simulator::new(SimulatorConfig::default());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(simulator::serve(listener, sim));
+    let _a = tokio_modbus::client::tcp::connect(addr).await.unwrap();
+    let _b = tokio_modbus::client::tcp::connect(addr).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let mut c = tokio_modbus::client::tcp::connect(addr).await.unwrap();
+    use tokio_modbus::prelude::*;
+    c.set_slave(Slave(200));
+    let res = tokio::time::timeout(Duration::from_secs(2), c.read_holding_registers(1105, 1))
+        .await
+        .unwrap();
+    assert!(res.is_err() || res.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn keepalive_stats_and_graceful_close() {
+    let sim = Simulator::new(SimulatorConfig::default());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(simulator::serve(listener, sim));
+    let mcfg = ModbusConfig {
+        ip: "127.0.0.1".to_string(),
+        port,
+        keepalive_s: 1,
+        ..Default::default()
+    };
+    let mut m = ModbusConnectionManager::new(&mcfg, Duration::from_millis(50));
+
+    // Not connected yet: nothing to keep alive
+    assert!(!m.keepalive().await.unwrap());
+    m.read_holding_registers(200, 1100, 2).await.unwrap();
+    assert!(!m.keepalive().await.unwrap());
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert!(m.keepalive().await.unwrap());
+
+    let stats = m.connection_stats().unwrap();
+    assert!(stats.connected && !stats.slots_full);
+    assert_eq!(
+        (stats.connects, stats.reconnect_count, stats.keepalives),
+        (1, 0, 1)
+    );
+
+    m.close().await;
+    let stats = m.connection_stats().unwrap();
+    assert!(!stats.connected);
+    assert!(stats.total_connected_s >= 1);
+
+    // The next request reconnects
+    m.read_holding_registers(200, 1100, 2).await.unwrap();
+    assert_eq!(m.connection_stats().unwrap().reconnect_count, 1);
+}
+
+#[tokio::test]
+async fn third_master_reports_full_slots() {
+    let sim = Simulator::new(SimulatorConfig::default());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(simulator::serve(listener, sim));
+    let _a = tokio_modbus::client::tcp::connect(addr).await.unwrap();
+    let _b = tokio_modbus::client::tcp::connect(addr).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let mcfg = ModbusConfig {
+        ip: "127.0.0.1".to_string(),
+        port: addr.port(),
+        open_circuit_after: 1,
+        ..Default::default()
+    };
+    let mut m = ModbusConnectionManager::new(&mcfg, Duration::from_millis(10));
+    assert!(m.read_holding_registers(200, 1100, 2).await.is_err());
+    let stats = m.connection_stats().unwrap();
+    assert!(stats.slots_full && !stats.connected);
+    assert!(stats.last_error.is_some());
+    // Accepted then dropped is a failed attempt: the circuit opens instead
+    // of reconnecting on every request
+    assert_eq!(stats.state, "open_circuit");
+    assert!(stats.next_attempt_in_ms.is_some());
+}
+
+#[tokio::test]
+async fn requests_fail_fast_while_reconnecting() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let mcfg = ModbusConfig {
+        ip: "127.0.0.1".to_string(),
+        port: addr.port(),
+        ..Default::default()
+    };
+    let mut m = ModbusConnectionManager::new(&mcfg, Duration::from_millis(200));
+
+    let started = std::time::Instant::now();
+    assert!(m.read_holding_registers(200, 1100, 2).await.is_err());
+    // Backing off: no new attempt, no waiting
+    assert!(m.read_holding_registers(200, 1100, 2).await.is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let stats = m.connection_stats().unwrap();
+    assert_eq!(
+        (stats.state.as_str(), stats.connect_failures),
+        ("reconnecting", 1)
+    );
+    assert!(stats.next_attempt_in_ms.is_some());
+
+    // The station comes back; the next due attempt connects
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    tokio::spawn(simulator::serve(
+        listener,
+        Simulator::new(SimulatorConfig::default()),
+    ));
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    m.read_holding_registers(200, 1100, 2).await.unwrap();
+    assert_eq!(m.connection_stats().unwrap().state, "connected");
+}
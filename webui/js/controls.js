@@ -3,6 +3,7 @@
   const manual = document.getElementById('mode_manual');
   const auto = document.getElementById('mode_auto');
   const sched = document.getElementById('mode_sched');
+  const minPv = document.getElementById('mode_minpv');
   const chargeBtn = document.getElementById('charge_btn');
   const slider = document.getElementById('current_slider');
   const phasesToggle = document.getElementById('phases_toggle');
@@ -42,6 +43,7 @@
   if (manual) manual.addEventListener('click', () => setMode(0));
   if (auto) auto.addEventListener('click', () => setMode(1));
   if (sched) sched.addEventListener('click', () => setMode(2));
+  if (minPv) minPv.addEventListener('click', () => setMode(3));
 
   if (chargeBtn) {
     addButtonFeedback(chargeBtn);
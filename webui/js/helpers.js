@@ -12,8 +12,14 @@ window.statusNames = {
   2: 'Charging',
   3: 'Charged',
   4: 'Wait sun',
+  5: 'Wait authorization',
   6: 'Wait start',
   7: 'Low SOC',
+  8: 'Station fault',
+  10: 'Pilot error',
+  14: 'Overheating',
+  22: 'Switching to 3 phase',
+  23: 'Switching to 1 phase',
 };
 
 window.getJSON = async function (url) {
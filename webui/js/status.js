@@ -30,7 +30,7 @@ window.fetchStatus = async function () {
       slider.setAttribute('aria-valuenow', String(Math.round(val)));
     }
     setTextIfExists('di', s.device_instance ?? '');
-    const stName = statusNames[s.status] || '-';
+    const stName = s.station_operative === false ? 'Inoperative' : (statusNames[s.status] || '-');
     setTextIfExists('status_text', s.status === 2 ? `Charging ${Number(s.active_phases) === 1 ? '1P' : '3P'}` : stName);
     const phasesToggle = $('phases_toggle');
     if (phasesToggle) {
@@ -49,7 +49,9 @@ window.fetchStatus = async function () {
         powerEl.style.transition = 'all 0.3s ease';
         setTimeout(() => { powerEl.style.transform = ''; }, 300);
       }
-      powerEl.textContent = newPower >= 1000 ? (newPower / 1000).toFixed(2) : newPower;
+      // null means the station reports the value as unavailable
+      if (s.ac_power === null || s.ac_power === undefined) { powerEl.textContent = '—'; }
+      else { powerEl.textContent = newPower >= 1000 ? (newPower / 1000).toFixed(2) : newPower; }
     }
     const unitEl = $('hero_power_unit'); if (unitEl) { unitEl.textContent = p >= 1000 ? 'kW' : 'W'; }
     if ($('session_time')) {
@@ -85,7 +87,7 @@ window.fetchStatus = async function () {
       const currency = s.pricing_currency || '€';
       $('session_cost').textContent = `${currency}${Number(cost).toFixed(2)}`;
     }
-    if ($('total_energy')) { const totalEnergy = s.total_energy_kwh ?? 0; $('total_energy').textContent = Number(totalEnergy).toFixed(2); }
+    if ($('total_energy')) { const totalEnergy = s.total_energy_kwh; $('total_energy').textContent = (totalEnergy === null || totalEnergy === undefined) ? '—' : Number(totalEnergy).toFixed(2); }
     addHistoryPoint(s);
     if (!isConfigOpen && currentSchema && currentConfig) { /* no-op heavy rebuild avoided */ }
   } catch (e) {
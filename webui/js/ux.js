@@ -7,10 +7,11 @@ window.isConfigOpen = false;
 
 window.setModeUI = function (mode) {
   if (Date.now() < modeDirtyUntil) return;
-  ['mode_manual','mode_auto','mode_sched'].forEach(id => { const btn = $(id); btn.classList.remove('active'); btn.setAttribute('aria-pressed','false'); });
+  ['mode_manual','mode_auto','mode_sched','mode_minpv'].forEach(id => { const btn = $(id); btn.classList.remove('active'); btn.setAttribute('aria-pressed','false'); });
   if (mode === 0) { $('mode_manual').classList.add('active'); $('mode_manual').setAttribute('aria-pressed','true'); }
   else if (mode === 1) { $('mode_auto').classList.add('active'); $('mode_auto').setAttribute('aria-pressed','true'); }
   else if (mode === 2) { $('mode_sched').classList.add('active'); $('mode_sched').setAttribute('aria-pressed','true'); }
+  else if (mode === 3) { $('mode_minpv').classList.add('active'); $('mode_minpv').setAttribute('aria-pressed','true'); }
 };
 
 window.setChargeUI = function (enabled) {